@@ -0,0 +1,326 @@
+// editing.rs - Operacje edycyjne na liscie klipow (ciecie, luki)
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::ffmpeg::generate_black_segment;
+use crate::types::{Clip, MediaAsset, MediaType};
+
+/// Minimalna luka brana pod uwage (krotsze to bledy zaokraglen)
+const MIN_GAP: f32 = 0.01;
+
+/// Dzieli klip w czasie `t` na osi; zwraca indeks prawej czesci
+pub fn split_clip_at(clips: &mut Vec<Clip>, idx: usize, t: f32) -> Option<usize> {
+    let clip = clips.get(idx)?;
+    if t <= clip.start || t >= clip.timeline_end() {
+        return None;
+    }
+    // Czas na osi -> odegrany czas klipu (predkosc i rampa); od niego dzielone jest zrodlo
+    let span = clip.end - clip.start;
+    let offset = clip.played_at_output(t - clip.start);
+    if offset <= 0.0 || offset >= span {
+        return None;
+    }
+    // Odtwarzanie wstecz: lewa czesc gra koniec zrodla
+    let (left_source_in, right_source_in) = if clip.reversed {
+        (clip.source_in + span - offset, clip.source_in)
+    } else {
+        (clip.source_in, clip.source_in + offset)
+    };
+    // Obwiednia dzielona w punkcie ciecia (z punktem granicznym po obu stronach)
+    let (mut left_keys, mut right_keys) = (Vec::new(), Vec::new());
+    if !clip.volume_keyframes.is_empty() {
+        let boundary = clip.envelope_gain(offset);
+        left_keys.extend(clip.volume_keyframes.iter().copied().filter(|k| k.0 < offset));
+        left_keys.push((offset, boundary));
+        right_keys.push((0.0, boundary));
+        right_keys.extend(clip.volume_keyframes.iter().filter(|k| k.0 > offset).map(|&(kt, kg)| (kt - offset, kg)));
+    }
+    // Rampa predkosci: kazda czesc dostaje swoj odcinek rampy
+    let (left_ramp, right_ramp) = match clip.speed_ramp {
+        Some((s0, s1)) => {
+            let at_cut = s0 + (s1 - s0) * offset / span;
+            (Some((s0, at_cut)), Some((at_cut, s1)))
+        }
+        None => (None, None),
+    };
+    let mut right = Clip {
+        start: t,
+        end: t + span - offset,
+        fade_in: 0.0,
+        source_in: right_source_in,
+        volume_keyframes: right_keys,
+        speed_ramp: right_ramp,
+        ..clip.clone()
+    };
+    clips[idx].end = clips[idx].start + offset;
+    clips[idx].source_in = left_source_in;
+    clips[idx].fade_out = 0.0;
+    clips[idx].volume_keyframes = left_keys;
+    clips[idx].speed_ramp = left_ramp;
+    // Przejscie wyjsciowe zostaje na koncu prawej czesci
+    clips[idx].transition_out = Default::default();
+    // Niezalezne przyciecia sciezek liczone od nowego poczatku prawej czesci
+    right.shift_track_trims(clips[idx].output_duration());
+    clips.insert(idx + 1, right);
+    Some(idx + 1)
+}
+
+/// Dzieli klip na `parts` rownych czesci; zwraca indeksy wszystkich czesci (fade in zostaje
+/// na pierwszej, fade out na ostatniej - split_clip_at zeruje je w miejscach ciec)
+pub fn chop_clip(clips: &mut Vec<Clip>, idx: usize, parts: usize) -> Vec<usize> {
+    let Some(clip) = clips.get(idx) else { return Vec::new() };
+    let (start, segment_duration) = (clip.start, clip.output_duration() / parts.max(1) as f32);
+    let mut indices = vec![idx];
+    let mut last = idx;
+    for i in 1..parts {
+        match split_clip_at(clips, last, start + segment_duration * i as f32) {
+            Some(right) => {
+                indices.push(right);
+                last = right;
+            }
+            None => break,
+        }
+    }
+    indices
+}
+
+/// Zostawia tylko klipy nachodzace na [start, end], przyciete na granicach zakresu
+pub fn restrict_to_range(clips: &mut Vec<Clip>, start: f32, end: f32) {
+    clips.retain(|c| c.start < end && c.timeline_end() > start);
+    for idx in (0..clips.len()).rev() {
+        if clips[idx].timeline_end() > end {
+            trim_clip_end(clips, idx, end);
+        }
+        if clips[idx].start < start {
+            trim_clip_start(clips, idx, start);
+        }
+    }
+}
+
+/// Wstawia klip w jego czasie startu i przesuwa w prawo wszystkie klipy od tego miejsca; zwraca indeks nowego klipu
+pub fn ripple_insert(clips: &mut Vec<Clip>, new_clip: Clip) -> usize {
+    let duration = new_clip.end - new_clip.start;
+    let idx = clips.iter().position(|c| c.start >= new_clip.start - MIN_GAP).unwrap_or(clips.len());
+    for c in clips.iter_mut().filter(|c| c.start >= new_clip.start - MIN_GAP) {
+        c.start += duration;
+        c.end += duration;
+    }
+    clips.insert(idx, new_clip);
+    idx
+}
+
+/// Przycina poczatek klipu do czasu `t` (odcina lewa czesc, fade in zostaje)
+pub fn trim_clip_start(clips: &mut Vec<Clip>, idx: usize, t: f32) -> bool {
+    let fade_in = match clips.get(idx) {
+        Some(clip) => clip.fade_in,
+        None => return false,
+    };
+    if split_clip_at(clips, idx, t).is_none() {
+        return false;
+    }
+    clips.remove(idx);
+    clips[idx].fade_in = fade_in.min(clips[idx].end - clips[idx].start);
+    true
+}
+
+/// Przycina koniec klipu do czasu `t` (odcina prawa czesc, fade out i przejscie zostaja)
+pub fn trim_clip_end(clips: &mut Vec<Clip>, idx: usize, t: f32) -> bool {
+    let (fade_out, transition) = match clips.get(idx) {
+        Some(clip) => (clip.fade_out, clip.transition_out),
+        None => return false,
+    };
+    let Some(right) = split_clip_at(clips, idx, t) else { return false };
+    clips.remove(right);
+    clips[idx].fade_out = fade_out.min(clips[idx].end - clips[idx].start);
+    clips[idx].transition_out = transition;
+    true
+}
+
+/// Poczatek najblizszego klipu za `idx` na tej samej sciezce wideo
+pub fn next_clip_start(clips: &[Clip], idx: usize) -> Option<f32> {
+    let clip = clips.get(idx)?;
+    clips
+        .iter()
+        .enumerate()
+        .filter(|&(i, c)| i != idx && c.track_index == clip.track_index && c.start >= clip.timeline_end() - MIN_GAP)
+        .map(|(_, c)| c.start)
+        .min_by(f32::total_cmp)
+}
+
+/// Kopia klipu wstawiona tuz za nim (kolejne klipy przesuwaja sie w prawo); zwraca indeks kopii
+pub fn duplicate_clip(clips: &mut Vec<Clip>, idx: usize) -> Option<usize> {
+    let clip = clips.get(idx)?;
+    let length = clip.end - clip.start;
+    let start = clip.timeline_end();
+    let copy = Clip { start, end: start + length, group_id: None, ..clip.clone() };
+    Some(ripple_insert(clips, copy))
+}
+
+/// Przesuwa klip `idx` o `delta` razem ze wszystkimi klipami o wiekszych indeksach
+/// (tryb ripple: odstepy miedzy kolejnymi klipami zostaja bez zmian)
+pub fn ripple_move(clips: &mut [Clip], idx: usize, delta: f32) {
+    for clip in clips.iter_mut().skip(idx) {
+        clip.start += delta;
+        clip.end += delta;
+    }
+}
+
+/// Indeksy klipow z tej samej grupy co `idx` (sam `idx`, gdy klip nie jest zgrupowany)
+pub fn group_members(clips: &[Clip], idx: usize) -> Vec<usize> {
+    match clips.get(idx).and_then(|c| c.group_id) {
+        Some(group) => clips
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.group_id == Some(group))
+            .map(|(i, _)| i)
+            .collect(),
+        None if idx < clips.len() => vec![idx],
+        None => Vec::new(),
+    }
+}
+
+/// Czy audio klipu jest slyszalne przy odsluchu: wylaczone M (mute) sciezki, a gdy
+/// jakakolwiek sciezka ma S (solo) - slychac tylko sciezki z solo. Nie zmienia klipu.
+pub fn effective_audio_enabled(clip: &Clip, muted: &[bool], soloed: &[bool]) -> bool {
+    let track = clip.track_index as usize;
+    let flag = |flags: &[bool]| flags.get(track).copied().unwrap_or(false);
+    if !clip.audio_enabled || flag(muted) {
+        return false;
+    }
+    !soloed.contains(&true) || flag(soloed)
+}
+
+/// Pierwszy wolny identyfikator grupy
+pub fn next_group_id(clips: &[Clip]) -> u32 {
+    clips.iter().filter_map(|c| c.group_id).max().map_or(1, |id| id + 1)
+}
+
+/// Klip z glownego wejscia (bez assetu) zawierajacy czas zrodla `src`; zwraca (indeks, czas na osi)
+pub fn source_to_timeline(clips: &[Clip], src: f32) -> Option<(usize, f32)> {
+    clips
+        .iter()
+        .position(|c| c.asset_id.is_none() && src >= c.source_in && src < c.source_out())
+        .map(|idx| (idx, clips[idx].start + clips[idx].output_time_at_source(src - clips[idx].source_in)))
+}
+
+/// Usuwa z osi czasu fragment zrodla glownego wejscia (ciecie na brzegach + usuniecie srodka).
+/// Przy `ripple` klipy za usunietym fragmentem sa przesuwane w lewo. Zwraca liczbe usunietych kawalkow.
+pub fn remove_source_range(clips: &mut Vec<Clip>, src_start: f32, src_end: f32, ripple: bool) -> usize {
+    let mut removed = 0;
+    while let Some(idx) = clips.iter().position(|c| {
+        c.asset_id.is_none() && c.source_in < src_end - MIN_GAP && c.source_out() > src_start + MIN_GAP
+    }) {
+        let clip = &clips[idx];
+        // Granice fragmentu na osi (predkosc, rampa; wstecz zamienia kolejnosc)
+        let a = clip.start + clip.output_time_at_source(src_start.max(clip.source_in) - clip.source_in);
+        let b = clip.start + clip.output_time_at_source(src_end.min(clip.source_out()) - clip.source_in);
+        let (t0, t1) = (a.min(b), a.max(b));
+        split_clip_at(clips, idx, t1);
+        let mid = split_clip_at(clips, idx, t0).unwrap_or(idx);
+        clips.remove(mid);
+        if ripple {
+            for c in clips.iter_mut().filter(|c| c.start >= t1 - MIN_GAP) {
+                c.start -= t1 - t0;
+                c.end -= t1 - t0;
+            }
+        }
+        removed += 1;
+    }
+    removed
+}
+
+/// Puste przedzialy na osi czasu (od 0 do konca ostatniego klipu) jako (start, end)
+pub fn find_gaps(clips: &[Clip]) -> Vec<(f32, f32)> {
+    let mut ranges: Vec<(f32, f32)> = clips
+        .iter()
+        .filter(|c| c.video_enabled || c.audio_enabled)
+        .map(|c| (c.start, c.timeline_end()))
+        .collect();
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut gaps = Vec::new();
+    let mut covered_until = 0.0f32;
+    for (start, end) in ranges {
+        if start - covered_until > MIN_GAP {
+            gaps.push((covered_until, start));
+        }
+        covered_until = covered_until.max(end);
+    }
+    gaps
+}
+
+/// Statystyki montazu liczone z listy klipow
+pub struct ProjectStats {
+    pub clip_count: usize,
+    /// Suma dlugosci zrodlowych klipow (end - start)
+    pub cut_duration: f32,
+    pub gap_count: usize,
+    pub gap_duration: f32,
+    pub faded_clips: usize,
+    pub average_clip: f32,
+    /// Wykorzystany material jako % dlugosci projektu
+    pub source_used_percent: f32,
+}
+
+pub fn project_stats(clips: &[Clip], duration: f32) -> ProjectStats {
+    let cut_duration: f32 = clips.iter().map(|c| c.end - c.start).sum();
+    let gaps = find_gaps(clips);
+    ProjectStats {
+        clip_count: clips.len(),
+        cut_duration,
+        gap_count: gaps.len(),
+        gap_duration: gaps.iter().map(|(s, e)| e - s).sum(),
+        faded_clips: clips.iter().filter(|c| c.fade_in > 0.0 || c.fade_out > 0.0).count(),
+        average_clip: if clips.is_empty() { 0.0 } else { cut_duration / clips.len() as f32 },
+        source_used_percent: if duration > 0.0 { cut_duration / duration * 100.0 } else { 0.0 },
+    }
+}
+
+/// Wypelnia luki czarnym obrazem z cisza: dla kazdej luki generuje plik w `dir` (tworzonym w razie
+/// potrzeby), dodaje go do biblioteki i wstawia klip. Zwraca liczbe wypelnionych luk.
+pub fn fill_gaps(
+    clips: &mut Vec<Clip>,
+    assets: &mut Vec<MediaAsset>,
+    width: u32,
+    height: u32,
+    fps: f32,
+    dir: &Path,
+) -> Result<usize> {
+    // Na kopiach - przy bledzie generowania lista klipow zostaje bez zmian
+    let (mut filled_clips, mut filled_assets) = (clips.clone(), assets.clone());
+    let fillers = insert_gap_fillers(&mut filled_clips, &mut filled_assets, dir);
+    if !fillers.is_empty() {
+        std::fs::create_dir_all(dir)?;
+    }
+    for (path, duration) in &fillers {
+        generate_black_segment(*duration, width, height, fps, path)?;
+    }
+    *clips = filled_clips;
+    *assets = filled_assets;
+    Ok(fillers.len())
+}
+
+/// Wstawia w luki klipy czarnych wypelnien (`black_*.mp4` w `dir`, bez generowania plikow);
+/// zwraca (sciezka, dlugosc) plikow do wygenerowania - render i skrypt renderu
+pub fn insert_gap_fillers(clips: &mut Vec<Clip>, assets: &mut Vec<MediaAsset>, dir: &Path) -> Vec<(PathBuf, f32)> {
+    let gaps = find_gaps(clips);
+    let mut fillers = Vec::new();
+    for (i, &(start, end)) in gaps.iter().enumerate() {
+        let duration = end - start;
+        let path = dir.join(format!("black_{}_{i:03}.mp4", assets.len()));
+
+        let asset_id = assets.len();
+        assets.push(MediaAsset {
+            id: asset_id,
+            path: path.to_string_lossy().into_owned(),
+            name: format!("Black {duration:.2}s"),
+            kind: MediaType::Video,
+            duration,
+        });
+        // Render sklada klipy w kolejnosci listy - wstawiamy w miejscu luki
+        let pos = clips.iter().position(|c| c.start >= end).unwrap_or(clips.len());
+        clips.insert(pos, Clip::new(Some(asset_id), start, end));
+        fillers.push((path, duration));
+    }
+    fillers
+}
@@ -0,0 +1,419 @@
+// export.rs - Eksport skryptu renderu (bash / bat) i archiwum projektu (ZIP)
+use anyhow::{anyhow, Result};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::editing::{insert_gap_fillers, restrict_to_range};
+use crate::ffmpeg::{
+    build_audio_crossfade_args, build_black_segment_args, build_composite_args, build_concat_args, build_copy_segment_args,
+    build_segment_args, build_track_extract_args, build_two_pass_args, build_watermark_args, build_xfade_args, ffmpeg_binary,
+    filler_format, null_device, plan_audio_crossfades, plan_stream_copy, plan_transitions, separate_track_paths,
+    timeline_to_output,
+};
+use crate::types::{Clip, Marker, MediaAsset, ProjectData, RenderSettings, TrackType};
+
+/// Cytowanie argumentu dla basha ('...' z escapowanym apostrofem)
+fn quote_sh(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Cytowanie argumentu dla cmd.exe ("..." z podwojonym cudzyslowem, % jako %%)
+fn quote_bat(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('"', "\"\"").replace('%', "%%"))
+}
+
+/// Znacznik czasu WebVTT: HH:MM:SS.mmm (kropka przed milisekundami)
+fn vtt_timestamp(secs: f32) -> String {
+    let total_ms = (secs.max(0.0) as f64 * 1000.0).round() as u64;
+    let (h, m, s, ms) = (total_ms / 3_600_000, (total_ms / 60_000) % 60, (total_ms / 1000) % 60, total_ms % 1000);
+    format!("{h:02}:{m:02}:{s:02}.{ms:03}")
+}
+
+/// Zapisuje rozdzialy jako plik WebVTT (`<track kind="chapters">`): kazdy znacznik otwiera
+/// rozdzial trwajacy do nastepnego znacznika, ostatni do `duration`
+pub fn export_webvtt(markers: &[Marker], duration: f32, path: &Path) -> Result<()> {
+    let mut sorted: Vec<&Marker> = markers.iter().filter(|m| m.time < duration).collect();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+    let mut vtt = String::from("WEBVTT\n\nNOTE chapters\n");
+    for (i, marker) in sorted.iter().enumerate() {
+        let end = sorted.get(i + 1).map_or(duration, |next| next.time);
+        if end - marker.time < 0.001 {
+            continue;
+        }
+        let title = if marker.title.trim().is_empty() { format!("Chapter {}", i + 1) } else { marker.title.trim().to_string() };
+        write!(vtt, "\n{}\n{} --> {}\n{}\n", i + 1, vtt_timestamp(marker.time), vtt_timestamp(end), title)?;
+    }
+    std::fs::write(path, vtt).map_err(|e| anyhow!("Blad zapisu {}: {e}", path.display()))
+}
+
+/// Rozdzialy obok pliku wyjsciowego ({output}.vtt) z czasami znacznikow przeliczonymi na gotowy film
+pub fn write_chapters_for_output(markers: &[Marker], clips: &[Clip], output_path: &str) -> Result<()> {
+    if markers.is_empty() {
+        return Ok(());
+    }
+    let base_clips: Vec<Clip> = clips.iter().filter(|c| c.track_index == 0).cloned().collect();
+    let duration = base_clips
+        .iter()
+        .filter(|c| c.video_enabled || c.audio_enabled)
+        .map(|c| timeline_to_output(&base_clips, c.start) + c.output_duration())
+        .fold(0.0, f32::max);
+    let retimed: Vec<Marker> = markers
+        .iter()
+        .map(|m| Marker { time: timeline_to_output(&base_clips, m.time), title: m.title.clone() })
+        .collect();
+    export_webvtt(&retimed, duration, &Path::new(output_path).with_extension("vtt"))
+}
+
+/// Linia skryptu z komenda ffmpeg; w .bat przerwanie po bledzie (bash ma `set -e`)
+fn write_ffmpeg_line(script: &mut String, ffmpeg: &str, args: &[String], quote: fn(&str) -> String, windows: bool) -> Result<()> {
+    let line: Vec<String> = args.iter().map(|a| quote(a)).collect();
+    writeln!(script, "{ffmpeg} {}", line.join(" "))?;
+    if windows {
+        writeln!(script, "if errorlevel 1 exit /b 1")?;
+    }
+    Ok(())
+}
+
+/// Wspolne dane przebiegow skryptu renderu: cytowanie, katalog segmentow i zrodla
+struct ScriptRender<'a> {
+    ffmpeg: String,
+    quote: fn(&str) -> String,
+    windows: bool,
+    seg_dir: String,
+    sep: char,
+    input_path: &'a str,
+    assets: &'a [MediaAsset],
+    clips: &'a [Clip],
+}
+
+impl ScriptRender<'_> {
+    /// Plik w katalogu segmentow
+    fn path(&self, name: &str) -> String {
+        format!("{}{}{name}", self.seg_dir, self.sep)
+    }
+
+    fn ffmpeg_line(&self, script: &mut String, args: &[String]) -> Result<()> {
+        write_ffmpeg_line(script, &self.ffmpeg, args, self.quote, self.windows)
+    }
+}
+
+/// Zapisuje skrypt wykonujacy dokladnie te same komendy ffmpeg co render_deliverables
+/// (zakres, wypelnienie luk, segmenty, concat, nakladki, znak wodny, wybor sciezek),
+/// bez ich uruchamiania. Rozszerzenie `.bat` = skrypt Windows.
+pub fn export_render_script(
+    project_path: &Path,
+    settings: &RenderSettings,
+    clips: &[Clip],
+    assets: &[MediaAsset],
+    input_path: &str,
+    render_output: &str,
+    output: &Path,
+) -> Result<()> {
+    // Jak render_video: render zakresu mark in / mark out
+    let mut clips = clips.to_vec();
+    if let Some((start, end)) = settings.active_range() {
+        restrict_to_range(&mut clips, start, end);
+    }
+    if clips.is_empty() {
+        return Err(anyhow!("Brak klipow do renderowania"));
+    }
+    let windows = output.extension().is_some_and(|e| e.eq_ignore_ascii_case("bat"));
+    let quote = if windows { quote_bat } else { quote_sh };
+    // Segmenty w katalogu obok pliku wyjsciowego (sciezki jawne, bez zmiennych)
+    let ctx = ScriptRender {
+        ffmpeg: quote(&ffmpeg_binary()),
+        quote,
+        windows,
+        seg_dir: format!("{render_output}.segments"),
+        sep: if windows { '\\' } else { '/' },
+        input_path,
+        assets,
+        clips: &clips,
+    };
+
+    let mut script = String::new();
+    if windows {
+        writeln!(script, "@echo off")?;
+        writeln!(script, "rem RustyCut render script - {}", project_path.display())?;
+        writeln!(script, "if not exist {q} mkdir {q}", q = quote(&ctx.seg_dir))?;
+    } else {
+        writeln!(script, "#!/usr/bin/env bash")?;
+        writeln!(script, "# RustyCut render script - {}", project_path.display())?;
+        writeln!(script, "set -e")?;
+        writeln!(script, "mkdir -p {}", quote(&ctx.seg_dir))?;
+    }
+
+    // Osobne pliki sciezek to dwa pelne rendery, jak w render_deliverables
+    if settings.separate_track_files {
+        let (video_path, audio_path) = separate_track_paths(render_output);
+        let video = RenderSettings { export_tracks: TrackType::Video, ..settings.clone() };
+        write_render_commands(&mut script, &ctx, &video, &video_path)?;
+        let audio = RenderSettings { export_tracks: TrackType::Audio, ..settings.clone() };
+        write_render_commands(&mut script, &ctx, &audio, &audio_path)?;
+    } else {
+        write_render_commands(&mut script, &ctx, settings, render_output)?;
+    }
+    if windows {
+        writeln!(script, "rmdir /s /q {}", quote(&ctx.seg_dir))?;
+    } else {
+        writeln!(script, "rm -rf {}", quote(&ctx.seg_dir))?;
+    }
+
+    std::fs::write(output, script).map_err(|e| anyhow!("Blad zapisu skryptu {}: {e}", output.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(output, std::fs::Permissions::from_mode(0o755));
+    }
+    Ok(())
+}
+
+/// Komendy jednego przebiegu render_video do `target_path`
+fn write_render_commands(script: &mut String, ctx: &ScriptRender, settings: &RenderSettings, target_path: &str) -> Result<()> {
+    let quote = ctx.quote;
+    let tracks = settings.export_tracks;
+    // Jak w render_video: V1 przez concat, wyzsze sciezki jako overlay
+    let overlay_clips: Vec<&Clip> = ctx.clips.iter().filter(|c| c.track_index > 0 && c.video_enabled).collect();
+    let mut base_clips: Vec<Clip> = ctx.clips.iter().filter(|c| c.track_index == 0).cloned().collect();
+    if base_clips.is_empty() {
+        return Err(anyhow!("Brak klipow na sciezce V1"));
+    }
+    let watermark = settings.watermark.as_ref().filter(|w| !w.path.as_os_str().is_empty());
+    // Format wypelnien i wymiary dla znaku wodnego (render czyta je z gotowego pliku)
+    let format = (settings.auto_fill_gaps || watermark.is_some())
+        .then(|| filler_format(&base_clips, ctx.input_path, ctx.assets, settings));
+    let mut filled_assets = ctx.assets.to_vec();
+    if settings.auto_fill_gaps
+        && let Some((width, height, fps)) = format
+    {
+        for (path, duration) in insert_gap_fillers(&mut base_clips, &mut filled_assets, Path::new(&ctx.seg_dir)) {
+            ctx.ffmpeg_line(script, &build_black_segment_args(duration, width, height, fps, &path.to_string_lossy()))?;
+        }
+    }
+    let assets = filled_assets.as_slice();
+
+    // Sam obraz / sam dzwiek: pelny film w katalogu segmentow, na koncu kopia wybranej sciezki
+    let full_path = ctx.path("full.mp4");
+    let composed_path = if tracks == TrackType::Both { target_path } else { full_path.as_str() };
+    let unmarked_path = ctx.path("unmarked.mp4");
+    let render_output = if watermark.is_some() { unmarked_path.as_str() } else { composed_path };
+
+    let mut segments = Vec::new();
+    let mut output_offset = 0.0f32;
+    let copy_sources = plan_stream_copy(&base_clips, ctx.input_path, assets, settings);
+    for (i, clip) in base_clips.iter().enumerate() {
+        if !clip.video_enabled && !clip.audio_enabled {
+            continue;
+        }
+        let seg_path = ctx.path(&format!("seg_{i:04}.mp4"));
+        let copy_source = copy_sources[i];
+        let passes = match copy_source {
+            Some(source) => vec![build_copy_segment_args(clip, source, &seg_path)],
+            None => {
+                let args = build_segment_args(clip, ctx.input_path, assets, settings, output_offset, &seg_path);
+                if settings.two_pass {
+                    let passlog = ctx.path(&format!("pass_{i:04}"));
+                    build_two_pass_args(&args, &passlog, null_device(ctx.windows)).to_vec()
+                } else {
+                    vec![args]
+                }
+            }
+        };
+        output_offset += clip.output_duration();
+        for args in passes {
+            ctx.ffmpeg_line(script, &args)?;
+        }
+        segments.push(seg_path);
+    }
+    if segments.is_empty() {
+        return Err(anyhow!("Brak segmentow do polaczenia"));
+    }
+
+    // Lista concat w formacie ffmpeg: file '...'
+    let concat_list = ctx.path("concat.txt");
+    if ctx.windows {
+        writeln!(script, "(")?;
+        for seg in &segments {
+            writeln!(script, "echo file '{}'", seg.replace('%', "%%"))?;
+        }
+        writeln!(script, ") > {}", quote(&concat_list))?;
+    } else {
+        writeln!(script, "cat > {} <<'EOF'", quote(&concat_list))?;
+        for seg in &segments {
+            writeln!(script, "file '{}'", seg.replace('\'', "'\\''"))?;
+        }
+        writeln!(script, "EOF")?;
+    }
+
+    let crossfades = plan_audio_crossfades(&base_clips, ctx.input_path, assets);
+    let transitions = plan_transitions(&base_clips);
+    let base_path = ctx.path("base.mp4");
+    let concat_output = if overlay_clips.is_empty() && crossfades.is_empty() { render_output } else { base_path.as_str() };
+    let concat_args = if transitions.iter().any(Option::is_some) {
+        let durations: Vec<f32> = base_clips
+            .iter()
+            .filter(|c| c.video_enabled || c.audio_enabled)
+            .map(|c| c.output_duration())
+            .collect();
+        build_xfade_args(&segments, &durations, &transitions, None, settings, concat_output)
+    } else {
+        build_concat_args(&concat_list, concat_output)
+    };
+    ctx.ffmpeg_line(script, &concat_args)?;
+    let mixed_path = ctx.path("mixed.mp4");
+    let base_path = if crossfades.is_empty() {
+        base_path
+    } else {
+        let mixed = if overlay_clips.is_empty() { render_output } else { mixed_path.as_str() };
+        let mix = build_audio_crossfade_args(concat_output, &crossfades, settings.audio_bitrate_kbps, mixed);
+        ctx.ffmpeg_line(script, &mix)?;
+        mixed_path.clone()
+    };
+    if !overlay_clips.is_empty() {
+        let mut overlay_paths = Vec::new();
+        let mut windows_out = Vec::new();
+        for (j, clip) in overlay_clips.iter().enumerate() {
+            let start = timeline_to_output(&base_clips, clip.start);
+            let seg_path = ctx.path(&format!("ovl_{j:04}.mp4"));
+            let args = build_segment_args(clip, ctx.input_path, assets, settings, start, &seg_path);
+            let passes = if settings.two_pass {
+                let passlog = ctx.path(&format!("ovl_pass_{j:04}"));
+                build_two_pass_args(&args, &passlog, null_device(ctx.windows)).to_vec()
+            } else {
+                vec![args]
+            };
+            for args in passes {
+                ctx.ffmpeg_line(script, &args)?;
+            }
+            overlay_paths.push(seg_path);
+            windows_out.push((start, start + clip.output_duration()));
+        }
+        let composite = build_composite_args(&base_path, &overlay_paths, &windows_out, &overlay_clips, settings, render_output);
+        ctx.ffmpeg_line(script, &composite)?;
+    }
+    if let Some(watermark) = watermark
+        && let Some((width, height, _)) = format
+    {
+        ctx.ffmpeg_line(script, &build_watermark_args(render_output, watermark, width, height, settings, composed_path))?;
+    }
+    if tracks != TrackType::Both {
+        ctx.ffmpeg_line(script, &build_track_extract_args(composed_path, tracks, target_path))?;
+    }
+    Ok(())
+}
+
+/// Postep archiwizacji: (bajty zapisane, bajty planowane)
+pub type ArchiveProgress = Arc<Mutex<(u64, u64)>>;
+
+/// Dopisuje plik do archiwum strumieniowo, aktualizujac postep co blok
+fn zip_add_file(
+    zip: &mut ZipWriter<BufWriter<File>>,
+    name: &str,
+    source: &Path,
+    options: SimpleFileOptions,
+    progress: Option<&ArchiveProgress>,
+) -> Result<()> {
+    let mut input = File::open(source).map_err(|e| anyhow!("Blad odczytu {}: {e}", source.display()))?;
+    zip.start_file(name, options)?;
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = input.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        zip.write_all(&buf[..n])?;
+        if let Some(progress) = progress
+            && let Ok(mut p) = progress.lock()
+        {
+            p.0 += n as u64;
+        }
+    }
+    Ok(())
+}
+
+/// Pakuje projekt do jednego pliku ZIP: `project.rev` + wszystkie pliki zrodlowe w `media/`.
+/// Sciezki w projekcie sa przepisywane na wzgledne wzgledem katalogu archiwum.
+pub fn archive_project(
+    project_data: &ProjectData,
+    library: &[MediaAsset],
+    archive_path: &Path,
+    progress: Option<&ArchiveProgress>,
+) -> Result<()> {
+    let mut data = project_data.clone();
+    data.media_library = library.to_vec();
+
+    // Lista unikalnych plikow: sciezka zrodlowa -> nazwa w archiwum
+    let mut sources: Vec<String> = Vec::new();
+    if !data.input_path.is_empty() {
+        sources.push(data.input_path.clone());
+    }
+    let overrides = data.clips.iter().filter_map(|c| c.audio_override.clone()).filter(|p| !p.trim().is_empty());
+    let watermark = data.watermark.as_ref().map(|w| w.path.to_string_lossy().into_owned()).filter(|p| !p.is_empty());
+    for path in data.media_library.iter().map(|a| a.path.clone()).chain(overrides).chain(watermark) {
+        if !sources.contains(&path) {
+            sources.push(path);
+        }
+    }
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut total: u64 = 0;
+    for (i, source) in sources.iter().enumerate() {
+        let meta = std::fs::metadata(source).map_err(|e| anyhow!("Brak pliku zrodlowego {source}: {e}"))?;
+        total += meta.len();
+        let file_name = Path::new(source)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("media_{i}"));
+        files.push((source.clone(), format!("media/{i}_{file_name}")));
+    }
+    if let Some(progress) = progress
+        && let Ok(mut p) = progress.lock()
+    {
+        *p = (0, total);
+    }
+
+    let relative = |path: &str| files.iter().find(|(src, _)| src == path).map(|(_, name)| name.clone());
+    if let Some(name) = relative(&data.input_path) {
+        data.input_path = name;
+    }
+    for asset in &mut data.media_library {
+        if let Some(name) = relative(&asset.path) {
+            asset.path = name;
+        }
+    }
+    for path in data.clips.iter_mut().filter_map(|c| c.audio_override.as_mut()) {
+        if let Some(name) = relative(path) {
+            *path = name;
+        }
+    }
+    if let Some(watermark) = &mut data.watermark
+        && let Some(name) = relative(&watermark.path.to_string_lossy())
+    {
+        watermark.path = name.into();
+    }
+
+    let json = serde_json::to_string_pretty(&data).map_err(|e| anyhow!("Blad serializacji: {e}"))?;
+    // Bez kompresji (media sa juz skompresowane); ZIP64 dla plikow i archiwow ponad 4 GB
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored).large_file(true);
+    let write = || -> Result<()> {
+        let file = File::create(archive_path).map_err(|e| anyhow!("Blad tworzenia archiwum {}: {e}", archive_path.display()))?;
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+        zip.start_file("project.rev", options)?;
+        zip.write_all(json.as_bytes())?;
+        for (source, name) in &files {
+            zip_add_file(&mut zip, name, Path::new(source), options, progress)?;
+        }
+        zip.finish()?.flush()?;
+        Ok(())
+    };
+    let result = write();
+    if result.is_err() {
+        // Niedokonczone archiwum jest bezuzyteczne
+        let _ = std::fs::remove_file(archive_path);
+    }
+    result
+}
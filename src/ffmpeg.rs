@@ -1,15 +1,82 @@
 // ffmpeg.rs - Wszystkie operacje FFmpeg
 use anyhow::{anyhow, Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::fs;
+use crate::editing::{fill_gaps, restrict_to_range};
+use crate::subtitle::{retime_subtitles, write_srt, SubtitleEntry};
 
-use crate::types::{Clip, MediaAsset, MediaType};
+use crate::types::{
+    AspectRatioMode, Clip, ClipEffect, MediaAsset, MediaInfo, MediaType, OutputCodec, OutputTarget, RenderProgress, RenderSettings,
+    StretchAlgorithm, TimecodePosition, TrackType, Transition, WatermarkPosition, WatermarkSettings, WaveformAmplitude,
+};
+
+/// Ścieżki ffmpeg / ffprobe z ustawień (puste = szukane w PATH)
+static FFMPEG_PATH: RwLock<String> = RwLock::new(String::new());
+static FFPROBE_PATH: RwLock<String> = RwLock::new(String::new());
+
+/// Ustawia ścieżki narzędzi używane przez wszystkie wywołania w tym module
+pub fn set_tool_paths(ffmpeg: &str, ffprobe: &str) {
+    if let Ok(mut path) = FFMPEG_PATH.write() {
+        *path = ffmpeg.trim().to_string();
+    }
+    if let Ok(mut path) = FFPROBE_PATH.write() {
+        *path = ffprobe.trim().to_string();
+    }
+}
+
+/// Skonfigurowana sciezka ffmpeg (Settings), "ffmpeg" z PATH gdy pusta - np. dla skryptu renderu
+pub fn ffmpeg_binary() -> String {
+    match FFMPEG_PATH.read() {
+        Ok(path) if !path.is_empty() => path.clone(),
+        _ => "ffmpeg".to_string(),
+    }
+}
+
+fn tool_command(path: &RwLock<String>, default: &str) -> Command {
+    match path.read() {
+        Ok(path) if !path.is_empty() => Command::new(path.as_str()),
+        _ => Command::new(default),
+    }
+}
+
+/// Nowy proces ffmpeg (ścieżka z ustawień)
+pub fn ffmpeg_command() -> Command {
+    tool_command(&FFMPEG_PATH, "ffmpeg")
+}
+
+/// Nowy proces ffprobe (ścieżka z ustawień)
+pub fn ffprobe_command() -> Command {
+    tool_command(&FFPROBE_PATH, "ffprobe")
+}
+
+/// Pierwsza linia `-version` narzędzia (np. "ffmpeg version 6.1.1 ...")
+fn tool_version(mut command: Command) -> Result<String> {
+    let output = command.arg("-version").output().context("Nie mozna uruchomic narzedzia (sprawdz PATH)")?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .ok_or_else(|| anyhow!("Brak informacji o wersji"))
+}
+
+/// Wersja ffmpeg (okno "O programie")
+pub fn check_ffmpeg_version() -> Result<String> {
+    tool_version(ffmpeg_command())
+}
+
+/// Wersja ffprobe (okno "O programie")
+pub fn check_ffprobe_version() -> Result<String> {
+    tool_version(ffprobe_command())
+}
 
 /// Uruchamia FFmpeg z podanymi argumentami
 pub fn run_ffmpeg(args: &[&str]) -> Result<()> {
-    let output = Command::new("ffmpeg")
+    let output = ffmpeg_command()
         .args(args)
         .output()
         .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
@@ -22,16 +89,70 @@ pub fn run_ffmpeg(args: &[&str]) -> Result<()> {
     Ok(())
 }
 
+/// Uruchamia FFmpeg z `-progress pipe:2` i przekazuje (frame, fps, speed) po każdym bloku postępu
+pub fn run_ffmpeg_with_progress(args: &[&str], mut on_progress: impl FnMut(u64, f32, f32)) -> Result<()> {
+    let mut child = ffmpeg_command()
+        .args(["-progress", "pipe:2", "-nostats"])
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+
+    let (mut frame, mut fps, mut speed) = (0u64, 0.0f32, 0.0f32);
+    let mut errors: Vec<String> = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+            match line.split_once('=') {
+                Some(("frame", v)) => frame = v.trim().parse().unwrap_or(frame),
+                Some(("fps", v)) => fps = v.trim().parse().unwrap_or(fps),
+                Some(("speed", v)) => speed = v.trim().trim_end_matches('x').parse().unwrap_or(speed),
+                Some(("progress", _)) => on_progress(frame, fps, speed),
+                Some((key, _)) if !key.contains(' ') => {}
+                _ => {
+                    // Zwykle komunikaty ffmpeg - zachowujemy ostatnie na wypadek bledu
+                    errors.push(line);
+                    if errors.len() > 20 {
+                        errors.remove(0);
+                    }
+                }
+            }
+        }
+    }
+
+    let status = child.wait().context("ffmpeg nie zakonczyl sie poprawnie")?;
+    if !status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", errors.join("\n")));
+    }
+    Ok(())
+}
+
 /// Generuje pojedynczą ramkę z wideo (z hardware acceleration)
 pub fn generate_frame_memory(input: &str, time: f32, width: u32, height: i32) -> Result<Vec<u8>> {
+    generate_frame_memory_filtered(input, time, width, height, None)
+}
+
+/// Uruchamia ffmpeg wypisujacy jedna klatke PNG na stdout (bez czekania na wynik).
+/// Zwrocony proces mozna zabic, gdy podglad jest juz nieaktualny.
+pub fn spawn_frame_process(
+    input: &str,
+    time: f32,
+    width: u32,
+    height: i32,
+    filter: Option<&str>,
+) -> Result<Child> {
     let width_str = if width == 0 { "-1".to_string() } else { width.to_string() };
     let height_str = if height == 0 { "-1".to_string() } else { height.to_string() };
     let time_str = format!("{:.3}", time.max(0.0));
-    let scale_str = format!("scale={width_str}:{height_str}");
+    let scale_str = match filter {
+        Some(chain) => format!("{chain},scale={width_str}:{height_str}"),
+        None => format!("scale={width_str}:{height_str}"),
+    };
 
-    let output = Command::new("ffmpeg")
+    ffmpeg_command()
         .args([
             "-y",
+            "-loglevel", "error",
             "-hwaccel", "auto",  // GPU acceleration
             "-ss", &time_str,
             "-i", input,
@@ -41,8 +162,24 @@ pub fn generate_frame_memory(input: &str, time: f32, width: u32, height: i32) ->
             "-vcodec", "png",
             "-",
         ])
-        .output()
-        .context("Nie mozna uruchomic ffmpeg dla frame memory")?;
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Nie mozna uruchomic ffmpeg dla frame memory")
+}
+
+/// Generuje ramkę z dodatkowymi filtrami klipu (np. odszumianie) przed skalowaniem
+pub fn generate_frame_memory_filtered(
+    input: &str,
+    time: f32,
+    width: u32,
+    height: i32,
+    filter: Option<&str>,
+) -> Result<Vec<u8>> {
+    let output = spawn_frame_process(input, time, width, height, filter)?
+        .wait_with_output()
+        .context("Blad oczekiwania na ffmpeg frame memory")?;
 
     if !output.status.success() {
         return Err(anyhow!("ffmpeg frame error: {}", String::from_utf8_lossy(&output.stderr)));
@@ -50,13 +187,294 @@ pub fn generate_frame_memory(input: &str, time: f32, width: u32, height: i32) ->
     Ok(output.stdout)
 }
 
-/// Pobiera informacje o wideo przez ffprobe
-pub fn get_video_info_ffprobe(path: &str) -> Result<(f32, u32, u32, f32)> {
-    let output = Command::new("ffprobe")
+/// Parametry probek zwracanych przez `scrub_audio` (stereo, 44.1 kHz)
+pub const SCRUB_SAMPLE_RATE: u32 = 44100;
+pub const SCRUB_CHANNELS: u16 = 2;
+
+/// Dekoduje krotki fragment audio (`duration` s) wysrodkowany na `time` jako probki s16le
+/// (stereo, 44.1 kHz) - do "drapania" dzwieku podczas przeciagania playheada
+pub fn scrub_audio(input: &str, time: f32, duration: f32) -> Result<Vec<i16>> {
+    let start_str = format!("{:.3}", (time - duration / 2.0).max(0.0));
+    let duration_str = format!("{:.3}", duration.max(0.0));
+    let output = ffmpeg_command()
+        .args([
+            "-hide_banner",
+            "-loglevel", "error",
+            "-ss", &start_str,
+            "-i", input,
+            "-t", &duration_str,
+            "-vn",
+            "-f", "s16le",
+            "-ac", "2",
+            "-ar", "44100",
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .context("Nie mozna uruchomic ffmpeg dla scrub audio")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg scrub error: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(output
+        .stdout
+        .chunks_exact(2)
+        .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect())
+}
+
+/// Czestotliwosc probkowania PCM do wykrywania rytmu (wystarcza dla obwiedni energii)
+pub const BEAT_SAMPLE_RATE: u32 = 11025;
+
+/// Dekoduje fragment audio [start, start + duration) jako probki mono f32le
+pub fn decode_mono_pcm(input: &str, start: f32, duration: f32, sample_rate: u32) -> Result<Vec<f32>> {
+    let start_str = format!("{:.3}", start.max(0.0));
+    let duration_str = format!("{:.3}", duration.max(0.0));
+    let rate_str = sample_rate.to_string();
+    let output = ffmpeg_command()
+        .args([
+            "-hide_banner",
+            "-loglevel", "error",
+            "-ss", &start_str,
+            "-i", input,
+            "-t", &duration_str,
+            "-vn",
+            "-f", "f32le",
+            "-ac", "1",
+            "-ar", &rate_str,
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(output
+        .stdout
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+/// Czy ffmpeg ma dany filtr (np. "rubberband" - wymaga builda z librubberband)
+pub fn ffmpeg_has_filter(name: &str) -> bool {
+    ffmpeg_command()
+        .args(["-hide_banner", "-filters"])
+        .output()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .any(|line| line.split_whitespace().nth(1) == Some(name))
+        })
+        .unwrap_or(false)
+}
+
+/// Wykrywa fragmenty ciszy filtrem silencedetect; zwraca przedzialy (start, end) w sekundach
+pub fn detect_silence(input: &str, noise_threshold_db: f32, min_silence_dur: f32) -> Result<Vec<(f32, f32)>> {
+    let filter = format!("silencedetect=noise={noise_threshold_db:.1}dB:d={min_silence_dur:.2}");
+    let output = ffmpeg_command()
+        .args(["-hide_banner", "-nostats", "-i", input, "-vn", "-af", &filter, "-f", "null", "-"])
+        .output()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(parse_silencedetect(&String::from_utf8_lossy(&output.stderr), None))
+}
+
+/// Przycina cisze z poczatku i konca fragmentu zrodla `[source_in, source_out]`;
+/// zwraca nowe (source_in, source_out) obejmujace pierwszy i ostatni fragment z dzwiekiem
+pub fn auto_trim_silence(input: &str, source_in: f32, source_out: f32, threshold_db: f32) -> Result<(f32, f32)> {
+    let length = source_out - source_in;
+    if length <= 0.0 {
+        return Err(anyhow!("Pusty zakres klipu"));
+    }
+    let filter = format!("silencedetect=noise={threshold_db:.1}dB:d=0.05");
+    let (start, duration) = (format!("{source_in:.3}"), format!("{length:.3}"));
+    let output = ffmpeg_command()
+        .args(["-hide_banner", "-nostats", "-ss", &start, "-t", &duration, "-i", input, "-vn", "-af", &filter, "-f", "null", "-"])
+        .output()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    // Czasy wzgledem `-ss`; cisza trwajaca do konca nie ma silence_end
+    let ranges = parse_silencedetect(&String::from_utf8_lossy(&output.stderr), Some(length));
+    let edge = 0.01;
+    let mut sound_start = 0.0;
+    let mut sound_end = length;
+    if let Some(&(start, end)) = ranges.first()
+        && start <= edge
+    {
+        sound_start = end;
+    }
+    if let Some(&(start, end)) = ranges.last()
+        && end >= length - edge
+    {
+        sound_end = start;
+    }
+    if sound_end - sound_start < 0.04 {
+        return Err(anyhow!("Klip zawiera tylko cisze"));
+    }
+    Ok((source_in + sound_start, source_in + sound_end))
+}
+
+/// Parsuje linie "silence_start: X" / "silence_end: Y | silence_duration: Z" z stderr.
+/// `open_end` zamyka cisze trwajaca do konca strumienia (bez silence_end).
+fn parse_silencedetect(stderr: &str, open_end: Option<f32>) -> Vec<(f32, f32)> {
+    let value_after = |line: &str, key: &str| -> Option<f32> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+    let mut ranges = Vec::new();
+    let mut pending_start = None;
+    for line in stderr.lines() {
+        if let Some(start) = value_after(line, "silence_start:") {
+            pending_start = Some(start.max(0.0));
+        } else if let Some(end) = value_after(line, "silence_end:")
+            && let Some(start) = pending_start.take()
+        {
+            ranges.push((start, end));
+        }
+    }
+    if let (Some(start), Some(end)) = (pending_start, open_end)
+        && start < end
+    {
+        ranges.push((start, end));
+    }
+    ranges
+}
+
+/// Parametry strumieni, ktore musza byc identyczne w segmentach laczonych przez concat -c copy
+#[derive(Clone, PartialEq)]
+pub struct CopyFormat {
+    video_codec: String,
+    profile: String,
+    pix_fmt: String,
+    width: u64,
+    height: u64,
+    fps: String,
+    /// (kodek, czestotliwosc, kanaly); None = zrodlo bez audio
+    audio: Option<(String, u64, u64)>,
+}
+
+/// Kodeki, ktore kontener mp4 przyjmuje bez rekompresji
+const COPY_VIDEO_CODECS: [&str; 2] = ["h264", "hevc"];
+const COPY_AUDIO_CODECS: [&str; 2] = ["aac", "mp3"];
+
+/// Format strumieni `input`, jesli odcinek [start, end] da sie skopiowac bez kodowania
+/// (kodeki zgodne z mp4, ciecie na klatce kluczowej)
+pub fn stream_copy_format(input: &str, start: f32, end: f32) -> Option<CopyFormat> {
+    if end <= start {
+        return None;
+    }
+    let output = ffprobe_command()
+        .args([
+            "-v", "error",
+            "-show_entries", "stream=codec_type,codec_name,profile,pix_fmt,width,height,r_frame_rate,sample_rate,channels",
+            "-of", "json",
+            input,
+        ])
+        .output()
+        .ok()?;
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let streams = value.get("streams")?.as_array()?;
+    let stream = |kind: &str| streams.iter().find(|s| s.get("codec_type").and_then(|t| t.as_str()) == Some(kind));
+    let text = |v: &serde_json::Value, key: &str| v.get(key).and_then(|t| t.as_str()).unwrap_or_default().to_string();
+    // sample_rate jest tekstem, width/height/channels liczbami
+    let number = |v: &serde_json::Value, key: &str| -> u64 {
+        v.get(key).and_then(|n| n.as_u64().or_else(|| n.as_str()?.parse().ok())).unwrap_or(0)
+    };
+
+    let video = stream("video")?;
+    let format = CopyFormat {
+        video_codec: text(video, "codec_name"),
+        profile: text(video, "profile"),
+        pix_fmt: text(video, "pix_fmt"),
+        width: number(video, "width"),
+        height: number(video, "height"),
+        fps: text(video, "r_frame_rate"),
+        audio: stream("audio").map(|a| (text(a, "codec_name"), number(a, "sample_rate"), number(a, "channels"))),
+    };
+    if !COPY_VIDEO_CODECS.contains(&format.video_codec.as_str()) || format.pix_fmt != "yuv420p" {
+        return None;
+    }
+    if let Some((codec, _, _)) = &format.audio
+        && !COPY_AUDIO_CODECS.contains(&codec.as_str())
+    {
+        return None;
+    }
+    keyframe_at(input, start).then_some(format)
+}
+
+/// Czy plik ma strumien audio
+fn has_audio_stream(input: &str) -> bool {
+    ffprobe_command()
+        .args(["-v", "error", "-select_streams", "a", "-show_entries", "stream=index", "-of", "csv=p=0", input])
+        .output()
+        .is_ok_and(|output| !String::from_utf8_lossy(&output.stdout).trim().is_empty())
+}
+
+/// Czy w okolicy `time` (do ~1 klatki) jest klatka kluczowa strumienia wideo
+fn keyframe_at(input: &str, time: f32) -> bool {
+    let interval = format!("{:.3}%+1", (time - 0.5).max(0.0));
+    let Ok(output) = ffprobe_command()
         .args([
             "-v", "error",
             "-select_streams", "v:0",
-            "-show_entries", "stream=width,height,duration,r_frame_rate",
+            "-skip_frame", "nokey",
+            "-read_intervals", &interval,
+            "-show_entries", "frame=best_effort_timestamp_time",
+            "-of", "csv=p=0",
+            input,
+        ])
+        .output()
+    else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.trim().trim_end_matches(',').parse::<f32>().ok())
+        .any(|t| (t - time).abs() < 0.04)
+}
+
+/// Srednia korelacja fazowa L/R (-1.0 = przeciwfaza, 1.0 = zgodnosc) fragmentu audio
+/// z filtra aphasemeter; mono daje 1.0
+pub fn measure_phase_correlation(input: &str, start: f32, duration: f32) -> Result<f32> {
+    let output = ffmpeg_command()
+        .args([
+            "-hide_banner", "-nostats",
+            "-ss", &format!("{:.3}", start.max(0.0)),
+            "-t", &format!("{:.3}", duration.max(0.1)),
+            "-i", input,
+            "-vn",
+            "-af", "aformat=channel_layouts=stereo,aphasemeter=video=0,ametadata=print:key=lavfi.aphasemeter.phase",
+            "-f", "null", "-",
+        ])
+        .output()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    let values: Vec<f32> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| line.split_once("lavfi.aphasemeter.phase=")?.1.trim().parse().ok())
+        .collect();
+    if values.is_empty() {
+        return Err(anyhow!("Brak pomiaru fazy (brak audio?)"));
+    }
+    Ok(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// Pobiera informacje o wideo przez ffprobe: (dlugosc, szerokosc, wysokosc, fps, bitrate kbps)
+pub fn get_video_info_ffprobe(path: &str) -> Result<(f32, u32, u32, f32, f32)> {
+    let output = ffprobe_command()
+        .args([
+            "-v", "error",
+            "-select_streams", "v:0",
+            "-show_entries", "stream=width,height,duration,r_frame_rate:format=bit_rate",
             "-of", "csv=p=0",
             path,
         ])
@@ -64,7 +482,14 @@ pub fn get_video_info_ffprobe(path: &str) -> Result<(f32, u32, u32, f32)> {
         .context("Nie mozna uruchomic ffprobe")?;
     
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let parts: Vec<&str> = stdout.trim().split(',').collect();
+    // Linia strumienia, potem linia sekcji format (bit_rate w b/s)
+    let mut lines = stdout.lines().map(str::trim).filter(|l| !l.is_empty());
+    let stream_line = lines.next().unwrap_or_default();
+    let bitrate_kbps = lines
+        .next()
+        .and_then(|l| l.trim_end_matches(',').parse::<f32>().ok())
+        .map_or(0.0, |bps| bps / 1000.0);
+    let parts: Vec<&str> = stream_line.split(',').collect();
     if parts.len() < 4 {
         return Err(anyhow!("Nieprawidlowy format ffprobe: {}", stdout));
     }
@@ -74,7 +499,57 @@ pub fn get_video_info_ffprobe(path: &str) -> Result<(f32, u32, u32, f32)> {
     let duration: f32 = parts[2].parse().unwrap_or(0.0);
     let fps = parse_fps(parts[3]).unwrap_or(30.0);
     
-    Ok((duration, width, height, fps))
+    Ok((duration, width, height, fps, bitrate_kbps))
+}
+
+/// Kodeki, bitrate i parametry audio pliku (uzupelnienie get_video_info_ffprobe)
+pub fn get_media_info_ffprobe(path: &str) -> Result<MediaInfo> {
+    let output = ffprobe_command()
+        .args([
+            "-v", "error",
+            "-show_entries", "stream=codec_type,codec_name,bit_rate,sample_rate,channels",
+            "-show_entries", "format=format_name,bit_rate",
+            "-of", "json",
+            path,
+        ])
+        .output()
+        .context("Nie mozna uruchomic ffprobe")?;
+    if !output.status.success() {
+        return Err(anyhow!("ffprobe: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    parse_media_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parsuje JSON z ffprobe (-of json); pierwszy strumien wideo i audio
+pub fn parse_media_info(json: &str) -> Result<MediaInfo> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| anyhow!("Blad parsowania ffprobe: {e}"))?;
+    // ffprobe zwraca liczby jako tekst ("44100")
+    let number = |v: &serde_json::Value, key: &str| -> u64 {
+        v.get(key).and_then(|n| n.as_str()).and_then(|n| n.parse().ok()).unwrap_or(0)
+    };
+    let text = |v: &serde_json::Value, key: &str| v.get(key).and_then(|t| t.as_str()).unwrap_or_default().to_string();
+    let streams = value.get("streams").and_then(|s| s.as_array()).cloned().unwrap_or_default();
+    let stream = |kind: &str| streams.iter().find(|s| s.get("codec_type").and_then(|t| t.as_str()) == Some(kind));
+
+    let mut info = MediaInfo::default();
+    if let Some(video) = stream("video") {
+        info.video_codec = text(video, "codec_name");
+        info.video_bitrate_kbps = (number(video, "bit_rate") / 1000) as u32;
+    }
+    if let Some(audio) = stream("audio") {
+        info.audio_codec = text(audio, "codec_name");
+        info.audio_sample_rate = number(audio, "sample_rate") as u32;
+        info.audio_channels = audio.get("channels").and_then(|c| c.as_u64()).unwrap_or(0) as u16;
+        info.audio_bitrate_kbps = (number(audio, "bit_rate") / 1000) as u32;
+    }
+    if let Some(format) = value.get("format") {
+        info.container_format = text(format, "format_name");
+        // MKV/WebM nie podaja bitrate strumienia: wideo = calosc - audio
+        if info.video_bitrate_kbps == 0 && !info.video_codec.is_empty() {
+            info.video_bitrate_kbps = ((number(format, "bit_rate") / 1000) as u32).saturating_sub(info.audio_bitrate_kbps);
+        }
+    }
+    Ok(info)
 }
 
 /// Parsuje FPS z formatu "30/1" lub "29.97"
@@ -90,16 +565,97 @@ pub fn parse_fps(value: &str) -> Option<f32> {
 }
 
 /// Generuje waveform z audio
-pub fn generate_waveform(input: &str, output: &Path) -> Result<()> {
+pub fn generate_waveform(input: &str, output: &Path, amplitude: WaveformAmplitude) -> Result<()> {
+    generate_waveform_sized(input, output, WAVEFORM_BASE_WIDTH, amplitude)
+}
+
+/// Szerokosc podstawowego waveformu i gorny limit dla wersji w duzym zoomie
+pub const WAVEFORM_BASE_WIDTH: u32 = 2048;
+pub const WAVEFORM_MAX_WIDTH: u32 = 8192;
+
+/// Waveform o zadanej szerokosci (wysokosc stala 100 px)
+pub fn generate_waveform_sized(input: &str, output: &Path, width: u32, amplitude: WaveformAmplitude) -> Result<()> {
+    let filter = waveform_filter(width, resolve_waveform_amplitude(input, amplitude));
     run_ffmpeg(&[
         "-y",
         "-i", input,
-        "-filter_complex", "showwavespic=s=2048x100:colors=white",
+        "-filter_complex", &filter,
         "-frames:v", "1",
         output.to_str().unwrap_or("waveform.png"),
     ])
 }
 
+/// Filtr showwavespic dla trybu amplitudy (Auto = domyslna skala liniowa)
+fn waveform_filter(width: u32, amplitude: WaveformAmplitude) -> String {
+    let wave = format!("showwavespic=s={width}x100");
+    match amplitude {
+        WaveformAmplitude::Auto => format!("{wave}:colors=white"),
+        WaveformAmplitude::Normalized => format!("aformat=channel_layouts=mono,{wave}:scale=cbrt:colors=white"),
+        WaveformAmplitude::Dbfs(threshold) => format!("volume={:.1}dB,{wave}:colors=white", (-threshold).max(0.0)),
+    }
+}
+
+/// Auto: wybiera skale wg szczytu nagrania. Glosne zostaje liniowe, ciche jest wzmacniane
+/// do pelnej skali, a bardzo ciche (lub nieudany pomiar szczytu) dostaje skale cbrt.
+fn resolve_waveform_amplitude(input: &str, amplitude: WaveformAmplitude) -> WaveformAmplitude {
+    if amplitude != WaveformAmplitude::Auto {
+        return amplitude;
+    }
+    match detect_peak_level(input) {
+        Ok(Some(peak)) if peak >= -6.0 => WaveformAmplitude::Auto,
+        Ok(Some(peak)) if peak >= -40.0 => WaveformAmplitude::Dbfs(peak),
+        Ok(Some(_)) => WaveformAmplitude::Normalized,
+        // Cisza lub brak audio: skala bez znaczenia
+        Ok(None) => WaveformAmplitude::Auto,
+        Err(_) => WaveformAmplitude::Normalized,
+    }
+}
+
+/// Szczytowy poziom audio w dBFS (filtr astats); None dla ciszy / pliku bez audio
+pub fn detect_peak_level(input: &str) -> Result<Option<f32>> {
+    let output = ffmpeg_command()
+        .args(["-hide_banner", "-nostats", "-i", input, "-vn", "-af", "astats", "-f", "null", "-"])
+        .output()
+        .context("Nie mozna uruchomic ffmpeg (sprawdz PATH)")?;
+    if !output.status.success() {
+        return Err(anyhow!("ffmpeg zwrocil blad: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(parse_astats_peak(&String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Ostatnia linia "Peak level dB: X" to sekcja "Overall" (wszystkie kanaly)
+fn parse_astats_peak(stderr: &str) -> Option<f32> {
+    let key = "Peak level dB:";
+    let line = stderr.lines().rev().find(|line| line.contains(key))?;
+    let peak: f32 = line[line.find(key)? + key.len()..].trim().parse().ok()?;
+    peak.is_finite().then_some(peak)
+}
+
+/// Generuje czarny obraz z cisza (wypelnienie luk na osi czasu)
+pub fn generate_black_segment(duration: f32, width: u32, height: u32, fps: f32, output: &Path) -> Result<()> {
+    let args = build_black_segment_args(duration, width, height, fps, &output.to_string_lossy());
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    run_ffmpeg(&args_refs)
+}
+
+/// Argumenty ffmpeg czarnego wypelnienia (wspolne dla renderu i eksportu skryptu)
+pub fn build_black_segment_args(duration: f32, width: u32, height: u32, fps: f32, output: &str) -> Vec<String> {
+    let fps = if fps > 0.0 { fps } else { 30.0 };
+    let color = format!("color=c=black:s={width}x{height}:r={fps:.3}");
+    let duration = format!("{:.3}", duration.max(0.04));
+    [
+        "-y",
+        "-f", "lavfi", "-i", &color,
+        "-f", "lavfi", "-i", "anullsrc=r=44100:cl=stereo",
+        "-t", &duration,
+        "-c:v", "libx264", "-pix_fmt", "yuv420p",
+        "-c:a", "aac",
+        output,
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
 /// Tworzy katalog tymczasowy
 pub fn create_temp_dir() -> Result<PathBuf> {
     let base = std::env::temp_dir();
@@ -112,12 +668,143 @@ pub fn create_temp_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
-/// Buduje filtry fade dla klipu
-pub fn build_fade_filters(clip: &Clip) -> (Option<String>, Option<String>) {
-    let duration = (clip.end - clip.start).max(0.0);
+/// Łańcuch atempo dla dowolnej prędkości (pojedynczy atempo obsługuje 0.5-2.0)
+pub fn atempo_chain(speed: f32) -> String {
+    let mut remaining = speed.max(0.01);
+    let mut parts = Vec::new();
+    while remaining > 2.0 {
+        parts.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        parts.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    parts.push(format!("atempo={remaining:.4}"));
+    parts.join(",")
+}
+
+/// Wyostrzenie / rozmycie; wokol zera (|s| < 0.05) bez filtra
+pub fn sharpness_filter(sharpness: f32) -> Option<String> {
+    let sharpness = sharpness.clamp(-5.0, 5.0);
+    if sharpness.abs() < 0.05 {
+        None
+    } else if sharpness > 0.0 {
+        Some(format!("unsharp=5:5:{sharpness:.2}:5:5:0"))
+    } else {
+        // Promien 0 nic nie robi - slabe rozmycie zaokraglamy w gore do 1
+        let radius = ((-sharpness).round() as u32).max(1);
+        Some(format!("boxblur={radius}:{radius}"))
+    }
+}
+
+/// Wyrażenie filtra pojedynczego efektu; None gdy efekt niczego nie zmienia
+pub fn effect_filter(effect: &ClipEffect) -> Option<String> {
+    match *effect {
+        ClipEffect::Sharpness(sharpness) => sharpness_filter(sharpness),
+        ClipEffect::Denoise(preset) => {
+            let (ls, cs, lt, ct) = preset.hqdn3d_params();
+            Some(format!("hqdn3d={ls:.2}:{cs:.2}:{lt:.2}:{ct:.2}"))
+        }
+        ClipEffect::ColorCorrection { brightness, contrast, saturation } => {
+            let neutral = brightness.abs() < 0.001 && (contrast - 1.0).abs() < 0.001 && (saturation - 1.0).abs() < 0.001;
+            (!neutral).then(|| format!("eq=brightness={brightness:.3}:contrast={contrast:.3}:saturation={saturation:.3}"))
+        }
+        ClipEffect::Vignette(angle) => {
+            (angle > 0.001).then(|| format!("vignette=angle={:.4}", angle.min(std::f32::consts::FRAC_PI_2)))
+        }
+    }
+}
+
+/// Filtry "wyglądu" klipu (bez zależności od czasu) - używane w renderze i podglądzie;
+/// stos efektów sklejony w kolejności listy
+pub fn clip_video_effects(clip: &Clip) -> Option<String> {
+    let parts: Vec<String> = clip.effects.iter().filter_map(effect_filter).collect();
+    if parts.is_empty() { None } else { Some(parts.join(",")) }
+}
+
+/// Sprawdza własny filtr klipu. Dozwolony jest jeden łańcuch filtrów rozdzielonych `,`;
+/// średnik (kolejny filtergraph) i znaki nowej linii są odrzucane.
+///
+/// Zasady escapowania w wartościach opcji: `,` `:` `'` `\` `[` `]` poprzedzamy `\`
+/// albo całą wartość bierzemy w apostrofy, np. `drawtext=text='a\:b'`.
+pub fn validate_filter_expr(expr: &str) -> Result<()> {
+    if expr.contains(';') || expr.contains('\n') {
+        return Err(anyhow!("Filtr nie moze zawierac ';' ani nowej linii"));
+    }
+    Ok(())
+}
+
+/// Własny filtr klipu, jeśli niepusty i poprawny
+fn custom_filter(expr: &Option<String>) -> Option<&str> {
+    let expr = expr.as_deref()?.trim();
+    (!expr.is_empty() && validate_filter_expr(expr).is_ok()).then_some(expr)
+}
+
+/// Filtry rampy prędkości v(x) = s0 + (s1 - s0) * x / D (x = czas źródła).
+/// Wideo: czas wyjściowy to całka z 1/v, czyli ln(1 + a*T) / (s0*a), a = (s1-s0)/(s0*D).
+/// Audio: tempo zmieniane skokowo komendami asendcmd w kilku punktach klipu.
+/// atempo przyjmuje 0.5 - 2.0 w jednym filtrze, wiec rampa idzie przez dwa nazwane
+/// stopnie po sqrt(tempo) - pokrywa to caly zakres rampy z UI (0.25 - 4.0).
+pub fn speed_ramp_filters(s0: f32, s1: f32, span: f32, algorithm: StretchAlgorithm) -> (String, String) {
+    const AUDIO_STEPS: usize = 16;
+    let span = span.max(0.001);
+    let a = (s1 - s0) / (s0 * span);
+    let k = span / (s1 - s0);
+    let vf = format!("setpts='{k:.6}*log(1+{a:.6}*T)/TB'");
+
+    let tempo_at = |u: f32| (s0 + (s1 - s0) * u).clamp(0.25, 4.0);
+    let commands: Vec<String> = (0..AUDIO_STEPS)
+        .map(|i| {
+            let x = span * i as f32 / AUDIO_STEPS as f32;
+            let tempo = tempo_at((i as f32 + 0.5) / AUDIO_STEPS as f32);
+            match algorithm {
+                StretchAlgorithm::Atempo => {
+                    let stage = tempo.sqrt();
+                    format!("{x:.3} atempo@ramp0 tempo {stage:.4},atempo@ramp1 tempo {stage:.4}")
+                }
+                StretchAlgorithm::Rubberband => format!("{x:.3} rubberband tempo {tempo:.4}"),
+            }
+        })
+        .collect();
+    let initial = match algorithm {
+        StretchAlgorithm::Atempo => {
+            let stage = tempo_at(0.0).sqrt();
+            format!("atempo@ramp0={stage:.4},atempo@ramp1={stage:.4}")
+        }
+        StretchAlgorithm::Rubberband => format!("rubberband=tempo={:.4}:pitch=1.0", tempo_at(0.0)),
+    };
+    let af = format!("asendcmd=c='{}',{initial}", commands.join(";"));
+    (vf, af)
+}
+
+/// Buduje filtry klipu: efekty, prędkość, fade in/out i głośność
+pub fn build_fade_filters(clip: &Clip, settings: &RenderSettings) -> (Option<String>, Option<String>) {
+    // Fade liczymy w czasie wyjściowym (po zmianie prędkości)
+    let duration = clip.output_duration();
     let mut vf_parts = Vec::new();
     let mut af_parts = Vec::new();
 
+    // Odwrocenie przed reszta filtrow, zeby fade'y i obwiednia dzialaly w czasie wyjsciowym
+    if clip.reversed {
+        vf_parts.push("reverse".to_string());
+        af_parts.push("areverse".to_string());
+    }
+    if let Some(effects) = clip_video_effects(clip) {
+        vf_parts.push(effects);
+    }
+    if let Some((s0, s1)) = clip.active_speed_ramp() {
+        let (vf, af) = speed_ramp_filters(s0, s1, clip.end - clip.start, settings.stretch_algorithm);
+        vf_parts.push(vf);
+        af_parts.push(af);
+    } else if (clip.speed - 1.0).abs() > f32::EPSILON {
+        vf_parts.push(format!("setpts=PTS/{:.4}", clip.speed.max(0.01)));
+        af_parts.push(match settings.stretch_algorithm {
+            StretchAlgorithm::Atempo => atempo_chain(clip.speed),
+            StretchAlgorithm::Rubberband => format!("rubberband=tempo={:.4}:pitch=1.0", clip.speed.max(0.01)),
+        });
+    }
+
     if clip.fade_in > 0.0 {
         vf_parts.push(format!("fade=t=in:st=0:d={:.2}", clip.fade_in));
         af_parts.push(format!("afade=t=in:st=0:d={:.2}", clip.fade_in));
@@ -127,6 +814,47 @@ pub fn build_fade_filters(clip: &Clip) -> (Option<String>, Option<String>) {
         vf_parts.push(format!("fade=t=out:st={:.2}:d={:.2}", out_start, clip.fade_out));
         af_parts.push(format!("afade=t=out:st={:.2}:d={:.2}", out_start, clip.fade_out));
     }
+    for band in clip.eq_bands.iter().filter(|b| b.gain_db.abs() > 0.01) {
+        af_parts.push(format!(
+            "equalizer=f={:.0}:width_type=o:width={:.2}:g={:.1}",
+            band.frequency_hz, band.bandwidth, band.gain_db
+        ));
+    }
+    if (clip.volume - 1.0).abs() > f32::EPSILON {
+        af_parts.push(format!("volume={:.3}", clip.volume.max(0.0)));
+    }
+    if clip.volume_keyframes.len() >= 2 {
+        af_parts.push(format!(
+            "volume='{}':eval=frame",
+            volume_envelope_expr(&clip.volume_keyframes, &clip_time_expr(clip))
+        ));
+    }
+    if clip.audio_pan.abs() > f32::EPSILON {
+        // Prawo stalej mocy; aformat zamienia mono na stereo zeby c1 istnialo
+        let pan = clip.audio_pan.clamp(-1.0, 1.0);
+        let left = (1.0 - pan.max(0.0)).sqrt();
+        let right = (1.0 + pan.min(0.0)).sqrt();
+        af_parts.push(format!(
+            "aformat=channel_layouts=stereo,pan=stereo|c0={left:.4}*c0|c1={right:.4}*c1"
+        ));
+    }
+    if clip.audio_phase_inverted {
+        // val(ch) = probka biezacego kanalu, wiec dziala dla mono i stereo
+        af_parts.push("aeval=-val(ch):c=same".to_string());
+    }
+    // Rozlaczony klip: obraz/dzwiek poza wlasnym zakresem zaciemniony/wyciszony
+    if let Some((a, b)) = clip.video_trim() {
+        vf_parts.push(format!("drawbox=c=black:t=fill:enable='not(between(t,{a:.3},{b:.3}))'"));
+    }
+    if let Some((a, b)) = clip.audio_trim() {
+        af_parts.push(format!("volume=0:enable='not(between(t,{a:.3},{b:.3}))'"));
+    }
+    if let Some(vf) = custom_filter(&clip.custom_vf) {
+        vf_parts.push(vf.to_string());
+    }
+    if let Some(af) = custom_filter(&clip.custom_af) {
+        af_parts.push(af.to_string());
+    }
 
     let vf = if vf_parts.is_empty() {
         None
@@ -141,14 +869,809 @@ pub fn build_fade_filters(clip: &Clip) -> (Option<String>, Option<String>) {
     (vf, af)
 }
 
+/// Wyrażenie ffmpeg: czas klipu (źródła) odtworzony po `t` s wyjścia, odwrotność
+/// całki rampy z `speed_ramp_filters`; bez rampy t*speed
+fn clip_time_expr(clip: &Clip) -> String {
+    match clip.active_speed_ramp() {
+        Some((s0, s1)) => {
+            let span = (clip.end - clip.start).max(0.001);
+            let c = s0 * span / (s1 - s0);
+            let k = (s1 - s0) / span;
+            format!("({c:.6}*(exp({k:.6}*t)-1))")
+        }
+        None => format!("(t*{:.4})", clip.speed.max(0.01)),
+    }
+}
+
+/// Wyrażenie ffmpeg interpolujące liniowo obwiednię głośności.
+/// Klatki kluczowe są w czasie klipu, a filtr działa po zmianie tempa - stąd `time`
+/// z `clip_time_expr` zamiast samego t.
+pub fn volume_envelope_expr(keyframes: &[(f32, f32)], time: &str) -> String {
+    let Some(&(_, last_gain)) = keyframes.last() else {
+        return "1".to_string();
+    };
+    let mut expr = format!("{last_gain:.4}");
+    for pair in keyframes.windows(2).rev() {
+        let ((t0, g0), (t1, g1)) = (pair[0], pair[1]);
+        let span = (t1 - t0).max(0.0001);
+        expr = format!(
+            "if(lt({time},{t1:.4}),{g0:.4}+({g1:.4}-{g0:.4})*({time}-{t0:.4})/{span:.4},{expr})"
+        );
+    }
+    let (t0, g0) = keyframes[0];
+    format!("if(lt({time},{t0:.4}),{g0:.4},{expr})")
+}
+
+/// Szuka systemowej czcionki dla drawtext (None = wbudowana czcionka ffmpeg)
+fn find_system_font() -> Option<String> {
+    const CANDIDATES: &[&str] = &[
+        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+        "/usr/share/fonts/TTF/DejaVuSans.ttf",
+        "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+        "/System/Library/Fonts/Supplemental/Arial.ttf",
+        "/Library/Fonts/Arial.ttf",
+        "C:\\Windows\\Fonts\\arial.ttf",
+    ];
+    CANDIDATES
+        .iter()
+        .find(|p| Path::new(p).exists())
+        // Dwukropek (C:) trzeba escapowac w filtergraph
+        .map(|p| p.replace('\\', "/").replace(':', "\\:"))
+}
+
+/// Buduje filtr drawtext z timecode; offset = pozycja segmentu w gotowym filmie
+pub fn build_timecode_filter(position: TimecodePosition, offset: f32) -> String {
+    let (x, y) = match position {
+        TimecodePosition::TopLeft => ("10", "10"),
+        TimecodePosition::TopRight => ("w-tw-10", "10"),
+        TimecodePosition::BottomLeft => ("10", "h-th-10"),
+        TimecodePosition::BottomRight => ("w-tw-10", "h-th-10"),
+        TimecodePosition::Center => ("(w-tw)/2", "(h-th)/2"),
+    };
+    let font = find_system_font()
+        .map(|f| format!("fontfile='{f}':"))
+        .unwrap_or_default();
+    format!(
+        "drawtext={font}text='%{{pts\\:hms\\:{offset:.3}}}':x={x}:y={y}:fontsize=24:fontcolor=white:box=1:boxcolor=black@0.5:boxborderw=5"
+    )
+}
+
+/// Skalowanie do rozdzielczości wyjściowej z zachowaniem proporcji (pad/crop na środku)
+pub fn build_aspect_filter(mode: AspectRatioMode, w: u32, h: u32) -> String {
+    let (x, y) = ("(ow-iw)/2", "(oh-ih)/2");
+    let filter = match mode {
+        AspectRatioMode::Stretch => format!("scale={w}:{h}"),
+        // Skalowanie zawsze miesci obraz w kadrze, pasy dokladane po tej stronie, ktorej brakuje
+        AspectRatioMode::Letterbox | AspectRatioMode::Pillarbox => {
+            format!("scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:{x}:{y}:black")
+        }
+        AspectRatioMode::Crop => {
+            format!("scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h}")
+        }
+    };
+    // Wszystkie segmenty musza miec ten sam SAR, inaczej concat -c copy sie rozjedzie
+    format!("{filter},setsar=1")
+}
+
+/// Plik zrodlowy klipu i czy to obraz (asset z biblioteki albo glowne wejscie)
+fn clip_source<'a>(clip: &Clip, input_path: &'a str, assets: &'a [MediaAsset]) -> (&'a str, bool) {
+    match clip.asset_id.and_then(|id| assets.get(id)) { // Assuming index based ID for MVP match
+        Some(asset) => (asset.path.as_str(), asset.kind == MediaType::Image),
+        None => (input_path, false),
+    }
+}
+
+/// Zastepcze audio klipu (inny plik); pusta sciezka = brak
+fn clip_audio_override(clip: &Clip) -> Option<&str> {
+    clip.audio_override.as_deref().filter(|path| !path.trim().is_empty())
+}
+
+/// Argumenty ffmpeg dla jednego segmentu (klipu) - wspolne dla renderu i eksportu skryptu.
+/// `output_offset` to pozycja segmentu w gotowym filmie (dla timecode).
+pub fn build_segment_args(
+    clip: &Clip,
+    input_path: &str,
+    assets: &[MediaAsset],
+    settings: &RenderSettings,
+    output_offset: f32,
+    seg_path: &str,
+) -> Vec<String> {
+    let duration = clip.end - clip.start;
+
+    let (mut vf, af) = build_fade_filters(clip, settings);
+    if let Some((w, h)) = settings.output_resolution.dimensions() {
+        let scale = build_aspect_filter(settings.aspect_mode, w, h);
+        vf = Some(match vf {
+            Some(chain) => format!("{chain},{scale}"),
+            None => scale,
+        });
+    }
+    if settings.burn_timecode {
+        let tc = build_timecode_filter(settings.timecode_position, output_offset);
+        vf = Some(match vf {
+            Some(chain) => format!("{chain},{tc}"),
+            None => tc,
+        });
+    }
+    let (clip_input, is_image) = clip_source(clip, input_path, assets);
+    let audio_override = clip_audio_override(clip);
+
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-hwaccel".into(), "auto".into(),
+    ];
+
+    if is_image {
+         args.push("-loop".into());
+         args.push("1".into());
+         // Image don't have start time in file usually
+         // But we might need -t before input? No, -t is after input for limiting duration usually?
+         // Actually for -loop 1, input is infinite. -t limits output read.
+         // -ss is irrelevant.
+    } else {
+         // -ss/-t zostaja opcjami wejscia, wiec filtry widza czas od 0. Domyslnie ffmpeg
+         // dekoduje od poprzedniej I-klatki i odrzuca klatki do punktu ciecia
+         if settings.force_iframe_seek {
+             args.push("-noaccurate_seek".into());
+         }
+         args.push("-ss".into());
+         args.push(format!("{:.3}", clip.source_in.max(0.0)));
+    }
+
+    args.push("-t".into());
+    args.push(format!("{:.3}", duration));
+    args.push("-i".into());
+    args.push(clip_input.into());
+    if let Some(audio) = audio_override {
+        if !is_image {
+            args.push("-ss".into());
+            args.push(format!("{:.3}", clip.source_in.max(0.0)));
+        }
+        args.push("-t".into());
+        args.push(format!("{:.3}", duration));
+        args.push("-i".into());
+        args.push(audio.into());
+    }
+    // Obraz lub zrodlo bez dzwieku: cisza, zeby kazdy segment mial [k:a] dla xfade/acrossfade
+    let silent = audio_override.is_none() && (is_image || !has_audio_stream(clip_input));
+    if silent {
+        args.extend(["-f", "lavfi", "-t"].map(String::from));
+        args.push(format!("{:.3}", duration));
+        args.extend(["-i", "anullsrc=r=44100:cl=stereo"].map(String::from));
+    }
+    if audio_override.is_some() || silent {
+        args.extend(["-map", "0:v:0", "-map", "1:a:0"].map(String::from));
+    }
+    if silent {
+        args.push("-shortest".into());
+    }
+
+    if let Some(vf_str) = vf {
+        args.push("-vf".into());
+        args.push(vf_str);
+    }
+    if let Some(af_str) = af {
+        args.push("-af".into());
+        args.push(af_str);
+    }
+
+    // Kodeki
+    args.push("-c:v".into());
+    args.push("libx264".into());
+    args.push("-preset".into());
+    args.push("fast".into());
+    args.extend(rate_control_args(settings));
+    args.push("-c:a".into());
+    args.push("aac".into());
+    args.push("-b:a".into());
+    args.push(format!("{}k", settings.audio_bitrate_kbps));
+    args.push(seg_path.into());
+
+    args
+}
+
+/// Jakosc wideo: staly bitrate przy dwoch przebiegach, inaczej CRF
+fn rate_control_args(settings: &RenderSettings) -> [String; 2] {
+    if settings.two_pass {
+        ["-b:v".into(), format!("{}k", settings.target_bitrate_kbps)]
+    } else {
+        ["-crf".into(), settings.crf.to_string()]
+    }
+}
+
+/// Urzadzenie "null" dla pierwszego przebiegu (wynik jest wyrzucany)
+pub fn null_device(windows: bool) -> &'static str {
+    if windows { "NUL" } else { "/dev/null" }
+}
+
+/// Dzieli argumenty segmentu (z plikiem wyjsciowym na koncu) na dwa przebiegi:
+/// 1. analiza bez audio do `-f null`, 2. wlasciwe kodowanie. Statystyki w `passlog*`.
+pub fn build_two_pass_args(args: &[String], passlog: &str, null_device: &str) -> [Vec<String>; 2] {
+    let (output, prefix) = match args.split_last() {
+        Some((output, prefix)) => (output.clone(), prefix.to_vec()),
+        None => (String::new(), Vec::new()),
+    };
+    let mut first = prefix.clone();
+    first.extend(["-pass", "1", "-passlogfile", passlog, "-an", "-f", "null", null_device].map(String::from));
+    let mut second = prefix;
+    second.extend(["-pass", "2", "-passlogfile", passlog].map(String::from));
+    second.push(output);
+    [first, second]
+}
+
+/// Segment kopiowany bez kodowania (zrodlo juz w docelowym kodeku, brak filtrow)
+pub fn build_copy_segment_args(clip: &Clip, clip_input: &str, seg_path: &str) -> Vec<String> {
+    vec![
+        "-y".into(),
+        "-ss".into(), format!("{:.3}", clip.source_in.max(0.0)),
+        "-t".into(), format!("{:.3}", clip.end - clip.start),
+        "-i".into(), clip_input.into(),
+        "-map".into(), "0:v:0".into(),
+        "-map".into(), "0:a:0?".into(),
+        "-c".into(), "copy".into(),
+        "-avoid_negative_ts".into(), "make_zero".into(),
+        seg_path.into(),
+    ]
+}
+
+/// Zrodla segmentow kopiowanych bez kodowania (rownolegle do `clips`). Kopiowanie tylko gdy
+/// kwalifikuje sie kazdy aktywny klip i wszystkie maja ten sam format - segment kodowany
+/// libx264/aac obok kopii zepsulby concat -c copy
+pub fn plan_stream_copy<'a>(
+    clips: &[Clip],
+    input_path: &'a str,
+    assets: &'a [MediaAsset],
+    settings: &RenderSettings,
+) -> Vec<Option<&'a str>> {
+    let none = vec![None; clips.len()];
+    let mut sources = Vec::with_capacity(clips.len());
+    let mut common: Option<CopyFormat> = None;
+    for clip in clips {
+        if !clip.video_enabled && !clip.audio_enabled {
+            sources.push(None);
+            continue;
+        }
+        let Some(input) = stream_copy_candidate(clip, input_path, assets, settings) else { return none };
+        let Some(format) = stream_copy_format(input, clip.source_in, clip.source_out()) else { return none };
+        if common.as_ref().is_some_and(|c| *c != format) {
+            return none;
+        }
+        common = Some(format);
+        sources.push(Some(input));
+    }
+    sources
+}
+
+/// Zrodlo klipu do kopiowania strumienia; None gdy segment musi byc kodowany
+/// (obraz, filtry, skalowanie, timecode, wylaczona sciezka)
+fn stream_copy_candidate<'a>(clip: &Clip, input_path: &'a str, assets: &'a [MediaAsset], settings: &RenderSettings) -> Option<&'a str> {
+    if !clip.video_enabled || !clip.audio_enabled || settings.burn_timecode || settings.output_resolution.dimensions().is_some() {
+        return None;
+    }
+    if build_fade_filters(clip, settings) != (None, None) || clip_audio_override(clip).is_some() {
+        return None;
+    }
+    let input = match clip.asset_id {
+        Some(id) => {
+            let asset = assets.get(id)?;
+            if asset.kind != MediaType::Video {
+                return None;
+            }
+            asset.path.as_str()
+        }
+        None => input_path,
+    };
+    Some(input)
+}
+
+/// Szacowany rozmiar pliku w MB; bitrate wideo przy CRF przyblizony od bitrate zrodla
+pub fn estimate_output_size_mb(duration: f32, source_kbps: f32, crf: u8, audio_kbps: u32) -> f32 {
+    let video_kbps = source_kbps * (-0.1 * (crf as f32 - 18.0)).exp();
+    duration * video_kbps / 8.0 / 1024.0 + duration * audio_kbps as f32 / 8.0 / 1024.0
+}
+
+/// Kodeki celu z `settings.output_codec` (Auto = wg rozszerzenia pliku); jakosc H.264 i bitrate audio z ustawien
+fn target_codec_args(path: &str, settings: &RenderSettings) -> Vec<String> {
+    let audio_bitrate = format!("{}k", settings.audio_bitrate_kbps);
+    match settings.output_codec.resolve(path) {
+        OutputCodec::Vp9 => ["-c:v", "libvpx-vp9", "-crf", "32", "-b:v", "0", "-c:a", "libopus", "-b:a", &audio_bitrate]
+            .map(String::from)
+            .to_vec(),
+        OutputCodec::ProRes => ["-c:v", "prores_ks", "-profile:v", "3", "-c:a", "pcm_s16le"].map(String::from).to_vec(),
+        _ => {
+            let mut args: Vec<String> = ["-c:v", "libx264", "-preset", "fast"].map(String::from).to_vec();
+            args.extend(rate_control_args(settings));
+            args.extend(["-c:a".into(), "aac".into(), "-b:a".into(), audio_bitrate]);
+            args
+        }
+    }
+}
+
+/// Koduje kazde dodatkowe wyjscie z listy concat (segmenty juz pociete).
+/// Statusy trafiaja do `progress.target_status`; blad jednego celu nie przerywa pozostalych.
+pub fn render_multi_output(
+    concat_path: &Path,
+    targets: &[OutputTarget],
+    progress: Option<&Arc<Mutex<RenderProgress>>>,
+) -> Result<()> {
+    let set_status = |i: usize, status: String| {
+        if let Some(progress) = progress
+            && let Ok(mut p) = progress.lock()
+            && let Some(slot) = p.target_status.get_mut(i)
+        {
+            *slot = status;
+        }
+    };
+    if let Some(progress) = progress
+        && let Ok(mut p) = progress.lock()
+    {
+        p.target_status = vec!["pending".to_string(); targets.len()];
+    }
+
+    let concat = concat_path.to_string_lossy();
+    let mut failed = Vec::new();
+    for (i, target) in targets.iter().enumerate() {
+        if target.path.trim().is_empty() {
+            set_status(i, "skipped (no path)".to_string());
+            continue;
+        }
+        set_status(i, "rendering...".to_string());
+        let mut args: Vec<String> = ["-y", "-f", "concat", "-safe", "0", "-i", &concat]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        if let Some((w, h)) = target.settings.output_resolution.dimensions() {
+            args.push("-vf".into());
+            args.push(build_aspect_filter(target.settings.aspect_mode, w, h));
+        }
+        args.extend(target_codec_args(&target.path, &target.settings));
+        args.push(target.path.clone());
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        match run_ffmpeg(&args_refs) {
+            Ok(()) => set_status(i, "done".to_string()),
+            Err(err) => {
+                set_status(i, format!("error: {err:#}"));
+                failed.push(target.path.clone());
+            }
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Blad dodatkowych wyjsc: {}", failed.join(", ")))
+    }
+}
+
+/// Przejscie miedzy dwoma kolejnymi klipami V1, skrocone tak, by miescilo sie w obu klipach
+pub fn transition_between(clip: &Clip, next: &Clip) -> Option<Transition> {
+    let transition = clip.transition_out;
+    if !transition.is_active() || !clip.video_enabled || !next.video_enabled {
+        return None;
+    }
+    // Margines jednej klatki - xfade wymaga, by przejscie konczylo sie przed koncem wejscia
+    let max = clip.output_duration().min(next.output_duration()) - 0.04;
+    (max > 0.0).then(|| Transition { duration: transition.duration.min(max), ..transition })
+}
+
+/// Przejscia miedzy kolejnymi aktywnymi klipami V1 (dlugosc = liczba segmentow - 1)
+pub fn plan_transitions(base_clips: &[Clip]) -> Vec<Option<Transition>> {
+    let active: Vec<&Clip> = base_clips.iter().filter(|c| c.video_enabled || c.audio_enabled).collect();
+    active.windows(2).map(|pair| transition_between(pair[0], pair[1])).collect()
+}
+
+/// Czas na osi -> czas w filmie zlozonym z klipow V1 (concat w kolejnosci listy;
+/// przejscia xfade nakladaja klipy, wiec kolejne zaczynaja sie wczesniej)
+pub fn timeline_to_output(base_clips: &[Clip], time: f32) -> f32 {
+    let active: Vec<&Clip> = base_clips.iter().filter(|c| c.video_enabled || c.audio_enabled).collect();
+    let mut offset = 0.0f32;
+    for (i, clip) in active.iter().enumerate() {
+        if time >= clip.start && time < clip.timeline_end() {
+            return offset + (time - clip.start);
+        }
+        let overlap = active
+            .get(i + 1)
+            .and_then(|next| transition_between(clip, next))
+            .map_or(0.0, |t| t.duration);
+        offset += clip.output_duration() - overlap;
+    }
+    // Poza klipami V1: wzgledem poczatku pierwszego klipu
+    let first = base_clips.iter().map(|c| c.start).fold(f32::INFINITY, f32::min);
+    (time - if first.is_finite() { first } else { 0.0 }).max(0.0)
+}
+
+/// Wyrazenie overlay dla jednej osi (1 = x, 2 = y) z punktow pozycji; czasy punktow liczone od `start`
+pub fn position_expr(keys: &[(f32, f32, f32)], start: f32, axis: usize) -> String {
+    let value = |k: &(f32, f32, f32)| if axis == 1 { k.1 } else { k.2 };
+    let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+        return "0".into();
+    };
+    // Od konca: if(lt(t,T1), lerp(v0,v1,(t-T0)/(T1-T0)), reszta)
+    let mut expr = format!("{:.1}", value(last));
+    for pair in keys.windows(2).rev() {
+        let (t0, t1) = (start + pair[0].0, start + pair[1].0);
+        let span = (t1 - t0).max(0.001);
+        expr = format!(
+            "if(lt(t,{t1:.3}),lerp({:.1},{:.1},(t-{t0:.3})/{span:.3}),{expr})",
+            value(&pair[0]),
+            value(&pair[1])
+        );
+    }
+    format!("if(lt(t,{:.3}),{:.1},{expr})", start + first.0, value(first))
+}
+
+/// Lancuch overlay: wejscie 0 = baza, kolejne wejscia nakladane w oknach (start, end) czasu wyjscia
+pub fn build_overlay_filtergraph(windows: &[(f32, f32)], positions: &[Vec<(f32, f32, f32)>], opacities: &[f32]) -> String {
+    let mut parts = Vec::new();
+    let mut prev = "0:v".to_string();
+    for (k, (start, end)) in windows.iter().enumerate() {
+        let input = k + 1;
+        let keys = positions.get(k).map(Vec::as_slice).unwrap_or_default();
+        let (x, y) = (position_expr(keys, *start, 1), position_expr(keys, *start, 2));
+        // Polprzezroczysta nakladka: kanal alfa skalowany przed overlay
+        let opacity = opacities.get(k).copied().unwrap_or(1.0).clamp(0.0, 1.0);
+        let alpha = if opacity < 1.0 { format!(",format=rgba,colorchannelmixer=aa={opacity:.3}") } else { String::new() };
+        parts.push(format!("[{input}:v]setpts=PTS-STARTPTS+{start:.3}/TB{alpha}[ov{input}]"));
+        parts.push(format!(
+            "[{prev}][ov{input}]overlay=x='{x}':y='{y}':eof_action=pass:enable='between(t,{start:.3},{end:.3})'[v{input}]"
+        ));
+        prev = format!("v{input}");
+    }
+    parts.join(";")
+}
+
+/// Kompozycja: baza V1 + nakladki z wyzszych sciezek (`overlay_clips` rownolegle do `overlays`).
+/// Dzwiek nakladek z wlaczonym audio jest przesuwany do ich okna i miksowany z baza.
+pub fn build_composite_args(
+    base: &str,
+    overlays: &[String],
+    windows: &[(f32, f32)],
+    overlay_clips: &[&Clip],
+    settings: &RenderSettings,
+    output_path: &str,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into(), "-i".into(), base.into()];
+    for overlay in overlays {
+        args.push("-i".into());
+        args.push(overlay.clone());
+    }
+    let positions: Vec<_> = overlay_clips.iter().map(|c| c.position_keyframes.clone()).collect();
+    let opacities: Vec<f32> = overlay_clips.iter().map(|c| c.opacity).collect();
+    let mut graph = build_overlay_filtergraph(windows, &positions, &opacities);
+    let mut mix_inputs = vec!["[0:a]".to_string()];
+    for (k, (clip, (start, _))) in overlay_clips.iter().zip(windows).enumerate() {
+        if clip.audio_enabled {
+            let delay = (start.max(0.0) * 1000.0).round() as u64;
+            graph.push_str(&format!(";[{}:a]adelay={delay}:all=1[oa{k}]", k + 1));
+            mix_inputs.push(format!("[oa{k}]"));
+        }
+    }
+    let mix = mix_inputs.len() > 1;
+    if mix {
+        graph.push_str(&format!(";{}amix=inputs={}:duration=first:normalize=0[amix]", mix_inputs.concat(), mix_inputs.len()));
+    }
+    args.extend([
+        "-filter_complex".into(), graph,
+        "-map".into(), format!("[v{}]", windows.len()),
+        "-map".into(), if mix { "[amix]".into() } else { "0:a?".into() },
+        "-c:v".into(), "libx264".into(),
+        "-preset".into(), "fast".into(),
+        "-crf".into(), settings.crf.to_string(),
+    ]);
+    if mix {
+        args.extend(["-c:a".into(), "aac".into(), "-b:a".into(), format!("{}k", settings.audio_bitrate_kbps)]);
+    } else {
+        args.extend(["-c:a".into(), "copy".into()]);
+    }
+    args.push(output_path.into());
+    args
+}
+
+/// Pozycja znaku wodnego dla filtra overlay: rogi z marginesem liczonym od wymiarow kadru
+/// (`w`/`h` to wymiary przeskalowanego logo w wyrazeniu overlay)
+pub fn watermark_offset(position: WatermarkPosition, width: u32, height: u32) -> (String, String) {
+    let margin = (width.min(height) / 36).max(8);
+    let right = format!("{width}-w-{margin}");
+    let bottom = format!("{height}-h-{margin}");
+    match position {
+        WatermarkPosition::TopLeft => (margin.to_string(), margin.to_string()),
+        WatermarkPosition::TopRight => (right, margin.to_string()),
+        WatermarkPosition::BottomLeft => (margin.to_string(), bottom),
+        WatermarkPosition::BottomRight => (right, bottom),
+        WatermarkPosition::Custom(x, y) => (x.to_string(), y.to_string()),
+    }
+}
+
+/// Nakladka logo na gotowy film (audio kopiowane bez zmian)
+pub fn build_watermark_args(base: &str, watermark: &WatermarkSettings, width: u32, height: u32, settings: &RenderSettings, output_path: &str) -> Vec<String> {
+    let (x, y) = watermark_offset(watermark.position, width, height);
+    let filter = format!(
+        "[1:v]scale=iw*{:.3}:-1,format=rgba,colorchannelmixer=aa={:.3}[wm];[0:v][wm]overlay={x}:{y}[v]",
+        watermark.scale.max(0.01),
+        watermark.opacity.clamp(0.0, 1.0)
+    );
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-i".into(), base.into(),
+        "-i".into(), watermark.path.to_string_lossy().into_owned(),
+        "-filter_complex".into(), filter,
+        "-map".into(), "[v]".into(),
+        "-map".into(), "0:a?".into(),
+        "-c:v".into(), "libx264".into(),
+        "-preset".into(), "fast".into(),
+    ];
+    args.extend(rate_control_args(settings));
+    args.extend(["-c:a".into(), "copy".into(), output_path.into()]);
+    args
+}
+
+/// Ogon audio klipu wmiksowany pod poczatek nastepnego klipu
+pub struct AudioCrossfade {
+    pub source: String,
+    /// Czas zrodla, od ktorego czytamy ogon (source_out klipu)
+    pub source_start: f32,
+    pub duration: f32,
+    /// Pozycja poczatku nastepnego klipu w gotowym filmie
+    pub at: f32,
+    pub volume: f32,
+}
+
+/// Pary kolejnych klipow V1 z przenikaniem audio (N: audio_crossfade_out > 0, N+1: fade_in > 0)
+pub fn plan_audio_crossfades(base_clips: &[Clip], input_path: &str, assets: &[MediaAsset]) -> Vec<AudioCrossfade> {
+    let active: Vec<&Clip> = base_clips.iter().filter(|c| c.video_enabled || c.audio_enabled).collect();
+    active
+        .windows(2)
+        .filter(|pair| {
+            let (clip, next) = (pair[0], pair[1]);
+            // Przy przejsciu xfade audio miesza acrossfade
+            clip.audio_enabled
+                && next.audio_enabled
+                && clip.audio_crossfade_out > 0.0
+                && next.fade_in > 0.0
+                && transition_between(clip, next).is_none()
+        })
+        .filter_map(|pair| {
+            let (clip, next) = (pair[0], pair[1]);
+            let (source, is_image) = match clip_audio_override(clip) {
+                Some(audio) => (audio, false),
+                None => clip_source(clip, input_path, assets),
+            };
+            (!is_image).then(|| AudioCrossfade {
+                source: source.to_string(),
+                source_start: clip.source_out(),
+                duration: clip.audio_crossfade_out.min(next.output_duration()),
+                at: timeline_to_output(base_clips, next.start),
+                volume: clip.volume,
+            })
+        })
+        .collect()
+}
+
+/// Miksuje ogony audio (amix) z dzwiekiem gotowego filmu; obraz kopiowany bez zmian
+pub fn build_audio_crossfade_args(base: &str, fades: &[AudioCrossfade], audio_bitrate_kbps: u32, output_path: &str) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into(), "-i".into(), base.into()];
+    for fade in fades {
+        args.extend([
+            "-ss".into(), format!("{:.3}", fade.source_start),
+            "-t".into(), format!("{:.3}", fade.duration),
+            "-i".into(), fade.source.clone(),
+        ]);
+    }
+    let mut parts = Vec::new();
+    let mut mix_inputs = String::from("[0:a]");
+    for (k, fade) in fades.iter().enumerate() {
+        let input = k + 1;
+        let delay_ms = (fade.at * 1000.0).round() as u64;
+        parts.push(format!(
+            "[{input}:a]volume={:.3},afade=t=out:st=0:d={:.3},adelay={delay_ms}:all=1[xf{input}]",
+            fade.volume, fade.duration
+        ));
+        mix_inputs.push_str(&format!("[xf{input}]"));
+    }
+    parts.push(format!("{mix_inputs}amix=inputs={}:duration=first:normalize=0[aout]", fades.len() + 1));
+    args.extend([
+        "-filter_complex".into(), parts.join(";"),
+        "-map".into(), "0:v?".into(),
+        "-map".into(), "[aout]".into(),
+        "-c:v".into(), "copy".into(),
+        "-c:a".into(), "aac".into(),
+        "-b:a".into(), format!("{audio_bitrate_kbps}k"),
+        output_path.into(),
+    ]);
+    args
+}
+
+/// Argumenty ffmpeg laczace segmenty z listy concat bez rekompresji
+pub fn build_concat_args(concat_list: &str, output_path: &str) -> Vec<String> {
+    [
+        "-y", "-f", "concat", "-safe", "0", "-i", concat_list, "-c", "copy", output_path,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Laczy segmenty filtrami zamiast demuxera concat: xfade + acrossfade tam, gdzie jest przejscie,
+/// concat w pozostalych miejscach. `fps` wyrownuje klatkaz (xfade wymaga zgodnych wejsc).
+pub fn build_xfade_args(
+    segments: &[String],
+    durations: &[f32],
+    transitions: &[Option<Transition>],
+    fps: Option<f32>,
+    settings: &RenderSettings,
+    output_path: &str,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into()];
+    for seg in segments {
+        args.extend(["-i".into(), seg.clone()]);
+    }
+    let mut parts = Vec::new();
+    for k in 0..segments.len() {
+        let fps = fps.map(|f| format!(",fps={f:.3}")).unwrap_or_default();
+        parts.push(format!("[{k}:v]settb=AVTB{fps},format=yuv420p[v{k}]"));
+    }
+    let (mut video, mut audio) = ("v0".to_string(), "0:a".to_string());
+    let mut length = durations.first().copied().unwrap_or(0.0);
+    for k in 1..segments.len() {
+        let duration = durations.get(k).copied().unwrap_or(0.0);
+        let transition = transitions.get(k - 1).copied().flatten();
+        match transition.and_then(|t| Some((t, t.kind.xfade_name()?))) {
+            Some((t, name)) => {
+                let offset = (length - t.duration).max(0.0);
+                parts.push(format!(
+                    "[{video}][v{k}]xfade=transition={name}:duration={:.3}:offset={offset:.3}[vx{k}]",
+                    t.duration
+                ));
+                parts.push(format!("[{audio}][{k}:a]acrossfade=d={:.3}[ax{k}]", t.duration));
+                length += duration - t.duration;
+            }
+            None => {
+                parts.push(format!("[{video}][{audio}][v{k}][{k}:a]concat=n=2:v=1:a=1[vx{k}][ax{k}]"));
+                length += duration;
+            }
+        }
+        video = format!("vx{k}");
+        audio = format!("ax{k}");
+    }
+    args.extend(["-filter_complex".into(), parts.join(";")]);
+    args.extend(["-map".into(), format!("[{video}]")]);
+    args.extend(["-map".into(), if audio == "0:a" { "0:a?".into() } else { format!("[{audio}]") }]);
+    args.extend(["-c:v".into(), "libx264".into(), "-preset".into(), "fast".into()]);
+    args.extend(rate_control_args(settings));
+    args.extend(["-c:a".into(), "aac".into(), "-b:a".into(), format!("{}k", settings.audio_bitrate_kbps)]);
+    args.push(output_path.into());
+    args
+}
+
+/// Format czarnych wypelnien luk: rozdzielczosc wyjscia (gdy ustawiona) albo zrodla pierwszego klipu
+pub fn filler_format(clips: &[Clip], input_path: &str, assets: &[MediaAsset], settings: &RenderSettings) -> (u32, u32, f32) {
+    let source = clips
+        .iter()
+        .map(|clip| clip_source(clip, input_path, assets))
+        .find(|(path, is_image)| !is_image && !path.is_empty())
+        .and_then(|(path, _)| get_video_info_ffprobe(path).ok())
+        .filter(|info| info.1 > 0 && info.2 > 0);
+    let (width, height) = settings
+        .output_resolution
+        .dimensions()
+        .or(source.map(|info| (info.1, info.2)))
+        .unwrap_or((1920, 1080));
+    (width, height, source.map_or(30.0, |info| info.3))
+}
+
+/// Kopiuje z gotowego pliku tylko obraz (`-an`) albo tylko dzwiek (`-vn`), bez rekompresji
+pub fn build_track_extract_args(input: &str, tracks: TrackType, output_path: &str) -> Vec<String> {
+    let streams: &[&str] = match tracks {
+        TrackType::Video => &["-map", "0:v:0", "-an"],
+        TrackType::Audio => &["-map", "0:a:0", "-vn"],
+        TrackType::Both => &["-map", "0"],
+    };
+    let mut args: Vec<String> = ["-y", "-i", input].map(String::from).to_vec();
+    args.extend(streams.iter().map(|s| s.to_string()));
+    args.extend(["-c", "copy", output_path].map(String::from));
+    args
+}
+
+/// Pliki dla "osobnych sciezek": {output}.video.mp4 i {output}.audio.aac
+pub fn separate_track_paths(output_path: &str) -> (String, String) {
+    let path = Path::new(output_path);
+    (
+        path.with_extension("video.mp4").to_string_lossy().to_string(),
+        path.with_extension("audio.aac").to_string_lossy().to_string(),
+    )
+}
+
+/// Render wg wyboru sciezek z ustawien (`export_tracks` czyta render_video);
+/// osobne pliki to dwa rendery: sam obraz i sam dzwiek
+pub fn render_deliverables(
+    input_path: &str,
+    output_path: &str,
+    clips: &[Clip],
+    assets: &[MediaAsset],
+    settings: &RenderSettings,
+    subtitles: &[SubtitleEntry],
+    progress: Option<&Arc<Mutex<RenderProgress>>>,
+) -> Result<()> {
+    if !settings.separate_track_files {
+        return render_video(input_path, output_path, clips, assets, settings, subtitles, progress);
+    }
+    let (video_path, audio_path) = separate_track_paths(output_path);
+    let video = RenderSettings { export_tracks: TrackType::Video, ..settings.clone() };
+    render_video(input_path, &video_path, clips, assets, &video, subtitles, progress)?;
+    let audio = RenderSettings { export_tracks: TrackType::Audio, ..settings.clone() };
+    render_video(input_path, &audio_path, clips, assets, &audio, subtitles, progress)
+}
+
 /// Renderuje wideo na podstawie listy klipów
-pub fn render_video(input_path: &str, output_path: &str, clips: &[Clip], assets: &[MediaAsset]) -> Result<()> {
+pub fn render_video(
+    input_path: &str,
+    output_path: &str,
+    clips: &[Clip],
+    assets: &[MediaAsset],
+    settings: &RenderSettings,
+    subtitles: &[SubtitleEntry],
+    progress: Option<&Arc<Mutex<RenderProgress>>>,
+) -> Result<()> {
+    let tracks = settings.export_tracks;
+    // Render zakresu mark in / mark out: klipy przyciete do zakresu
+    let ranged_clips;
+    let clips = match settings.active_range() {
+        Some((start, end)) => {
+            let mut ranged = clips.to_vec();
+            restrict_to_range(&mut ranged, start, end);
+            ranged_clips = ranged;
+            ranged_clips.as_slice()
+        }
+        None => clips,
+    };
     if clips.is_empty() {
         return Err(anyhow!("Brak klipow do renderowania"));
     }
-    
+    // V1 jest skladana concatem; klipy z wyzszych sciezek nakladamy potem overlayem
+    let overlay_clips: Vec<Clip> = clips.iter().filter(|c| c.track_index > 0 && c.video_enabled).cloned().collect();
+    let mut base_clips: Vec<Clip> = clips.iter().filter(|c| c.track_index == 0).cloned().collect();
+    if base_clips.is_empty() {
+        return Err(anyhow!("Brak klipow na sciezce V1"));
+    }
+
     let temp_dir = create_temp_dir()?;
+    // Luki V1 wypelniane czarnym obrazem z cisza tylko na czas renderu (lista klipow projektu bez zmian)
+    let mut filled_assets;
+    let assets = if settings.auto_fill_gaps {
+        filled_assets = assets.to_vec();
+        let (width, height, fps) = filler_format(&base_clips, input_path, assets, settings);
+        fill_gaps(&mut base_clips, &mut filled_assets, width, height, fps, &temp_dir)?;
+        filled_assets.as_slice()
+    } else {
+        assets
+    };
+    let clips = base_clips.as_slice();
+
+    // Postep: liczba klatek wyjsciowych ustawiona przez wywolujacego (total_frames)
+    let total_frames = progress
+        .and_then(|p| p.lock().ok().map(|p| p.total_frames))
+        .unwrap_or(0)
+        .max(1);
+    let total_duration: f32 = clips
+        .iter()
+        .filter(|c| c.video_enabled || c.audio_enabled)
+        .map(|c| c.output_duration())
+        .sum();
+    let frame_rate = total_frames as f32 / total_duration.max(0.001);
+    let mut frames_before = 0u64;
+
+    // Sam obraz / sam dzwiek: pelny film powstaje w temp, na koncu kopiujemy z niego wybrana sciezke
+    let target_path = output_path;
+    let full_path = temp_dir.join("full.mp4").to_string_lossy().to_string();
+    let composed_path = if tracks == TrackType::Both { target_path } else { full_path.as_str() };
+    // Znak wodny: montaz laduje w temp, logo naklada ostatni przebieg do `composed_path`
+    let watermark = settings.watermark.as_ref().filter(|w| !w.path.as_os_str().is_empty());
+    let unmarked_path = temp_dir.join("unmarked.mp4").to_string_lossy().to_string();
+    let output_path = if watermark.is_some() { unmarked_path.as_str() } else { composed_path };
     let mut segment_paths: Vec<PathBuf> = Vec::new();
+    // Pozycja segmentu w gotowym filmie (dla timecode)
+    let mut output_offset = 0.0f32;
+    let copy_sources = plan_stream_copy(clips, input_path, assets, settings);
 
     for (i, clip) in clips.iter().enumerate() {
         if !clip.video_enabled && !clip.audio_enabled {
@@ -156,66 +1679,46 @@ pub fn render_video(input_path: &str, output_path: &str, clips: &[Clip], assets:
         }
         
         let seg_path = temp_dir.join(format!("seg_{i:04}.mp4"));
-        let duration = clip.end - clip.start;
-        
-        let (vf, af) = build_fade_filters(clip);
-        
-        let (clip_input, is_image) = if let Some(asset_id) = clip.asset_id {
-            if let Some(asset) = assets.get(asset_id) { // Assuming index based ID for MVP match
-                 (asset.path.as_str(), asset.kind == MediaType::Image)
-            } else {
-                 (input_path, false)
-            }
-        } else {
-            (input_path, false)
+        let copy_source = copy_sources[i];
+        let mut args = match copy_source {
+            Some(source) => build_copy_segment_args(clip, source, &seg_path.to_string_lossy()),
+            None => build_segment_args(clip, input_path, assets, settings, output_offset, &seg_path.to_string_lossy()),
         };
-
-        let mut args: Vec<String> = vec![
-            "-y".into(),
-            "-hwaccel".into(), "auto".into(),
-        ];
-
-        if is_image {
-             args.push("-loop".into());
-             args.push("1".into());
-             // Image don't have start time in file usually
-             // But we might need -t before input? No, -t is after input for limiting duration usually?
-             // Actually for -loop 1, input is infinite. -t limits output read.
-             // -ss is irrelevant.
-        } else {
-             args.push("-ss".into());
-             args.push(format!("{:.3}", clip.start));
+        output_offset += clip.output_duration();
+        if let Some(progress) = progress
+            && let Ok(mut p) = progress.lock()
+        {
+            let mode = match copy_source {
+                Some(_) => "stream copy",
+                None if settings.two_pass => "two-pass",
+                None => "re-encode",
+            };
+            p.messages.push(format!("Segment {}: {mode}", i + 1));
+        }
+        if copy_source.is_none() && settings.two_pass {
+            // Pierwszy przebieg bez raportowania postepu; pasek idzie z drugiego
+            let passlog = temp_dir.join(format!("pass_{i:04}"));
+            let [first, second] = build_two_pass_args(&args, &passlog.to_string_lossy(), null_device(cfg!(windows)));
+            let first_refs: Vec<&str> = first.iter().map(|s| s.as_str()).collect();
+            run_ffmpeg(&first_refs)?;
+            args = second;
         }
-
-        args.push("-t".into());
-        args.push(format!("{:.3}", duration));
-        args.push("-i".into());
-        args.push(clip_input.into());
-
-        if let Some(vf_str) = vf {
-            args.push("-vf".into());
-            args.push(vf_str);
-        }
-        if let Some(af_str) = af {
-            args.push("-af".into());
-            args.push(af_str);
-        }
-
-        // Kodeki
-        args.push("-c:v".into());
-        args.push("libx264".into());
-        args.push("-preset".into());
-        args.push("fast".into());
-        args.push("-crf".into());
-        args.push("18".into());
-        args.push("-c:a".into());
-        args.push("aac".into());
-        args.push("-b:a".into());
-        args.push("192k".into());
-        args.push(seg_path.to_string_lossy().into());
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        run_ffmpeg(&args_refs)?;
+        run_ffmpeg_with_progress(&args_refs, |frame, fps, speed| {
+            let Some(progress) = progress else { return };
+            if let Ok(mut p) = progress.lock() {
+                let processed = (frames_before + frame).min(total_frames);
+                p.frames_done = processed;
+                p.percent = processed as f32 / total_frames as f32 * 100.0;
+                p.fps = fps;
+                p.speed = speed;
+                if fps > 0.0 {
+                    p.eta_secs = (total_frames - processed) as f64 / fps as f64;
+                }
+            }
+        })?;
+        frames_before += (clip.output_duration() * frame_rate).round() as u64;
         segment_paths.push(seg_path);
     }
 
@@ -224,25 +1727,125 @@ pub fn render_video(input_path: &str, output_path: &str, clips: &[Clip], assets:
     }
 
     // Concat lista
-    let concat_list = temp_dir.join("concat.txt");
+    let mut concat_list = temp_dir.join("concat.txt");
     let concat_content: String = segment_paths
         .iter()
         .map(|p| format!("file '{}'\n", p.to_string_lossy()))
         .collect();
     fs::write(&concat_list, concat_content)?;
 
-    // Concat
-    run_ffmpeg(&[
-        "-y",
-        "-f", "concat",
-        "-safe", "0",
-        "-i", concat_list.to_str().unwrap(),
-        "-c", "copy",
-        output_path,
-    ])?;
+    // Concat (przy przenikaniach audio / nakladkach najpierw do pliku bazowego w temp)
+    let crossfades = plan_audio_crossfades(clips, input_path, assets);
+    let transitions = plan_transitions(clips);
+    let base_path = temp_dir.join("base.mp4");
+    let concat_output = if overlay_clips.is_empty() && crossfades.is_empty() {
+        output_path.to_string()
+    } else {
+        base_path.to_string_lossy().to_string()
+    };
+    let concat_args = if transitions.iter().any(Option::is_some) {
+        // Przejscia wymagaja kodowania calosci filtrem xfade zamiast concat -c copy
+        let segments: Vec<String> = segment_paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+        let durations: Vec<f32> = clips
+            .iter()
+            .filter(|c| c.video_enabled || c.audio_enabled)
+            .map(|c| c.output_duration())
+            .collect();
+        let fps = get_video_info_ffprobe(&segments[0]).ok().map(|info| info.3).filter(|f| *f > 0.0);
+        if let Some(progress) = progress
+            && let Ok(mut p) = progress.lock()
+        {
+            p.messages.push("Transitions: xfade".to_string());
+        }
+        build_xfade_args(&segments, &durations, &transitions, fps, settings, &concat_output)
+    } else {
+        build_concat_args(&concat_list.to_string_lossy(), &concat_output)
+    };
+    let concat_refs: Vec<&str> = concat_args.iter().map(|s| s.as_str()).collect();
+    run_ffmpeg(&concat_refs)?;
+    if concat_output == output_path && transitions.iter().any(Option::is_some) {
+        // Dodatkowe wyjscia koduja gotowy plik z przejsciami
+        concat_list = temp_dir.join("final.txt");
+        fs::write(&concat_list, format!("file '{output_path}'\n"))?;
+    }
+
+    // Przenikania audio: ogony klipow miksowane z dzwiekiem bazy
+    let mut concat_output = concat_output;
+    if !crossfades.is_empty() {
+        let mixed = if overlay_clips.is_empty() { output_path.to_string() } else { temp_dir.join("mixed.mp4").to_string_lossy().to_string() };
+        let args = build_audio_crossfade_args(&concat_output, &crossfades, settings.audio_bitrate_kbps, &mixed);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        run_ffmpeg(&args_refs)?;
+        concat_output = mixed;
+        if overlay_clips.is_empty() {
+            concat_list = temp_dir.join("final.txt");
+            fs::write(&concat_list, format!("file '{output_path}'\n"))?;
+        }
+    }
+
+    if !overlay_clips.is_empty() {
+        let mut overlay_paths = Vec::new();
+        let mut windows = Vec::new();
+        for (j, clip) in overlay_clips.iter().enumerate() {
+            let start = timeline_to_output(clips, clip.start);
+            let seg_path = temp_dir.join(format!("ovl_{j:04}.mp4"));
+            let args = build_segment_args(clip, input_path, assets, settings, start, &seg_path.to_string_lossy());
+            let passes = if settings.two_pass {
+                let passlog = temp_dir.join(format!("ovl_pass_{j:04}"));
+                build_two_pass_args(&args, &passlog.to_string_lossy(), null_device(cfg!(windows))).to_vec()
+            } else {
+                vec![args]
+            };
+            for args in passes {
+                let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                run_ffmpeg(&args_refs)?;
+            }
+            overlay_paths.push(seg_path.to_string_lossy().to_string());
+            windows.push((start, start + clip.output_duration()));
+        }
+        let overlay_refs: Vec<&Clip> = overlay_clips.iter().collect();
+        let args = build_composite_args(&concat_output, &overlay_paths, &windows, &overlay_refs, settings, output_path);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        run_ffmpeg(&args_refs)?;
+        // Dodatkowe wyjscia koduja gotowy plik z nakladkami
+        concat_list = temp_dir.join("final.txt");
+        fs::write(&concat_list, format!("file '{output_path}'\n"))?;
+    }
+
+    if let Some(watermark) = watermark {
+        let (_, width, height, ..) = get_video_info_ffprobe(output_path)?;
+        let args = build_watermark_args(output_path, watermark, width, height, settings, composed_path);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        run_ffmpeg(&args_refs)?;
+        // Dodatkowe wyjscia koduja film z logo
+        concat_list = temp_dir.join("final.txt");
+        fs::write(&concat_list, format!("file '{composed_path}'\n"))?;
+    }
+
+    // Dodatkowe formaty kodowane z tej samej listy segmentow (przed sprzataniem temp)
+    let multi_result = if settings.multi_output.enabled && !settings.multi_output.formats.is_empty() {
+        render_multi_output(&concat_list, &settings.multi_output.formats, progress)
+    } else {
+        Ok(())
+    };
+
+    let extract_result = if tracks == TrackType::Both {
+        Ok(())
+    } else {
+        let args = build_track_extract_args(composed_path, tracks, target_path);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        run_ffmpeg(&args_refs)
+    };
 
     // Cleanup
     let _ = fs::remove_dir_all(&temp_dir);
-    
-    Ok(())
+    extract_result?;
+
+    // Napisy z czasami po montazu obok pliku wyjsciowego
+    if settings.write_srt && !subtitles.is_empty() {
+        let srt_path = Path::new(target_path).with_extension("srt");
+        write_srt(&srt_path, &retime_subtitles(subtitles, clips))?;
+    }
+
+    multi_result
 }
@@ -0,0 +1,99 @@
+// lock.rs - Blokada pliku projektu (.lock) przed edycja z dwoch procesow naraz
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Zawartosc pliku blokady: kto i kiedy otworzyl projekt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockInfo {
+    pub pid: u32,
+    /// Czas zalozenia blokady (sekundy od epoki Unix)
+    pub timestamp: i64,
+}
+
+impl LockInfo {
+    fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let timestamp = lines.next().and_then(|l| l.trim().parse().ok()).unwrap_or(0);
+        Some(Self { pid, timestamp })
+    }
+}
+
+/// Blokada `{projekt}.lock` z PID i czasem; zwalniana (plik usuwany) przy drop
+pub struct ProjectLock {
+    path: PathBuf,
+}
+
+impl ProjectLock {
+    pub fn lock_path(project: &Path) -> PathBuf {
+        let mut name = project.as_os_str().to_owned();
+        name.push(".lock");
+        PathBuf::from(name)
+    }
+
+    /// Blokada trzymana przez inny, dzialajacy proces. Blokada martwego procesu
+    /// (po awarii) jest po cichu usuwana.
+    pub fn holder(project: &Path) -> Option<LockInfo> {
+        let path = Self::lock_path(project);
+        let info = std::fs::read_to_string(&path).ok().and_then(|c| LockInfo::parse(&c));
+        match info {
+            Some(info) if info.pid == std::process::id() => None,
+            Some(info) if process_alive(info.pid) => Some(info),
+            _ => {
+                let _ = std::fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Zaklada blokade; blad, gdy projekt jest otwarty w innej instancji
+    pub fn acquire(project: &Path) -> Result<ProjectLock> {
+        if let Some(info) = Self::holder(project) {
+            return Err(anyhow!("Projekt jest otwarty w innej instancji (PID {})", info.pid));
+        }
+        Self::force_acquire(project)
+    }
+
+    /// Zaklada blokade, nadpisujac blokade innej instancji ("otworz mimo to")
+    pub fn force_acquire(project: &Path) -> Result<ProjectLock> {
+        let path = Self::lock_path(project);
+        let content = format!("{}\n{}\n", std::process::id(), chrono::Utc::now().timestamp());
+        std::fs::write(&path, content).with_context(|| format!("Nie mozna zapisac blokady {}", path.display()))?;
+        Ok(ProjectLock { path })
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // Nie usuwamy blokady, ktora w miedzyczasie przejela inna instancja
+        let ours = std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|c| LockInfo::parse(&c))
+            .is_some_and(|info| info.pid == std::process::id());
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Czy proces o danym PID nadal dziala
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    Path::new(&format!("/proc/{pid}")).exists()
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+        .output()
+        .is_ok_and(|out| String::from_utf8_lossy(&out.stdout).contains(&pid.to_string()))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
@@ -2,20 +2,34 @@ use anyhow::{anyhow, Result};
 
 mod types;
 mod ffmpeg;
+mod subtitle;
+mod migration;
+mod editing;
+mod export;
+mod scripting;
+mod lock;
 // mod i18n; 
 mod utils; 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use eframe::egui;
 use egui::load::SizedTexture;
 use serde::{Deserialize, Serialize};
-use std::collections::{VecDeque, HashMap};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Read;
 use std::fs;
 use crate::types::*;
 use crate::ffmpeg::*;
 use crate::utils::*;
+use crate::subtitle::{load_srt, SubtitleEntry};
+use crate::editing::{
+    chop_clip, duplicate_clip, effective_audio_enabled, fill_gaps, group_members, next_clip_start, next_group_id, project_stats, remove_source_range,
+    restrict_to_range, ripple_insert, ripple_move, source_to_timeline, split_clip_at, trim_clip_end, trim_clip_start,
+};
+use crate::export::{archive_project, export_render_script, write_chapters_for_output, ArchiveProgress};
+use crate::scripting::{parse_script_response, run_script, validate_script_clips, ScriptResponse};
+use crate::migration::CURRENT_PROJECT_VERSION;
+use crate::lock::{LockInfo, ProjectLock};
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::Instant;
 use std::process::Stdio;
 use std::sync::{
@@ -46,6 +60,14 @@ fn main() -> Result<()> {
     let mut options = eframe::NativeOptions::default();
     // Set icon
     options.viewport.icon = Some(Arc::new(load_icon()));
+    // Okno w rozmiarze i miejscu z poprzedniej sesji
+    let layout = load_settings().window_layout;
+    options.viewport = options.viewport.with_inner_size(layout.window_size);
+    if let Some(pos) = layout.window_pos {
+        options.viewport = options.viewport.with_position(pos);
+    }
+    // Motyw ustawia aplikacja (Settings.theme), takze w trybie "System"
+    options.follow_system_theme = false;
     
     if let Err(err) = eframe::run_native(
         "RustyCut",
@@ -80,6 +102,112 @@ impl std::fmt::Display for HwAccelMode {
     }
 }
 
+/// Sciezka wyjsciowa pokazywana w UI. Rozwiniecie szablonu skanuje katalog ({seq}),
+/// wiec wynik jest trzymany do zmiany szablonu, projektu lub katalogu (i do startu renderu)
+struct OutputPathPreview {
+    /// (szablon wlaczony, szablon, nazwa projektu, sciezka/katalog wyjscia, glowne wejscie,
+    /// biezaca sekunda - tokeny {date}/{time} zmieniaja sie z czasem)
+    key: (bool, String, String, String, String, String),
+    path: String,
+}
+
+/// Render wstrzymany do decyzji uzytkownika - plik wyjsciowy juz istnieje
+struct PendingRenderConfirmation {
+    output_path: String,
+}
+
+/// Watek wykrywania ciszy; wynik to przedzialy (start, end) w czasie zrodla
+type SilenceJob = thread::JoinHandle<Result<Vec<(f32, f32)>>>;
+
+/// Watek wykrywania rytmu; wynik to (tempo BPM, uderzenia w czasie timeline)
+type BeatJob = thread::JoinHandle<Result<(Option<f32>, Vec<f32>)>>;
+
+/// Wynik przycinania ciszy: (indeks klipu, zakres zrodla przed, nowy zakres)
+type SilenceTrim = (usize, (f32, f32), Result<(f32, f32)>);
+type TrimSilenceJob = thread::JoinHandle<Vec<SilenceTrim>>;
+
+/// Watek analizy fazy; wynik to (indeks klipu, srednia korelacja L/R)
+type PhaseJob = thread::JoinHandle<Vec<(usize, f32)>>;
+
+/// Podstawowe informacje o pliku przeciaganym nad oknem (przed upuszczeniem)
+struct DropProbe {
+    duration: f32,
+    width: u32,
+    height: u32,
+    info: MediaInfo,
+    size_bytes: u64,
+}
+
+/// Watek ffprobe pliku przeciaganego nad oknem
+type DropProbeJob = thread::JoinHandle<Result<DropProbe>>;
+
+/// Wynik ffprobe w tle: (dlugosc, szer., wys., fps, bitrate) + szczegoly strumieni
+type ProbeResult = Result<((f32, u32, u32, f32, f32), Result<MediaInfo>)>;
+
+/// Watek pakowania projektu do archiwum ZIP (sciezka archiwum w wyniku)
+type ArchiveJob = thread::JoinHandle<Result<PathBuf>>;
+
+/// Watek szukania brakujacych mediow: (szukane sciezki, trafienia wg nazwy pliku)
+type MediaSearchJob = thread::JoinHandle<(Vec<String>, HashMap<String, PathBuf>)>;
+
+/// Watek importu katalogu (ffprobe kazdego pliku): (katalog, znalezione media)
+type ImportFolderJob = thread::JoinHandle<(PathBuf, Result<Vec<MediaAsset>>)>;
+
+/// Watek skryptu uzytkownika (sciezka skryptu + odpowiedz)
+type ScriptJob = (PathBuf, thread::JoinHandle<Result<ScriptResponse>>);
+
+/// Wynik podgladu z watku w tle; `generation` odrzuca wyniki nieaktualnych zadan
+struct PreviewResult {
+    generation: u64,
+    frame: Result<Vec<u8>>,
+    /// Surowa klatka (bez filtrow) dla trybu A/B
+    raw: Option<Vec<u8>>,
+}
+
+/// Uruchomiony strumien audio: publikowany w chwili `stream.play()`, wiec watek wideo
+/// (startujacy wczesniej) widzi format urzadzenia biezacego odtwarzania
+#[derive(Clone, Copy)]
+struct AudioClockStart {
+    wall_clock_start: Instant,
+    /// PTS audio (s na osi czasu) w chwili startu odtwarzania
+    audio_pts_start: f64,
+    sample_rate: u32,
+    channels: u16,
+}
+
+/// Zegar audio odtwarzania. Glownym zrodlem jest czas sciany od uruchomienia strumienia
+/// (odporny na nieregularne callbacki cpal i niedobory bufora), licznik probek jest
+/// zrodlem pomocniczym - dopuszczamy go, dopoki nie odbiega od zegara sciany.
+/// `audio_clock_correction` przelicza probki na czas sciany (tak jest mierzona), wiec
+/// dotyczy tylko licznika; dlugofalowy dryf, ktory kompensowala, zegar sciany juz pomija.
+#[derive(Clone)]
+struct AvClock {
+    /// None = audio jeszcze nie gra
+    start: Arc<Mutex<Option<AudioClockStart>>>,
+    samples_played: Arc<AtomicU64>,
+    correction: f32,
+}
+
+impl AvClock {
+    /// Maksymalna rozbieznosc licznika probek od zegara sciany (s)
+    const MAX_DRIFT: f64 = 0.04;
+
+    /// Czas odtwarzania od startu (s); None przed uruchomieniem strumienia audio
+    fn elapsed(&self) -> Option<f64> {
+        let start = (*self.start.lock().ok()?)?;
+        let wall = start.wall_clock_start.elapsed().as_secs_f64();
+        let played = self.samples_played.load(Ordering::Relaxed) as f64;
+        let samples = played / (start.sample_rate.max(1) as f64 * start.channels.max(1) as f64) * self.correction as f64;
+        // Niedobor (licznik stoi) i nadmiar (callback z zapasem) obcinamy do zegara sciany
+        Some(samples.clamp(wall - Self::MAX_DRIFT, wall + Self::MAX_DRIFT))
+    }
+
+    /// Biezacy PTS audio (s na osi czasu)
+    fn audio_pts(&self) -> Option<f64> {
+        let pts_start = self.start.lock().ok()?.as_ref()?.audio_pts_start;
+        self.elapsed().map(|t| pts_start + t)
+    }
+}
 
 #[allow(dead_code)]
 struct TextResources {
@@ -93,27 +221,171 @@ struct TextResources {
     mark_in: String,
     mark_out: String,
     add_clip: String,
+    ripple_insert: String,
     split_clip: String,
     remove_clip: String,
+    properties_button: String,
+    clip_properties_title: String,
+    no_clip_selected: String,
+    missing_media_title: String,
+    missing_media_info: String,
+    locate_button: String,
+    goto_title: String,
+    goto_hint: String,
     // Tools
     editor_title: String,
     input_file: String,
     output_file: String,
+    use_template: String,
+    template_hint: String,
     duration_label: String,
     auto_ffprobe: String,
     create_full_clip: String,
     tools_label: String,
     tool_hand: String,
     tool_scissors: String,
+    tool_envelope: String,
     live_preview: String,
     ripple_delete: String,
     render_button: String,
+    render_settings_label: String,
+    burn_timecode: String,
+    output_resolution: String,
+    aspect_mode: String,
+    compare_ab: String,
+    histogram: String,
+    snap_frames: String,
+    frame_label: String,
+    frame_edit_hint: String,
+    estimated_size: String,
+    estimate_note: String,
+    extra_outputs: String,
+    import_srt: String,
+    apply_color_selected: String,
+    fill_gaps: String,
+    detect_phase: String,
+    export_render_script: String,
+    export_project_archive: String,
+    max_cache_size: String,
+    import_folder: String,
+    project_stats: String,
+    clear_mark_in: String,
+    probing_media: String,
+    clear_mark_out: String,
+    group_clips: String,
+    link_clip: String,
+    unlink_clip: String,
+    drop_probing: String,
+    drop_duration: String,
+    drop_video: String,
+    drop_audio: String,
+    drop_file_size: String,
+    split_at_playhead: String,
+    duplicate_clip: String,
+    reverse_clip: String,
+    unreverse_clip: String,
+    trim_to_playhead_left: String,
+    trim_to_playhead_right: String,
+    fill_to_next_clip: String,
+    color_label: String,
+    move_to_track: String,
+    two_pass: String,
+    help_menu: String,
+    remap_media: String,
+    remap_media_title: String,
+    remap_media_empty: String,
+    remap_not_found: String,
+    auto_search: String,
+    auto_search_missing: String,
+    add_search_folder: String,
+    searching_media: String,
+    drop_frame_hint: String,
+    about_title: String,
+    about_tagline: String,
+    about_source: String,
+    about_license: String,
+    copy_to_clipboard: String,
+    editing_label: String,
+    playback_label: String,
+    playback_fps_limit: String,
+    audio_device_label: String,
+    system_default: String,
+    tool_paths_label: String,
+    auto_save_interval: String,
+    auto_save_note: String,
+    rename_group: String,
+    ungroup: String,
+    select_group: String,
+    group_default_name: String,
+    choose_output_file: String,
+    choose_output_folder: String,
+    will_render_to: String,
+    media_info: String,
+    stats_clips: String,
+    stats_cut_duration: String,
+    stats_gaps: String,
+    stats_faded: String,
+    stats_average: String,
+    stats_render_estimate: String,
+    stats_source_used: String,
+    av_sync_label: String,
+    calibrate_av_sync: String,
+    auto_arrange_title: String,
+    auto_arrange_question: String,
+    archiving_project: String,
+    view_menu: String,
+    tools_menu: String,
+    run_script: String,
+    secondary_preview: String,
+    overwrite_title: String,
+    overwrite_question: String,
+    yes: String,
+    no: String,
+    auto_rename: String,
+    silence_label: String,
+    detect_silence: String,
+    silence_threshold: String,
+    silence_min_duration: String,
+    split_here: String,
+    remove_range: String,
+    beats_label: String,
+    detect_bpm: String,
+    bpm_label: String,
+    split_at_beats: String,
+    clear_beats: String,
+    storyboard: String,
+    storyboard_columns: String,
+    clip_list: String,
+    waveform_amplitude: String,
+    waveform_amplitude_hint: String,
+    stretch_label: String,
+    iframe_seek: String,
+    seek_note: String,
+    rubberband_note: String,
+    project_too_new: String,
+    project_locked_title: String,
+    project_locked_message: String,
+    open_read_only: String,
+    force_open: String,
+    project_read_only: String,
+    write_srt: String,
     // Status
     status_ready: String,
     status_render_done: String,
     status_new_project: String,
     status_project_loaded: String,
     status_project_saved: String,
+    status_rendering: String,
+    status_render_failed: String,
+    status_queue_running: String,
+    status_detecting_silence: String,
+    status_importing_folder: String,
+    trim_silence: String,
+    trim_all_silence: String,
+    trim_silence_none: String,
+    chop_parts: String,
+    chop_clip: String,
+    status_trimming_silence: String,
     // Errors
     err_mark_out_greater: String,
     err_set_marks: String,
@@ -126,7 +398,43 @@ struct TextResources {
     loading_change_lang: String,
     // Settings
     settings_title: String,
+    add_to_queue: String,
+    add_to_queue_hint: String,
+    render_queue_title: String,
+    render_queue_empty: String,
+    start_queue: String,
+    clear_finished: String,
+    queue_pending: String,
+    queue_done: String,
+    queue_failed: String,
+    theme_label: String,
+    caching_label: String,
+    use_cache_copy: String,
+    add_marker: String,
+    marker_default_name: String,
+    markers_label: String,
+    markers_empty: String,
+    write_webvtt_chapters: String,
+    export_video_only: String,
+    export_audio_only: String,
+    export_separate_tracks: String,
+    auto_fill_gaps: String,
+    render_range_only: String,
+    render_range_only_hint: String,
+    watermark: String,
+    watermark_choose: String,
+    watermark_position: String,
+    watermark_opacity: String,
+    watermark_scale: String,
+    preview_fit_hint: String,
+    shortcuts_label: String,
+    record_shortcut: String,
+    press_key: String,
+    reset_shortcuts: String,
+    accent_color_label: String,
     language_label: String,
+    thumbnails_label: String,
+    regenerate_thumbnails: String,
     // Generic
     no_preview: String,
     no_duration: String,
@@ -145,25 +453,169 @@ impl TextResources {
                 mark_in: "Mark In".to_owned(),
                 mark_out: "Mark Out".to_owned(),
                 add_clip: "Add Clip".to_owned(),
+                ripple_insert: "Ripple Insert".to_owned(),
                 split_clip: "Split Clip".to_owned(),
                 remove_clip: "Remove Clip".to_owned(),
+                properties_button: "Properties".to_owned(),
+                clip_properties_title: "Clip Properties".to_owned(),
+                no_clip_selected: "No clip selected".to_owned(),
+                missing_media_title: "Missing Media".to_owned(),
+                missing_media_info: "Some source files could not be found:".to_owned(),
+                locate_button: "Locate...".to_owned(),
+                goto_title: "Go to…".to_owned(),
+                goto_hint: "Enter clip # or timecode (HH:MM:SS:FF)".to_owned(),
                 editor_title: "Video Editor".to_owned(),
                 input_file: "Input File:".to_owned(),
                 output_file: "Output File:".to_owned(),
+                use_template: "Use template".to_owned(),
+                template_hint: "Tokens: {date}, {time}, {project}, {seq:03}".to_owned(),
                 duration_label: "Duration (s):".to_owned(),
                 auto_ffprobe: "Auto (ffprobe)".to_owned(),
                 create_full_clip: "Create Full Clip".to_owned(),
                 tools_label: "Tools:".to_owned(),
                 tool_hand: "Hand".to_owned(),
                 tool_scissors: "Blade".to_owned(),
+                tool_envelope: "Envelope".to_owned(),
                 live_preview: "Live Preview".to_owned(),
                 ripple_delete: "Ripple Delete".to_owned(),
                 render_button: "RENDER VIDEO".to_owned(),
+                render_settings_label: "Render Settings".to_owned(),
+                burn_timecode: "Burn-in timecode".to_owned(),
+                output_resolution: "Output resolution".to_owned(),
+                aspect_mode: "Aspect ratio".to_owned(),
+                compare_ab: "A/B Compare".to_owned(),
+                histogram: "Histogram".to_owned(),
+                extra_outputs: "Extra outputs".to_owned(),
+                snap_frames: "Snap to frames".to_owned(),
+                frame_label: "Frame".to_owned(),
+                frame_edit_hint: "Click to enter a frame number".to_owned(),
+                estimated_size: "Estimated output size".to_owned(),
+                estimate_note: "(estimate only)".to_owned(),
+                import_srt: "Import SRT...".to_owned(),
+                apply_color_selected: "Apply color to all selected".to_owned(),
+                fill_gaps: "Fill gaps with black".to_owned(),
+                detect_phase: "Detect phase issues".to_owned(),
+                export_render_script: "Export render script...".to_owned(),
+                export_project_archive: "Export project archive...".to_owned(),
+                max_cache_size: "Max cache size".to_owned(),
+                import_folder: "📁 Import Folder".to_owned(),
+                project_stats: "Project Stats".to_owned(),
+                clear_mark_in: "Clear mark in".to_owned(),
+                probing_media: "Reading file info...".to_owned(),
+                clear_mark_out: "Clear mark out".to_owned(),
+                group_clips: "Group selected clips".to_owned(),
+                link_clip: "Unlinked: drag video/audio edges to trim them separately. Click to link".to_owned(),
+                unlink_clip: "Linked: video and audio move and trim together. Click to unlink".to_owned(),
+                drop_probing: "Probing...".to_owned(),
+                drop_duration: "Duration".to_owned(),
+                drop_video: "Video".to_owned(),
+                drop_audio: "Audio".to_owned(),
+                drop_file_size: "File size".to_owned(),
+                split_at_playhead: "Split at playhead".to_owned(),
+                duplicate_clip: "Duplicate".to_owned(),
+                reverse_clip: "Reverse".to_owned(),
+                unreverse_clip: "Play forward".to_owned(),
+                trim_to_playhead_left: "Trim to playhead (left)".to_owned(),
+                trim_to_playhead_right: "Trim to playhead (right)".to_owned(),
+                fill_to_next_clip: "Fill to next clip".to_owned(),
+                color_label: "Color label".to_owned(),
+                move_to_track: "Move to track".to_owned(),
+                two_pass: "Two-pass encoding (target bitrate)".to_owned(),
+                help_menu: "Help".to_owned(),
+                remap_media: "Remap media...".to_owned(),
+                remap_media_title: "Remap Media".to_owned(),
+                remap_media_empty: "The project has no source files.".to_owned(),
+                remap_not_found: "not found".to_owned(),
+                auto_search: "Auto-search".to_owned(),
+                auto_search_missing: "Auto-search all missing".to_owned(),
+                add_search_folder: "Add search folder...".to_owned(),
+                searching_media: "Searching...".to_owned(),
+                drop_frame_hint: "Drop-frame timecode (HH:MM:SS;FF) for 29.97 / 59.94 fps".to_owned(),
+                about_title: "About RustyCut".to_owned(),
+                about_tagline: "Lightweight video editor written in Rust, powered by FFmpeg.".to_owned(),
+                about_source: "Source code".to_owned(),
+                about_license: "License".to_owned(),
+                copy_to_clipboard: "Copy to clipboard".to_owned(),
+                editing_label: "Editing:".to_owned(),
+                playback_label: "Playback:".to_owned(),
+                playback_fps_limit: "Max playback FPS".to_owned(),
+                audio_device_label: "Audio output:".to_owned(),
+                system_default: "System default".to_owned(),
+                tool_paths_label: "Tool paths (empty = from PATH):".to_owned(),
+                auto_save_interval: "Auto-save interval".to_owned(),
+                auto_save_note: "Writes {project}.autosave.rev next to the saved project; 0 = off".to_owned(),
+                rename_group: "Rename group".to_owned(),
+                ungroup: "Ungroup".to_owned(),
+                select_group: "Select group".to_owned(),
+                group_default_name: "Group".to_owned(),
+                choose_output_file: "Save as file...".to_owned(),
+                choose_output_folder: "Choose folder...".to_owned(),
+                will_render_to: "Will render to:".to_owned(),
+                media_info: "Media Info".to_owned(),
+                stats_clips: "Clips".to_owned(),
+                stats_cut_duration: "Cut duration".to_owned(),
+                stats_gaps: "Gaps".to_owned(),
+                stats_faded: "Clips with fades".to_owned(),
+                stats_average: "Average clip".to_owned(),
+                stats_render_estimate: "Render estimate".to_owned(),
+                stats_source_used: "Source used".to_owned(),
+                av_sync_label: "A/V sync correction:".to_owned(),
+                calibrate_av_sync: "Calibrate A/V sync".to_owned(),
+                auto_arrange_title: "Auto-arrange on timeline".to_owned(),
+                auto_arrange_question: "Place the imported files one after another on the timeline (sorted by name)?".to_owned(),
+                archiving_project: "Archiving project".to_owned(),
+                view_menu: "View".to_owned(),
+                tools_menu: "Tools".to_owned(),
+                run_script: "Run script...".to_owned(),
+                secondary_preview: "Secondary preview window".to_owned(),
+                overwrite_title: "File exists".to_owned(),
+                overwrite_question: "Output file already exists. Overwrite?".to_owned(),
+                yes: "Yes".to_owned(),
+                no: "No".to_owned(),
+                auto_rename: "Auto-rename".to_owned(),
+                silence_label: "Silence Detection".to_owned(),
+                detect_silence: "Detect Silence".to_owned(),
+                silence_threshold: "Threshold (dB)".to_owned(),
+                silence_min_duration: "Min duration (s)".to_owned(),
+                split_here: "Split here".to_owned(),
+                remove_range: "Remove this range".to_owned(),
+                beats_label: "Beats".to_owned(),
+                detect_bpm: "Detect BPM".to_owned(),
+                bpm_label: "BPM (0 = auto)".to_owned(),
+                split_at_beats: "Split clips at beats".to_owned(),
+                clear_beats: "Clear beats".to_owned(),
+                storyboard: "Storyboard".to_owned(),
+                storyboard_columns: "Columns".to_owned(),
+                clip_list: "Clip list".to_owned(),
+                waveform_amplitude: "Waveform amplitude".to_owned(),
+                waveform_amplitude_hint: "Waveform scaling: Auto picks it from the audio peak, Normalized makes quiet audio visible, dBFS boosts so the threshold fills the height".to_owned(),
+                stretch_label: "Audio stretch".to_owned(),
+                iframe_seek: "I-frame seek".to_owned(),
+                seek_note: "I-frame seek: faster, less accurate vs. Exact seek: slower, frame-accurate".to_owned(),
+                rubberband_note: "Requires ffmpeg built with librubberband".to_owned(),
+                project_too_new: "This project was created with a newer version of RustyCut".to_owned(),
+                project_locked_title: "Project in use".to_owned(),
+                project_locked_message: "This project is already open in another instance. Open read-only or force-open?".to_owned(),
+                open_read_only: "Open read-only".to_owned(),
+                force_open: "Force open".to_owned(),
+                project_read_only: "Project is open read-only - save it under a different name.".to_owned(),
+                write_srt: "Write SRT alongside output".to_owned(),
                 status_ready: "Ready.".to_owned(),
                 status_render_done: "Render finished.".to_owned(),
                 status_new_project: "New project created.".to_owned(),
                 status_project_loaded: "Project loaded.".to_owned(),
                 status_project_saved: "Project saved.".to_owned(),
+                status_rendering: "Rendering...".to_owned(),
+                status_render_failed: "Render failed".to_owned(),
+                status_queue_running: "Rendering queue...".to_owned(),
+                status_detecting_silence: "Detecting silence...".to_owned(),
+                status_importing_folder: "Importing folder...".to_owned(),
+                trim_silence: "Trim silence".to_owned(),
+                trim_all_silence: "Trim all clips".to_owned(),
+                trim_silence_none: "No silence to trim at clip ends".to_owned(),
+                chop_parts: "Number of parts".to_owned(),
+                chop_clip: "Chop".to_owned(),
+                status_trimming_silence: "Trimming silence...".to_owned(),
                 err_mark_out_greater: "Mark Out must be > Mark In.".to_owned(),
                 err_set_marks: "Set Mark In and Mark Out first.".to_owned(),
                 err_playhead_inside: "Playhead must be inside a clip.".to_owned(),
@@ -173,7 +625,43 @@ impl TextResources {
                 err_no_clip_cursor: "No clip under cursor.".to_owned(),
                 loading_change_lang: "Changing language...".to_owned(),
                 settings_title: "Settings".to_owned(),
+                add_to_queue: "Add to queue".to_owned(),
+                add_to_queue_hint: "Queues the saved project file with the current output path and render settings".to_owned(),
+                render_queue_title: "Render queue".to_owned(),
+                render_queue_empty: "The queue is empty.".to_owned(),
+                start_queue: "Start queue".to_owned(),
+                clear_finished: "Clear finished".to_owned(),
+                queue_pending: "Pending".to_owned(),
+                queue_done: "Done".to_owned(),
+                queue_failed: "Failed".to_owned(),
+                theme_label: "Theme".to_owned(),
+                caching_label: "Caching".to_owned(),
+                use_cache_copy: "Use local cache copy of source files (slow / network drives)".to_owned(),
+                add_marker: "Add marker".to_owned(),
+                marker_default_name: "Chapter".to_owned(),
+                markers_label: "Markers".to_owned(),
+                markers_empty: "No markers (M adds one at the playhead).".to_owned(),
+                write_webvtt_chapters: "Write WebVTT chapters (.vtt) alongside output".to_owned(),
+                export_video_only: "Export video only (no audio)".to_owned(),
+                export_audio_only: "Export audio only (no video)".to_owned(),
+                export_separate_tracks: "Export both as separate files (.video.mp4 + .audio.aac)".to_owned(),
+                auto_fill_gaps: "Auto-fill gaps with black/silence".to_owned(),
+                render_range_only: "Render only mark in / mark out range".to_owned(),
+                render_range_only_hint: "Clips outside the range are skipped and clips on its edges are trimmed; needs both marks".to_owned(),
+                watermark: "Watermark:".to_owned(),
+                watermark_choose: "Choose image...".to_owned(),
+                watermark_position: "Position".to_owned(),
+                watermark_opacity: "Opacity".to_owned(),
+                watermark_scale: "Scale".to_owned(),
+                preview_fit_hint: "Preview scaling: Fit / Fill (crop) / 1:1 (drag to pan) / Stretch".to_owned(),
+                shortcuts_label: "Keyboard shortcuts".to_owned(),
+                record_shortcut: "Record shortcut (Esc cancels)".to_owned(),
+                press_key: "Press a key...".to_owned(),
+                reset_shortcuts: "Restore default shortcuts".to_owned(),
+                accent_color_label: "Accent color".to_owned(),
                 language_label: "Language".to_owned(),
+                thumbnails_label: "Timeline Thumbnails".to_owned(),
+                regenerate_thumbnails: "Regenerate Thumbnails".to_owned(),
                 no_preview: "No preview".to_owned(),
                 no_duration: "No material duration".to_owned(),
             },
@@ -186,25 +674,169 @@ impl TextResources {
                 mark_in: "Mark In".to_owned(),
                 mark_out: "Mark Out".to_owned(),
                 add_clip: "Dodaj klip".to_owned(),
+                ripple_insert: "Wstaw z przesunieciem".to_owned(),
                 split_clip: "Podziel klip".to_owned(),
                 remove_clip: "Usuń klip".to_owned(),
+                properties_button: "Właściwości".to_owned(),
+                clip_properties_title: "Właściwości klipu".to_owned(),
+                no_clip_selected: "Nie wybrano klipu".to_owned(),
+                missing_media_title: "Brakujące pliki".to_owned(),
+                missing_media_info: "Nie znaleziono niektórych plików źródłowych:".to_owned(),
+                locate_button: "Wskaż...".to_owned(),
+                goto_title: "Przejdź do…".to_owned(),
+                goto_hint: "Podaj nr klipu lub timecode (HH:MM:SS:FF)".to_owned(),
                 editor_title: "Edytor Wideo".to_owned(),
                 input_file: "Plik wejściowy:".to_owned(),
                 output_file: "Plik wyjściowy:".to_owned(),
+                use_template: "Użyj szablonu".to_owned(),
+                template_hint: "Tokeny: {date}, {time}, {project}, {seq:03}".to_owned(),
                 duration_label: "Długość (s):".to_owned(),
                 auto_ffprobe: "Auto (ffprobe)".to_owned(),
                 create_full_clip: "Utwórz cały klip".to_owned(),
                 tools_label: "Narzędzia:".to_owned(),
                 tool_hand: "Ręka".to_owned(),
                 tool_scissors: "Nożyczki".to_owned(),
+                tool_envelope: "Obwiednia".to_owned(),
                 live_preview: "Podgląd live".to_owned(),
                 ripple_delete: "Ripple Delete (Auto-przesuwanie)".to_owned(),
                 render_button: "RENDERUJ FILM".to_owned(),
+                render_settings_label: "Ustawienia renderu".to_owned(),
+                burn_timecode: "Wypal timecode".to_owned(),
+                output_resolution: "Rozdzielczosc wyjsciowa".to_owned(),
+                aspect_mode: "Proporcje obrazu".to_owned(),
+                compare_ab: "Porownanie A/B".to_owned(),
+                histogram: "Histogram".to_owned(),
+                extra_outputs: "Dodatkowe wyjscia".to_owned(),
+                snap_frames: "Przyciagaj do klatek".to_owned(),
+                frame_label: "Klatka".to_owned(),
+                frame_edit_hint: "Kliknij, aby wpisac numer klatki".to_owned(),
+                estimated_size: "Szacowany rozmiar pliku".to_owned(),
+                estimate_note: "(tylko szacunek)".to_owned(),
+                import_srt: "Importuj SRT...".to_owned(),
+                apply_color_selected: "Kolor dla zaznaczonych".to_owned(),
+                fill_gaps: "Wypelnij luki czernia".to_owned(),
+                detect_phase: "Wykryj problemy z faza".to_owned(),
+                export_render_script: "Eksportuj skrypt renderu...".to_owned(),
+                export_project_archive: "Eksportuj archiwum projektu...".to_owned(),
+                max_cache_size: "Maks. rozmiar cache".to_owned(),
+                import_folder: "📁 Importuj folder".to_owned(),
+                project_stats: "Statystyki projektu".to_owned(),
+                clear_mark_in: "Usun mark in".to_owned(),
+                probing_media: "Odczyt informacji o pliku...".to_owned(),
+                clear_mark_out: "Usun mark out".to_owned(),
+                group_clips: "Grupuj zaznaczone klipy".to_owned(),
+                link_clip: "Rozlaczony: przeciagnij krawedz obrazu/dzwieku, aby przyciac osobno. Kliknij, aby polaczyc".to_owned(),
+                unlink_clip: "Polaczony: obraz i dzwiek przesuwaja sie i przycinaja razem. Kliknij, aby rozlaczyc".to_owned(),
+                drop_probing: "Odczyt pliku...".to_owned(),
+                drop_duration: "Dlugosc".to_owned(),
+                drop_video: "Wideo".to_owned(),
+                drop_audio: "Audio".to_owned(),
+                drop_file_size: "Rozmiar pliku".to_owned(),
+                split_at_playhead: "Podziel na playheadzie".to_owned(),
+                duplicate_clip: "Duplikuj".to_owned(),
+                reverse_clip: "Odwroc (wstecz)".to_owned(),
+                unreverse_clip: "Odtwarzaj do przodu".to_owned(),
+                trim_to_playhead_left: "Przytnij do playheada (lewo)".to_owned(),
+                trim_to_playhead_right: "Przytnij do playheada (prawo)".to_owned(),
+                fill_to_next_clip: "Wydluz do nastepnego klipu".to_owned(),
+                color_label: "Etykieta koloru".to_owned(),
+                move_to_track: "Przenies na sciezke".to_owned(),
+                two_pass: "Kodowanie dwuprzebiegowe (docelowy bitrate)".to_owned(),
+                help_menu: "Pomoc".to_owned(),
+                remap_media: "Przemapuj media...".to_owned(),
+                remap_media_title: "Przemapowanie mediow".to_owned(),
+                remap_media_empty: "Projekt nie ma plikow zrodlowych.".to_owned(),
+                remap_not_found: "nie znaleziono".to_owned(),
+                auto_search: "Szukaj automatycznie".to_owned(),
+                auto_search_missing: "Szukaj wszystkich brakujacych".to_owned(),
+                add_search_folder: "Dodaj folder do przeszukania...".to_owned(),
+                searching_media: "Szukanie...".to_owned(),
+                drop_frame_hint: "Timecode drop-frame (HH:MM:SS;FF) dla 29.97 / 59.94 fps".to_owned(),
+                about_title: "O programie RustyCut".to_owned(),
+                about_tagline: "Lekki edytor wideo napisany w Rust, oparty na FFmpeg.".to_owned(),
+                about_source: "Kod zrodlowy".to_owned(),
+                about_license: "Licencja".to_owned(),
+                copy_to_clipboard: "Kopiuj do schowka".to_owned(),
+                editing_label: "Edycja:".to_owned(),
+                playback_label: "Odtwarzanie:".to_owned(),
+                playback_fps_limit: "Maks. FPS odtwarzania".to_owned(),
+                audio_device_label: "Wyjscie audio:".to_owned(),
+                system_default: "Domyslne systemowe".to_owned(),
+                tool_paths_label: "Sciezki narzedzi (puste = z PATH):".to_owned(),
+                auto_save_interval: "Interwal autozapisu".to_owned(),
+                auto_save_note: "Zapisuje {projekt}.autosave.rev obok zapisanego projektu; 0 = wylaczone".to_owned(),
+                rename_group: "Zmien nazwe grupy".to_owned(),
+                ungroup: "Rozgrupuj".to_owned(),
+                select_group: "Zaznacz grupe".to_owned(),
+                group_default_name: "Grupa".to_owned(),
+                choose_output_file: "Zapisz jako plik...".to_owned(),
+                choose_output_folder: "Wybierz folder...".to_owned(),
+                will_render_to: "Render do:".to_owned(),
+                media_info: "Informacje o pliku".to_owned(),
+                stats_clips: "Klipy".to_owned(),
+                stats_cut_duration: "Dlugosc montazu".to_owned(),
+                stats_gaps: "Luki".to_owned(),
+                stats_faded: "Klipy z fade".to_owned(),
+                stats_average: "Sredni klip".to_owned(),
+                stats_render_estimate: "Szacowany render".to_owned(),
+                stats_source_used: "Wykorzystany material".to_owned(),
+                av_sync_label: "Korekta synchronizacji A/V:".to_owned(),
+                calibrate_av_sync: "Kalibruj synchronizacje A/V".to_owned(),
+                auto_arrange_title: "Uloz na osi czasu".to_owned(),
+                auto_arrange_question: "Ulozyc zaimportowane pliki jeden za drugim na osi czasu (wg nazwy)?".to_owned(),
+                archiving_project: "Pakowanie projektu".to_owned(),
+                view_menu: "Widok".to_owned(),
+                tools_menu: "Narzedzia".to_owned(),
+                run_script: "Uruchom skrypt...".to_owned(),
+                secondary_preview: "Drugie okno podgladu".to_owned(),
+                overwrite_title: "Plik istnieje".to_owned(),
+                overwrite_question: "Plik wyjsciowy juz istnieje. Nadpisac?".to_owned(),
+                yes: "Tak".to_owned(),
+                no: "Nie".to_owned(),
+                auto_rename: "Zmien nazwe".to_owned(),
+                silence_label: "Wykrywanie ciszy".to_owned(),
+                detect_silence: "Wykryj cisze".to_owned(),
+                silence_threshold: "Prog (dB)".to_owned(),
+                silence_min_duration: "Min. dlugosc (s)".to_owned(),
+                split_here: "Podziel tutaj".to_owned(),
+                remove_range: "Usun ten fragment".to_owned(),
+                beats_label: "Rytm".to_owned(),
+                detect_bpm: "Wykryj BPM".to_owned(),
+                bpm_label: "BPM (0 = auto)".to_owned(),
+                split_at_beats: "Podziel klipy na uderzeniach".to_owned(),
+                clear_beats: "Wyczysc uderzenia".to_owned(),
+                storyboard: "Storyboard".to_owned(),
+                storyboard_columns: "Kolumny".to_owned(),
+                clip_list: "Lista klipow".to_owned(),
+                waveform_amplitude: "Amplituda waveformu".to_owned(),
+                waveform_amplitude_hint: "Skalowanie waveformu: Auto dobiera je wg szczytu audio, Normalized pokazuje ciche audio, dBFS wzmacnia tak, by prog wypelnial wysokosc".to_owned(),
+                stretch_label: "Rozciaganie audio".to_owned(),
+                iframe_seek: "Seek do I-klatki".to_owned(),
+                seek_note: "Seek do I-klatki: szybciej, mniej dokladnie vs. Dokladny seek: wolniej, co do klatki".to_owned(),
+                rubberband_note: "Wymaga ffmpeg zbudowanego z librubberband".to_owned(),
+                project_too_new: "Ten projekt utworzono w nowszej wersji RustyCut".to_owned(),
+                project_locked_title: "Projekt w uzyciu".to_owned(),
+                project_locked_message: "Ten projekt jest juz otwarty w innej instancji. Otworzyc tylko do odczytu czy wymusic otwarcie?".to_owned(),
+                open_read_only: "Tylko do odczytu".to_owned(),
+                force_open: "Wymus otwarcie".to_owned(),
+                project_read_only: "Projekt otwarty tylko do odczytu - zapisz go pod inna nazwa.".to_owned(),
+                write_srt: "Zapisz SRT obok pliku wyjsciowego".to_owned(),
                 status_ready: "Gotowy.".to_owned(),
                 status_render_done: "Render zakończony.".to_owned(),
                 status_new_project: "Nowy projekt utworzony.".to_owned(),
                 status_project_loaded: "Projekt wczytany.".to_owned(),
                 status_project_saved: "Projekt zapisany.".to_owned(),
+                status_rendering: "Renderowanie...".to_owned(),
+                status_render_failed: "Blad renderu".to_owned(),
+                status_queue_running: "Renderowanie kolejki...".to_owned(),
+                status_detecting_silence: "Wykrywanie ciszy...".to_owned(),
+                status_importing_folder: "Importowanie folderu...".to_owned(),
+                trim_silence: "Przytnij cisze".to_owned(),
+                trim_all_silence: "Przytnij wszystkie klipy".to_owned(),
+                trim_silence_none: "Brak ciszy do przyciecia na koncach klipow".to_owned(),
+                chop_parts: "Liczba czesci".to_owned(),
+                chop_clip: "Potnij".to_owned(),
+                status_trimming_silence: "Przycinanie ciszy...".to_owned(),
                 err_mark_out_greater: "Mark Out musi być > Mark In.".to_owned(),
                 err_set_marks: "Ustaw najpierw Mark In i Mark Out.".to_owned(),
                 err_playhead_inside: "Głowica musi być wewnątrz klipu.".to_owned(),
@@ -214,7 +846,43 @@ impl TextResources {
                 err_no_clip_cursor: "Brak klipu pod kursorem.".to_owned(),
                 loading_change_lang: "Zmieniam język...".to_owned(),
                 settings_title: "Ustawienia".to_owned(),
+                add_to_queue: "Dodaj do kolejki".to_owned(),
+                add_to_queue_hint: "Dodaje zapisany plik projektu z biezaca sciezka wyjscia i ustawieniami renderu".to_owned(),
+                render_queue_title: "Kolejka renderu".to_owned(),
+                render_queue_empty: "Kolejka jest pusta.".to_owned(),
+                start_queue: "Uruchom kolejke".to_owned(),
+                clear_finished: "Usun zakonczone".to_owned(),
+                queue_pending: "Oczekuje".to_owned(),
+                queue_done: "Gotowe".to_owned(),
+                queue_failed: "Blad".to_owned(),
+                theme_label: "Motyw".to_owned(),
+                caching_label: "Kopiowanie do cache".to_owned(),
+                use_cache_copy: "Uzywaj lokalnej kopii plikow zrodlowych (wolne / sieciowe dyski)".to_owned(),
+                add_marker: "Dodaj znacznik".to_owned(),
+                marker_default_name: "Rozdzial".to_owned(),
+                markers_label: "Znaczniki".to_owned(),
+                markers_empty: "Brak znacznikow (M dodaje znacznik na playheadzie).".to_owned(),
+                write_webvtt_chapters: "Zapisz rozdzialy WebVTT (.vtt) obok pliku wyjsciowego".to_owned(),
+                export_video_only: "Eksportuj tylko obraz (bez dzwieku)".to_owned(),
+                export_audio_only: "Eksportuj tylko dzwiek (bez obrazu)".to_owned(),
+                export_separate_tracks: "Eksportuj obraz i dzwiek osobno (.video.mp4 + .audio.aac)".to_owned(),
+                auto_fill_gaps: "Wypelniaj luki czarnym obrazem i cisza".to_owned(),
+                render_range_only: "Renderuj tylko zakres mark in / mark out".to_owned(),
+                render_range_only_hint: "Klipy poza zakresem sa pomijane, a klipy na jego granicach przycinane; wymaga obu znacznikow".to_owned(),
+                watermark: "Znak wodny:".to_owned(),
+                watermark_choose: "Wybierz obraz...".to_owned(),
+                watermark_position: "Pozycja".to_owned(),
+                watermark_opacity: "Krycie".to_owned(),
+                watermark_scale: "Skala".to_owned(),
+                preview_fit_hint: "Skalowanie podgladu: Fit / Fill (przyciecie) / 1:1 (przeciagnij, by przesunac) / Stretch".to_owned(),
+                shortcuts_label: "Skroty klawiszowe".to_owned(),
+                record_shortcut: "Nagraj skrot (Esc anuluje)".to_owned(),
+                press_key: "Nacisnij klawisz...".to_owned(),
+                reset_shortcuts: "Przywroc domyslne skroty".to_owned(),
+                accent_color_label: "Kolor akcentu".to_owned(),
                 language_label: "Język".to_owned(),
+                thumbnails_label: "Miniatury na osi czasu".to_owned(),
+                regenerate_thumbnails: "Wygeneruj miniatury".to_owned(),
                 no_preview: "Brak podglądu".to_owned(),
                 no_duration: "Brak długości materiału".to_owned(),
             }
@@ -227,20 +895,133 @@ impl TextResources {
 struct VideoEditorApp {
     input_path: String,
     output_path: String,
+    output_template: OutputTemplate,
+    use_output_template: bool,
+    output_path_preview: Option<OutputPathPreview>,
+    project_path: Option<PathBuf>,
+    /// Blokada otwartego projektu ({projekt}.lock); None przy trybie tylko do odczytu
+    project_lock: Option<ProjectLock>,
+    project_read_only: bool,
+    /// Projekt zablokowany przez inna instancje - czeka na decyzje uzytkownika
+    pending_locked_project: Option<(PathBuf, LockInfo)>,
+    render_settings: RenderSettings,
+    /// ffmpeg ma filtr rubberband (sprawdzane przy starcie)
+    rubberband_available: bool,
+    /// Napisy zaimportowane z SRT (czasy zrodla)
+    subtitles: Vec<SubtitleEntry>,
+    settings: Settings,
+    render_thread: Option<thread::JoinHandle<Result<()>>>,
+    /// Kolejka renderu (status pozycji aktualizowany na zywo przez watek kolejki)
+    render_queue: Arc<Mutex<RenderQueue>>,
+    queue_thread: Option<thread::JoinHandle<()>>,
+    show_render_queue: bool,
+    silence_thread: Option<SilenceJob>,
+    phase_thread: Option<PhaseJob>,
+    trim_silence_thread: Option<TrimSilenceJob>,
+    /// Liczba czesci dla "Potnij klip na N czesci"
+    chop_parts: usize,
+    archive_thread: Option<ArchiveJob>,
+    script_thread: Option<ScriptJob>,
+    /// Pomiar dryfu zegara audio (tonem testowym)
+    calibration_thread: Option<thread::JoinHandle<Result<f32>>>,
+    archive_progress: ArchiveProgress,
+    /// Lokalne kopie zrodel (sciezka oryginalna -> kopia), uzywane gdy settings.use_cache_copy
+    source_cache: HashMap<String, PathBuf>,
+    /// Zrodla, ktorych nie udalo sie skopiowac (bez ponawiania w tej sesji)
+    source_cache_failed: HashSet<String>,
+    source_cache_thread: Option<thread::JoinHandle<(String, Result<PathBuf>)>>,
+    source_cache_progress: CopyProgress,
+    pending_render_confirmation: Option<PendingRenderConfirmation>,
+    /// Assety z importu folderu czekajace na decyzje "uloz na osi czasu"
+    pending_auto_arrange: Option<Vec<usize>>,
+    /// Zoom z klawiatury (+/-) do wykonania przy rysowaniu timeline
+    timeline_zoom_request: Option<f32>,
+    silence_ranges: Vec<(f32, f32)>,
+    silence_threshold_db: f32,
+    silence_min_duration: f32,
+    /// Tempo do siatki uderzen (0 = wykrywane automatycznie)
+    bpm: f32,
+    /// Wykryte uderzenia (czas timeline) - niezalezne od znacznikow rozdzialow
+    beat_markers: Vec<f32>,
+    /// Znaczniki rozdzialow (zapisywane w projekcie, eksport WebVTT)
+    markers: Vec<Marker>,
+    beat_thread: Option<BeatJob>,
+    render_progress: Arc<Mutex<RenderProgress>>,
+    show_clip_properties: bool,
+    missing_media: Vec<String>,
+    /// Okno "Remap media" i sciezki, dla ktorych auto-wyszukiwanie nic nie znalazlo
+    show_remap_media: bool,
+    remap_not_found: HashSet<String>,
+    /// Dodatkowe katalogi wybrane do szukania mediow (oprocz katalogu projektu)
+    remap_search_roots: Vec<PathBuf>,
+    media_search_thread: Option<MediaSearchJob>,
+    import_folder_thread: Option<ImportFolderJob>,
+    show_goto: bool,
+    goto_input: String,
     clips: Vec<Clip>,
     duration: f32,
     video_width: u32,
     video_height: u32,
     video_fps: f32,
+    /// Timecode SMPTE drop-frame (HH:MM:SS;FF); ustawiane automatycznie dla 29.97 / 59.94 fps
+    drop_frame: bool,
+    /// Licznik klatek w trybie edycji (DragValue z numerem klatki)
+    editing_frame: bool,
+    /// Ostatnio zastosowany motyw (motyw, akcent, systemowy ciemny) - set_visuals tylko po zmianie
+    applied_theme: Option<(Theme, egui::Color32, Option<bool>)>,
+    /// Przesuniecie podgladu w trybie 1:1 (od srodka kadru, w punktach)
+    preview_pan: egui::Vec2,
+    /// Akcja, dla ktorej edytor skrotow czeka na nastepny klawisz
+    recording_shortcut: Option<&'static str>,
+    /// Bitrate zrodla z ffprobe (0 = nieznany) - podstawa szacowania rozmiaru renderu
+    source_bitrate_kbps: f32,
+    /// Kodeki / audio wczytanego pliku (panel Media Info)
+    media_info: Option<MediaInfo>,
+    /// ffprobe glownego wejscia w tle (UI nie blokuje sie na wolnych dyskach)
+    pending_probe: Option<mpsc::Receiver<ProbeResult>>,
+    /// Kat obrotu spinnera podczas probe (rad)
+    probe_spinner_angle: f32,
+    /// Wyniki ffprobe plikow przeciaganych nad oknem (klucz: sciezka; blad jako tekst)
+    drop_probes: HashMap<String, Result<DropProbe, String>>,
+    drop_probe_threads: Vec<(String, DropProbeJob)>,
+    /// Zoom (wzgledem min_zoom), dla ktorego wygenerowano waveform_texture
+    waveform_zoom_level: f32,
+    /// Nazwy urzadzen audio do wyboru w ustawieniach (odswiezane recznie)
+    audio_device_names: Vec<String>,
+    last_auto_save: Instant,
+    /// Waveform w wyzszej rozdzielczosci generowany w tle: (poziom zoomu, plik PNG)
+    pending_waveform: Option<mpsc::Receiver<(f32, Result<PathBuf>)>>,
     playhead: f32,
     mark_in: Option<f32>,
     mark_out: Option<f32>,
     selected_clip: Option<usize>,
+    /// Zaznaczenie wielu klipow (Ctrl+klik); puste = tylko selected_clip
+    selected_clips: Vec<usize>,
+    /// Nazwy grup klipow (klucz = Clip::group_id)
+    groups: HashMap<u32, String>,
+    /// Grupa w trakcie zmiany nazwy (id, edytowana nazwa)
+    renaming_group: Option<(u32, String)>,
+    /// Historia zmian klipow (snapshoty) dla Ctrl+Z / Ctrl+Y
+    undo_stack: Vec<UndoAction>,
+    redo_stack: Vec<UndoAction>,
     selected_track: TrackType,
     preview_texture: Option<egui::TextureHandle>,
+    /// Tryb A/B: lewa strona bez filtrow klipu, prawa z filtrami
+    comparison_mode: bool,
+    comparison_a_texture: Option<egui::TextureHandle>,
+    comparison_b_texture: Option<egui::TextureHandle>,
+    /// Pozycja linii podzialu A/B (0.0 - 1.0)
+    comparison_split: f32,
+    /// Nakladka histogramu RGB + luma na podgladzie
+    show_histogram: bool,
+    preview_histogram: Option<Box<Histogram>>,
     waveform_texture: Option<egui::TextureHandle>,
-    thumb_textures: Vec<egui::TextureHandle>,
+    thumb_textures: Vec<Option<egui::TextureHandle>>,
     thumb_times: Vec<f32>,
+    /// Kolejka miniatur do wygenerowania (czasy widocznych slotow bez tekstury)
+    thumb_requests: Vec<f32>,
+    /// Miniatury zapisane na dysku (miedzy uruchomieniami)
+    thumb_cache: ThumbnailCache,
     temp_dir: Option<PathBuf>,
     last_preview_time: Option<Instant>,
     last_preview_playhead: f32,
@@ -254,21 +1035,52 @@ struct VideoEditorApp {
     audio_stream: Option<cpal::Stream>,
     audio_buffer: Arc<Mutex<VecDeque<i16>>>,
     audio_samples_played: Arc<AtomicU64>,
+    /// Czas sciany, PTS i format audio w chwili startu odtwarzania (zegar A/V, patrz `AvClock`)
+    audio_clock_start: Arc<Mutex<Option<AudioClockStart>>>,
     audio_sample_rate: u32,
     audio_channels: u16,
+    /// Strumien odtwarzajacy fragmenty audio podczas przeciagania playheada
+    scrub_stream: Option<cpal::Stream>,
+    /// Osobny bufor scrubu - spozniony watek dekodujacy nie trafia do bufora odtwarzania
+    scrub_buffer: Arc<Mutex<VecDeque<i16>>>,
+    scrub_busy: Arc<AtomicBool>,
+    last_scrub_playhead: f32,
     dragging_playhead: bool,
     was_dragging_playhead: bool,
     timeline_zoom: f32,
     timeline_offset: f32,
+    /// Pozycja X (ekran) poczatku przeciagania na minimapie osi czasu
+    minimap_drag_start: Option<f32>,
     last_drag_preview_playhead: f32,
-    live_drag_preview: bool,
     tool: Tool,
     dragging_timeline: bool,
     dragging_fade: Option<FadeDrag>,
     dragging_clip: Option<usize>,      // NEW: Index of clip being dragged
     drag_clip_offset: f32,             // NEW: Offset from clip start to mouse
-    ripple_delete: bool,
+    track_height_video: f32,
+    track_height_audio: f32,
+    /// Nazwy sciezek w kolumnie etykiet
+    track_video_name: String,
+    track_audio_name: String,
+    /// Zablokowana sciezka ignoruje edycje klipow
+    track_video_locked: bool,
+    track_audio_locked: bool,
+    /// Odsluch: wyciszenie (M) i solo (S) sciezek wg track_index - tylko w sesji, nie w projekcie
+    track_muted: Vec<bool>,
+    track_soloed: Vec<bool>,
+    /// Klip pod kursorem i od kiedy (opoznienie tooltipa)
+    hover_start: Option<(usize, Instant)>,
+    /// Widok storyboardu zamiast podgladu w panelu centralnym
+    show_storyboard: bool,
+    /// Osobne okno systemowe z samym podgladem (np. na drugi monitor)
+    show_secondary_preview: bool,
+    storyboard_columns: usize,
+    /// Lista klipow zamiast osi czasu w panelu dolnym
+    show_clip_list: bool,
     show_settings: bool,
+    /// Okno "O programie"; wersje ffmpeg/ffprobe sprawdzane przy pierwszym otwarciu
+    show_about: bool,
+    about_tool_versions: Option<(String, String)>,
     language: Language,
     text: TextResources,
     
@@ -276,15 +1088,29 @@ struct VideoEditorApp {
     media_library: Vec<MediaAsset>,
     media_thumbs: HashMap<usize, egui::TextureHandle>, // ID -> Texture (Library Icon)
     media_waveforms: HashMap<usize, egui::TextureHandle>, // ID -> Waveform Texture
+    /// Waveformy zastepczego audio klipow: sciezka -> (tekstura, dlugosc pliku); None = blad generowania
+    override_waveforms: HashMap<String, Option<(egui::TextureHandle, f32)>>,
+    override_waveform_requests: Vec<String>,
+    /// Generowanie waveformu zastepczego audio w tle: (sciezka, plik PNG, dlugosc pliku)
+    override_waveform_thread: Option<thread::JoinHandle<(String, PathBuf, Result<f32>)>>,
     media_filmstrips: HashMap<usize, Vec<(f32, egui::TextureHandle)>>, // ID -> Vec<(Timestamp, Texture)>
     dragging_library_asset: Option<usize>, // Asset being dragged from library
 
     language_switch_start: Option<Instant>,
-    status: String,
+    log: Vec<LogEntry>,
+    /// Komunikat na pasku stanu (ostatnia operacja) i kat jego spinnera
+    status: Option<StatusMessage>,
+    status_spinner_angle: f32,
+    session_start: Instant,
     // Async Preview
     preview_rx: mpsc::Receiver<(f32, Vec<u8>)>,
     preview_tx: mpsc::Sender<(f32, Vec<u8>)>,
     preview_busy: Arc<AtomicBool>,
+    /// Proces ffmpeg generujacy aktualny podglad (zabijany przy nowym zadaniu)
+    pending_preview: Option<std::process::Child>,
+    preview_generation: u64,
+    preview_result_tx: mpsc::Sender<PreviewResult>,
+    preview_result_rx: mpsc::Receiver<PreviewResult>,
     // Frame Cache (LRU) - klucz: timestamp w ms, wartość: PNG bytes
     #[allow(dead_code)]
     frame_cache: HashMap<i64, Vec<u8>>,
@@ -305,46 +1131,98 @@ struct VideoEditorApp {
 
 
 impl eframe::App for VideoEditorApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    /// Przelaczniki z paska narzedzi (ripple, snap...) tez trafiaja do ustawien
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let _ = save_settings(&self.settings);
+        self.project_lock = None;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.apply_theme(ctx, frame.info().system_theme);
         if let Ok((_time, data)) = self.preview_rx.try_recv() {
              // Hack: musimy zaladowac teksture w glownym watku (tutaj), bo ctx jest dostepny
              // Ale load_texture wymaga Context. OK.
-             if let Ok(texture) = load_texture_from_memory(ctx, &data, "preview_async") {
-                 self.preview_texture = Some(texture);
+             if let Ok(image) = decode_color_image(&data) {
+                 self.update_histogram(&image);
+                 self.preview_texture = Some(ctx.load_texture("preview_async", image, egui::TextureOptions::LINEAR));
                  // self.last_preview_time = Some(Instant::now()); // Opcjonalne
                  // self.last_preview_playhead = time; // Ważne dla logiki
              }
         }
+        self.poll_preview_result(ctx);
+        self.poll_probe_result(ctx);
+        self.poll_waveform_result(ctx);
+        self.handle_file_drag_and_drop(ctx);
+        self.maybe_auto_save();
 
         let mut user_seeked = false;
 
-        // Skroty klawiszowe
-        if ctx.input(|i| i.key_pressed(egui::Key::A)) {
+        // Skroty klawiszowe (Settings.keybindings; pomijane podczas pisania i nagrywania skrotu)
+        let typing = ctx.wants_keyboard_input() || self.recording_shortcut.is_some();
+        let keys = self.settings.keybindings.clone();
+        let pressed = |combo: &KeyCombo| !typing && ctx.input(|i| combo.pressed(i));
+        if pressed(&keys.play_pause) {
+            self.toggle_playback();
+        }
+        if pressed(&keys.tool_hand) {
             self.tool = Tool::Hand;
         }
-        if ctx.input(|i| i.key_pressed(egui::Key::B)) {
+        if pressed(&keys.tool_blade) {
             self.tool = Tool::Scissors;
         }
-        // Delete / Backspace - usuwa zaznaczony klip
-        if ctx.input(|i| i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)) {
-            if let Some(idx) = self.selected_clip {
-                if idx < self.clips.len() {
-                    if self.ripple_delete {
-                        // Ripple Delete - przesun pozostale klipy
-                        let duration = self.clips[idx].end - self.clips[idx].start;
-                        self.clips.remove(idx);
-                        for clip in self.clips.iter_mut().skip(idx) {
-                            clip.start -= duration;
-                            clip.end -= duration;
-                        }
-                    } else {
-                        self.clips.remove(idx);
-                    }
-                    self.selected_clip = None;
-                    self.status = "Klip usuniety.".to_string();
-                }
+        // Ciecie pod playheadem bez zmiany narzedzia
+        if pressed(&keys.split_clip) {
+            self.split_at_playhead();
+        }
+        if pressed(&keys.mark_in) {
+            self.mark_in = Some(self.snap(self.playhead));
+        }
+        if pressed(&keys.mark_out) {
+            self.mark_out = Some(self.snap(self.playhead));
+        }
+        if pressed(&keys.add_marker) {
+            self.add_marker();
+        }
+        // Zoom osi czasu wokol playheada (wykonywany w draw_timeline, gdzie znamy szerokosc)
+        // Na klawiaturach bez osobnego '+' domyslny skrot dziala tez pod '='
+        let zoom_in_equals = keys.zoom_in.key == egui::Key::Plus
+            && pressed(&KeyCombo::new(egui::Key::Equals, keys.zoom_in.modifiers));
+        if pressed(&keys.zoom_in) || zoom_in_equals {
+            self.timeline_zoom_request = Some(1.2);
+        }
+        if pressed(&keys.zoom_out) {
+            self.timeline_zoom_request = Some(0.83);
+        }
+        // Grupuje zaznaczone klipy (min. 2), w przeciwnym razie okno "Go to"
+        if pressed(&keys.group_or_goto) {
+            if self.selected_indices().len() >= 2 {
+                self.group_selected();
+            } else {
+                self.show_goto = true;
+                self.goto_input.clear();
             }
         }
+        // Przelacza audio / wideo zaznaczonych klipow
+        if pressed(&keys.toggle_audio) {
+            self.toggle_selected_tracks(TrackType::Audio);
+        }
+        if pressed(&keys.toggle_video) {
+            self.toggle_selected_tracks(TrackType::Video);
+        }
+        if pressed(&keys.undo) {
+            self.undo();
+        }
+        if pressed(&keys.redo) || pressed(&keys.redo_alt) {
+            self.redo();
+        }
+        // Usuwa zaznaczony klip
+        if (pressed(&keys.delete_clip) || pressed(&keys.delete_clip_alt))
+            && let Some(idx) = self.selected_clip
+            && idx < self.clips.len()
+        {
+            self.remove_clip_with_group(idx);
+            self.log.push(LogEntry::info("Klip usuniety."));
+        }
 
         // Logika Fake Loading przy zmianie jezyka
         if let Some(start_time) = self.language_switch_start {
@@ -380,13 +1258,176 @@ impl eframe::App for VideoEditorApp {
             }
         }
 
+        // Leniwe miniatury - jedna na klatke
+        self.poll_thumbnail_requests(ctx);
+        self.poll_override_waveforms(ctx);
+
+        // Render w tle - sprawdzamy czy watek skonczyl
+        if let Some(handle) = &self.render_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.render_thread.take() {
+                    // Nowy plik w katalogu wyjsciowym zmienia nastepny {seq}
+                    self.output_path_preview = None;
+                    match handle.join() {
+                        Ok(Ok(())) => {
+                            let messages = self.render_progress.lock().map(|p| p.messages.clone()).unwrap_or_default();
+                            self.log.extend(messages.into_iter().map(LogEntry::info));
+                            self.set_status(StatusKind::Success, self.text.status_render_done.clone());
+                        }
+                        Ok(Err(err)) => self.set_status(StatusKind::Error, format!("{}: {err:#}", self.text.status_render_failed)),
+                        Err(_) => self.set_status(StatusKind::Error, "Blad: watek renderu przerwany."),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Kolejka renderu w tle
+        if let Some(handle) = &self.queue_thread {
+            if handle.is_finished() {
+                self.queue_thread = None;
+                let (done, failed) = self.render_queue.lock().map(|q| {
+                    let done = q.iter().filter(|e| matches!(e.status, QueueStatus::Done(_))).count();
+                    let failed = q.iter().filter(|e| matches!(e.status, QueueStatus::Failed(_))).count();
+                    (done, failed)
+                }).unwrap_or_default();
+                let kind = if failed > 0 { StatusKind::Warning } else { StatusKind::Success };
+                self.set_status(kind, format!("Kolejka renderu zakonczona: {done} gotowe, {failed} bledy"));
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Wykrywanie ciszy w tle
+        if let Some(handle) = &self.silence_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.silence_thread.take() {
+                    match handle.join() {
+                        Ok(Ok(ranges)) => {
+                            self.set_status(StatusKind::Success, format!("Wykryto fragmenty ciszy: {}", ranges.len()));
+                            self.silence_ranges = ranges;
+                        }
+                        Ok(Err(err)) => self.set_status(StatusKind::Error, format!("Blad wykrywania ciszy: {err:#}")),
+                        Err(_) => self.set_status(StatusKind::Error, "Blad: watek wykrywania ciszy przerwany."),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Wykrywanie rytmu w tle
+        if let Some(handle) = &self.beat_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.beat_thread.take() {
+                    match handle.join() {
+                        Ok(Ok((bpm, beats))) => {
+                            if let Some(bpm) = bpm {
+                                self.bpm = (bpm * 10.0).round() / 10.0;
+                            }
+                            self.log.push(LogEntry::info(format!("Wykryto uderzenia: {} (BPM {:.1})", beats.len(), self.bpm)));
+                            self.beat_markers = beats;
+                        }
+                        Ok(Err(err)) => self.log.push(LogEntry::error(format!("Blad wykrywania rytmu: {err:#}"))),
+                        Err(_) => self.log.push(LogEntry::error("Blad: watek wykrywania rytmu przerwany.")),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Analiza fazy w tle
+        if let Some(handle) = &self.phase_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.phase_thread.take() {
+                    match handle.join() {
+                        Ok(results) => self.report_phase_results(&results),
+                        Err(_) => self.log.push(LogEntry::error("Blad: watek analizy fazy przerwany.")),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Przycinanie ciszy na koncach klipow w tle
+        if let Some(handle) = &self.trim_silence_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.trim_silence_thread.take() {
+                    match handle.join() {
+                        Ok(results) => self.apply_silence_trim(results),
+                        Err(_) => self.set_status(StatusKind::Error, "Blad: watek przycinania ciszy przerwany."),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Kalibracja zegara audio w tle
+        if let Some(handle) = &self.calibration_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.calibration_thread.take() {
+                    match handle.join() {
+                        Ok(Ok(correction)) => {
+                            self.settings.audio_clock_correction = correction;
+                            self.log.push(LogEntry::info(format!("Kalibracja A/V: korekta zegara {correction:.4}")));
+                            if let Err(err) = save_settings(&self.settings) {
+                                self.log.push(LogEntry::error(format!("Blad zapisu ustawien: {err:#}")));
+                            }
+                        }
+                        Ok(Err(err)) => self.log.push(LogEntry::error(format!("Blad kalibracji A/V: {err:#}"))),
+                        Err(_) => self.log.push(LogEntry::error("Blad: watek kalibracji przerwany.")),
+                    }
+                }
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
+
+        // Pakowanie archiwum projektu w tle
+        if let Some(handle) = &self.archive_thread {
+            if handle.is_finished() {
+                if let Some(handle) = self.archive_thread.take() {
+                    match handle.join() {
+                        Ok(Ok(path)) => self.log.push(LogEntry::info(format!("Zapisano archiwum: {}", path.display()))),
+                        Ok(Err(err)) => self.log.push(LogEntry::error(format!("Blad archiwizacji: {err:#}"))),
+                        Err(_) => self.log.push(LogEntry::error("Blad: watek archiwizacji przerwany.")),
+                    }
+                }
+            } else {
+                let (written, total) = self.archive_progress.lock().map(|p| *p).unwrap_or((0, 0));
+                let fraction = if total > 0 { written as f32 / total as f32 } else { 0.0 };
+                egui::Window::new(&self.text.archiving_project)
+                    .collapsible(false)
+                    .resizable(false)
+                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                    .show(ctx, |ui| {
+                        ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                        ui.label(format!(
+                            "{:.1} / {:.1} MB",
+                            written as f64 / 1_048_576.0,
+                            total as f64 / 1_048_576.0
+                        ));
+                    });
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
+        self.poll_source_cache(ctx);
+        self.poll_script_result(ctx);
+        self.poll_media_search(ctx);
+        self.poll_import_folder(ctx);
+
         // VIDEO SYNC: Check if waiting for video buffer
         if self.waiting_for_video_ready {
             if self.video_ready_signal.load(Ordering::Relaxed) {
                 // Video is ready! Start Audio and Time
                 self.waiting_for_video_ready = false;
                 if let Err(e) = self.start_audio_playback() {
-                    self.status = format!("Audio error: {}", e);
+                    self.log.push(LogEntry::error(format!("Audio error: {}", e)));
                 }
                 self.is_playing = true;
                 self.last_tick = Some(Instant::now());
@@ -397,7 +1438,7 @@ impl eframe::App for VideoEditorApp {
                     if handle.is_finished() {
                         self.waiting_for_video_ready = false;
                         self.is_playing = false;
-                        self.status = "Błąd: Wątek wideo zakończył pracę przed startem.".to_string();
+                        self.log.push(LogEntry::error("Błąd: Wątek wideo zakończył pracę przed startem."));
                         return;
                     }
                 }
@@ -417,11 +1458,7 @@ impl eframe::App for VideoEditorApp {
             if !self.clips.is_empty() {
                 let new_playhead = if self.audio_stream.is_some() {
                      // AUDIO MASTER SYNC
-                     let played = self.audio_samples_played.load(Ordering::Relaxed) as f32;
-                     let rate = self.audio_sample_rate.max(1) as f32;
-                     let channels = self.audio_channels.max(1) as f32;
-                     let audio_time = played / (rate * channels);
-                     self.playback_start_playhead + audio_time
+                     self.av_clock().audio_pts().map_or(self.playback_start_playhead, |pts| pts as f32)
                 } else {
                      // Fallback to strict timer if no audio
                      self.playhead + dt
@@ -430,13 +1467,13 @@ impl eframe::App for VideoEditorApp {
                 // Find the last clip end (effective duration)
                 let effective_end = self.clips.iter()
                     .filter(|c| c.video_enabled || c.audio_enabled)
-                    .map(|c| c.end)
+                    .map(|c| c.timeline_end())
                     .fold(0.0f32, |a, b| a.max(b));
                 
                 // Check if new playhead is in a gap (not inside any clip)
                 let in_clip = self.clips.iter()
                     .filter(|c| c.video_enabled || c.audio_enabled)
-                    .any(|c| new_playhead >= c.start && new_playhead < c.end);
+                    .any(|c| new_playhead >= c.start && new_playhead < c.timeline_end());
                 
                 if !in_clip && new_playhead < effective_end {
                     // Start playback is just linear, gaps are black/silent.
@@ -457,6 +1494,7 @@ impl eframe::App for VideoEditorApp {
 
         if self.is_playing {
             if let Some(frame) = self.take_latest_frame() {
+                self.update_histogram(&frame);
                 if let Some(tex) = &mut self.preview_texture {
                     tex.set(frame, egui::TextureOptions::LINEAR);
                 } else {
@@ -478,14 +1516,26 @@ impl eframe::App for VideoEditorApp {
                         self.input_path.clear();
                         self.output_path.clear();
                         self.clips.clear();
+                        self.subtitles.clear();
+                        self.groups.clear();
+                        self.markers.clear();
+                        self.selected_clips.clear();
+                        self.undo_stack.clear();
+                        self.redo_stack.clear();
+                        self.project_path = None;
+                        self.project_lock = None;
+                        self.project_read_only = false;
                         self.duration = 0.0;
                         self.playhead = 0.0;
                         self.stop_playback();
                         self.thumb_textures.clear();
                         self.thumb_times.clear();
+                        self.thumb_requests.clear();
                         self.preview_texture = None;
                         self.waveform_texture = None;
-                        self.status = self.text.status_new_project.clone();
+                        self.pending_waveform = None;
+                        self.media_info = None;
+                        self.set_status(StatusKind::Info, self.text.status_new_project.clone());
                         ui.close_menu();
                     }
                     if ui.button(&open_proj).clicked() {
@@ -496,6 +1546,42 @@ impl eframe::App for VideoEditorApp {
                         self.save_project_as();
                         ui.close_menu();
                     }
+                    ui.separator();
+                    if ui.button(&self.text.export_render_script).clicked() {
+                        self.export_render_script_dialog();
+                        ui.close_menu();
+                    }
+                    if ui.button(&self.text.import_folder).clicked() {
+                        ui.close_menu();
+                        self.import_folder_dialog();
+                    }
+                    if ui.button(&self.text.remap_media).clicked() {
+                        ui.close_menu();
+                        self.show_remap_media = true;
+                    }
+                    if ui.add_enabled(self.archive_thread.is_none(), egui::Button::new(&self.text.export_project_archive)).clicked() {
+                        ui.close_menu();
+                        self.export_project_archive_dialog();
+                    }
+                });
+
+                ui.menu_button(&self.text.view_menu, |ui| {
+                    ui.checkbox(&mut self.show_storyboard, &self.text.storyboard);
+                    ui.checkbox(&mut self.show_secondary_preview, &self.text.secondary_preview);
+                });
+
+                ui.menu_button(self.text.tools_menu.clone(), |ui| {
+                    if ui.add_enabled(self.script_thread.is_none(), egui::Button::new(&self.text.run_script)).clicked() {
+                        ui.close_menu();
+                        self.run_script_dialog();
+                    }
+                });
+
+                ui.menu_button(self.text.help_menu.clone(), |ui| {
+                    if ui.button(&self.text.about_title).clicked() {
+                        ui.close_menu();
+                        self.show_about = true;
+                    }
                 });
 
                 // Przelacznik Settings
@@ -507,10 +1593,77 @@ impl eframe::App for VideoEditorApp {
             });
         });
 
+        // Propozycja ulozenia importowanego folderu na osi czasu
+        if let Some(assets) = &self.pending_auto_arrange {
+            let assets = assets.clone();
+            let mut decision = None;
+            egui::Window::new(&self.text.auto_arrange_title)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(&self.text.auto_arrange_question);
+                    ui.horizontal(|ui| {
+                        if ui.button(&self.text.yes).clicked() {
+                            decision = Some(true);
+                        }
+                        if ui.button(&self.text.no).clicked() {
+                            decision = Some(false);
+                        }
+                    });
+                });
+            if let Some(arrange) = decision {
+                self.pending_auto_arrange = None;
+                if arrange {
+                    self.auto_arrange_assets(&assets);
+                }
+            }
+        }
+
+        // Potwierdzenie nadpisania pliku wyjsciowego
+        if let Some(pending) = &self.pending_render_confirmation {
+            let output_path = pending.output_path.clone();
+            let mut decision = None;
+            egui::Window::new(&self.text.overwrite_title)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.label(&self.text.overwrite_question);
+                    ui.label(egui::RichText::new(&output_path).small().color(egui::Color32::GRAY));
+                    ui.horizontal(|ui| {
+                        if ui.button(&self.text.yes).clicked() {
+                            decision = Some(Some(output_path.clone()));
+                        }
+                        if ui.button(&self.text.auto_rename).clicked() {
+                            decision = Some(Some(auto_rename_output(&output_path).display().to_string()));
+                        }
+                        if ui.button(&self.text.no).clicked() {
+                            decision = Some(None);
+                        }
+                    });
+                });
+            match decision {
+                Some(Some(path)) => {
+                    self.pending_render_confirmation = None;
+                    if !self.use_output_template {
+                        self.output_path = path.clone();
+                    }
+                    self.launch_render(path);
+                }
+                Some(None) => {
+                    self.pending_render_confirmation = None;
+                    self.log.push(LogEntry::info("Render anulowany."));
+                }
+                None => {}
+            }
+        }
+
         // Okno Ustawien
         if self.show_settings {
             let title = self.text.settings_title.clone();
             let label_lang = self.text.language_label.clone();
+            let mut regenerate_thumbs = false;
             
             egui::Window::new(title)
                 .pivot(egui::Align2::CENTER_CENTER)
@@ -533,6 +1686,25 @@ impl eframe::App for VideoEditorApp {
                          }
                     });
                      
+                     ui.add_space(10.0);
+                     ui.horizontal(|ui| {
+                         ui.label(&self.text.theme_label);
+                         egui::ComboBox::from_id_source("settings_theme")
+                            .selected_text(self.settings.theme.to_string())
+                            .show_ui(ui, |ui| {
+                                for theme in Theme::ALL {
+                                    ui.selectable_value(&mut self.settings.theme, theme, theme.to_string());
+                                }
+                            });
+                     });
+                     ui.horizontal(|ui| {
+                         ui.label(&self.text.accent_color_label);
+                         ui.color_edit_button_srgba(&mut self.settings.accent_color);
+                         if ui.small_button("⟲").on_hover_text("Default").clicked() {
+                             self.settings.accent_color = Settings::default().accent_color;
+                         }
+                     });
+
                      ui.add_space(10.0);
                      ui.label("Hardware Acceleration:");
                      egui::ComboBox::from_id_source("hw_accel")
@@ -544,60 +1716,259 @@ impl eframe::App for VideoEditorApp {
                             ui.selectable_value(&mut self.hw_accel_mode, HwAccelMode::Vaapi, "VAAPI (Linux)");
                             ui.selectable_value(&mut self.hw_accel_mode, HwAccelMode::VideoToolbox, "VideoToolbox (Mac)");
                         });
+
+                     ui.add_space(10.0);
+                     ui.label(&self.text.thumbnails_label);
+                     let thumbs = &mut self.settings.thumbnails;
+                     ui.add(egui::Slider::new(&mut thumbs.width, 50..=400).text("Width"));
+                     ui.add(egui::Slider::new(&mut thumbs.height, 0..=300).text("Height"))
+                        .on_hover_text("0 = keep aspect ratio");
+                     ui.add(egui::Slider::new(&mut thumbs.count, 4..=32).text("Count"));
+                     if ui.button(&self.text.regenerate_thumbnails).clicked() {
+                         regenerate_thumbs = true;
+                     }
+
+                     ui.add_space(10.0);
+                     ui.label(&self.text.av_sync_label);
+                     ui.horizontal(|ui| {
+                         ui.add(
+                             egui::DragValue::new(&mut self.settings.audio_clock_correction)
+                                 .speed(0.0005)
+                                 .clamp_range(0.8..=1.2)
+                                 .fixed_decimals(4),
+                         )
+                         .on_hover_text("Audio clock multiplier (1.0 = no correction)");
+                         let calibrating = self.calibration_thread.is_some();
+                         if ui.add_enabled(!calibrating, egui::Button::new(&self.text.calibrate_av_sync)).clicked() {
+                             let device_name = self.settings.audio_device_name.clone();
+                             self.calibration_thread = Some(thread::spawn(move || {
+                                 measure_audio_clock_correction(std::time::Duration::from_secs(5), &device_name)
+                             }));
+                         }
+                         if calibrating {
+                             ui.spinner();
+                         }
+                     });
+
+                     ui.add_space(10.0);
+                     ui.checkbox(&mut self.settings.use_cache_copy, &self.text.use_cache_copy)
+                        .on_hover_text(source_cache_dir().display().to_string());
+                     ui.add(egui::Slider::new(&mut self.settings.max_cache_size_mb, 64..=16384).logarithmic(true).suffix(" MB").text(&self.text.max_cache_size));
+
+                     ui.add_space(10.0);
+                     ui.label(&self.text.editing_label);
+                     ui.checkbox(&mut self.settings.live_drag_preview, &self.text.live_preview);
+                     ui.checkbox(&mut self.settings.ripple_delete, &self.text.ripple_delete);
+                     ui.checkbox(&mut self.settings.timeline_snap_to_frames, &self.text.snap_frames);
+
+                     ui.add_space(10.0);
+                     ui.label(&self.text.playback_label);
+                     egui::ComboBox::from_id_source("settings_preview_quality")
+                        .selected_text(self.settings.preview_quality.to_string())
+                        .show_ui(ui, |ui| {
+                            for quality in PreviewQuality::ALL {
+                                ui.selectable_value(&mut self.settings.preview_quality, quality, quality.to_string());
+                            }
+                        });
+                     ui.add(egui::Slider::new(&mut self.settings.playback_fps_limit, 0..=120).text(&self.text.playback_fps_limit))
+                        .on_hover_text("0 = no limit");
+                     if self.audio_device_names.is_empty() {
+                         self.audio_device_names = output_device_names();
+                     }
+                     let current_device = if self.settings.audio_device_name.is_empty() {
+                         self.text.system_default.clone()
+                     } else {
+                         self.settings.audio_device_name.clone()
+                     };
+                     ui.horizontal(|ui| {
+                         ui.label(&self.text.audio_device_label);
+                         egui::ComboBox::from_id_source("settings_audio_device")
+                            .selected_text(current_device)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.settings.audio_device_name, String::new(), &self.text.system_default);
+                                for name in &self.audio_device_names {
+                                    ui.selectable_value(&mut self.settings.audio_device_name, name.clone(), name);
+                                }
+                            });
+                         if ui.small_button("⟳").clicked() {
+                             self.audio_device_names = output_device_names();
+                         }
+                     });
+
+                     ui.add_space(10.0);
+                     ui.label(&self.text.tool_paths_label);
+                     egui::Grid::new("settings_tool_paths").num_columns(2).show(ui, |ui| {
+                         ui.label("ffmpeg");
+                         ui.add(egui::TextEdit::singleline(&mut self.settings.ffmpeg_path).hint_text("ffmpeg"));
+                         ui.end_row();
+                         ui.label("ffprobe");
+                         ui.add(egui::TextEdit::singleline(&mut self.settings.ffprobe_path).hint_text("ffprobe"));
+                         ui.end_row();
+                     });
+
+                     ui.add_space(10.0);
+                     ui.add(egui::Slider::new(&mut self.settings.auto_save_interval_secs, 0..=3600).suffix(" s").text(&self.text.auto_save_interval))
+                        .on_hover_text(&self.text.auto_save_note);
+
+                     ui.add_space(10.0);
+                     ui.collapsing(&self.text.shortcuts_label, |ui| {
+                         keybindings_ui(ui, &mut self.settings.keybindings, &mut self.recording_shortcut, &self.text);
+                     });
                 });
+
+            if regenerate_thumbs {
+                self.reset_thumbnails();
+            }
+            // Zamkniecie okna - zapis ustawien
+            if !self.show_settings {
+                self.recording_shortcut = None;
+                set_tool_paths(&self.settings.ffmpeg_path, &self.settings.ffprobe_path);
+                self.about_tool_versions = None;
+                if let Err(err) = save_settings(&self.settings) {
+                    self.log.push(LogEntry::error(format!("Blad zapisu ustawien: {err:#}")));
+                }
+            }
+        }
+
+        if self.show_about {
+            self.show_about_window(ctx);
+        }
+
+        // Okno brakujacych plikow
+        if !self.missing_media.is_empty() {
+            self.show_missing_media_window(ctx);
+        }
+        if self.show_render_queue {
+            self.show_render_queue_window(ctx);
+        }
+        if self.show_remap_media {
+            self.show_remap_media_window(ctx);
+        }
+        if self.pending_locked_project.is_some() {
+            self.show_project_locked_window(ctx);
+        }
+
+        // Okno "Go to"
+        if self.show_goto && self.show_goto_window(ctx) {
+            user_seeked = true;
+        }
+
+        // Okno zmiany nazwy grupy
+        if self.renaming_group.is_some() {
+            self.show_rename_group_window(ctx);
+        }
+
+        // Okno wlasciwosci klipu
+        if self.show_clip_properties {
+            self.show_clip_properties_window(ctx);
         }
 
         // Panel dolny: Timeline
-        egui::TopBottomPanel::bottom("timeline_panel")
+        // Pasek stanu: komunikat ostatniej operacji i kopiowanie zrodla do lokalnego cache
+        if self.status.as_ref().is_some_and(StatusMessage::expired) {
+            self.status = None;
+        }
+        if self.status.is_some() || self.source_cache_thread.is_some() {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    self.status_message_ui(ui);
+                    if self.source_cache_thread.is_some() {
+                        let (copied, total) = self.source_cache_progress.lock().map(|p| *p).unwrap_or((0, 0));
+                        ui.separator();
+                        ui.spinner();
+                        ui.label(format!(
+                            "{}: {:.0} MB / {:.0} MB",
+                            self.text.caching_label,
+                            copied as f64 / 1_048_576.0,
+                            total as f64 / 1_048_576.0
+                        ));
+                        let fraction = if total > 0 { copied as f32 / total as f32 } else { 0.0 };
+                        ui.add(egui::ProgressBar::new(fraction).desired_width(160.0));
+                    }
+                });
+            });
+        }
+
+        let timeline_panel = egui::TopBottomPanel::bottom("timeline_panel")
             .resizable(true)
             .min_height(150.0)
+            .default_height(self.settings.window_layout.timeline_height)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    ui.label(&self.text.timeline_label);
-                    if draw_timeline(ui, self) {
+                    ui.horizontal(|ui| {
+                        ui.label(&self.text.timeline_label);
+                        ui.toggle_value(&mut self.show_clip_list, format!("☰ {}", self.text.clip_list));
+                        if self.waveform_amplitude_ui(ui) {
+                            self.regenerate_waveforms(ctx);
+                        }
+                    });
+                    let seeked = if self.show_clip_list {
+                        self.clip_list_ui(ui)
+                    } else {
+                        draw_timeline_minimap(ui, self);
+                        draw_timeline(ui, self)
+                    };
+                    if seeked {
                         user_seeked = true;
                     }
                     ui.separator();
                     ui.horizontal(|ui| {
                         ui.label(format!("Playhead: {:.2}s", self.playhead));
+                        if self.frame_counter_ui(ui) {
+                            user_seeked = true;
+                        }
+                        ui.checkbox(&mut self.settings.timeline_snap_to_frames, &self.text.snap_frames);
+                        // Drop-frame tylko dla klatkazy NTSC (29.97 / 59.94)
+                        ui.add_enabled(is_drop_frame_rate(self.video_fps), egui::Checkbox::new(&mut self.drop_frame, "DF"))
+                            .on_hover_text(&self.text.drop_frame_hint);
                         if ui.button(&self.text.mark_in).clicked() {
-                            self.mark_in = Some(self.playhead);
+                            self.mark_in = Some(self.snap(self.playhead));
                         }
                         if ui.button(&self.text.mark_out).clicked() {
-                            self.mark_out = Some(self.playhead);
+                            self.mark_out = Some(self.snap(self.playhead));
+                        }
+                        if ui.button(&self.text.add_marker).clicked() {
+                            self.add_marker();
                         }
                         if ui.button(&self.text.add_clip).clicked() {
                             if let (Some(start), Some(end)) = (self.mark_in, self.mark_out) {
                                 if end > start {
-                                    self.clips.push(Clip {
-                                        asset_id: None,
-                                        start,
-                                        end,
-                                        fade_in: 0.0,
-                                        fade_out: 0.0,
-                                        linked: true,
-                                        video_enabled: true,
-                                        audio_enabled: true,
-                                    });
+                                    self.clips.push(Clip::new(None, start, end));
                                     self.selected_clip = Some(self.clips.len() - 1);
-                                    self.status.clear();
                                 } else {
-                                    self.status = self.text.err_mark_out_greater.clone();
+                                    self.log.push(LogEntry::warning(self.text.err_mark_out_greater.clone()));
+                                }
+                            } else {
+                                self.log.push(LogEntry::warning(self.text.err_set_marks.clone()));
+                            }
+                        }
+                        // Wstawienie [mark in, mark out] na playhead z przesunieciem dalszych klipow
+                        if ui.button(&self.text.ripple_insert).clicked() {
+                            if let (Some(start), Some(end)) = (self.mark_in, self.mark_out) {
+                                if end > start {
+                                    self.push_undo();
+                                    let at = self.snap(self.playhead);
+                                    let mut clip = Clip::new(None, start, end);
+                                    clip.start = at;
+                                    clip.end = at + (end - start);
+                                    let idx = ripple_insert(&mut self.clips, clip);
+                                    self.selected_clip = Some(idx);
+                                } else {
+                                    self.log.push(LogEntry::warning(self.text.err_mark_out_greater.clone()));
                                 }
                             } else {
-                                self.status = self.text.err_set_marks.clone();
+                                self.log.push(LogEntry::warning(self.text.err_set_marks.clone()));
                             }
                         }
                         if ui.button(&self.text.split_clip).clicked() {
                             if let Some(idx) = self.selected_clip {
                                 if let Some(split) = split_clip_at(&mut self.clips, idx, self.playhead) {
                                     self.selected_clip = Some(split);
-                                    self.status.clear();
                                 } else {
-                                    self.status = self.text.err_playhead_inside.clone();
+                                    self.log.push(LogEntry::warning(self.text.err_playhead_inside.clone()));
                                 }
                             } else {
-                                self.status = self.text.err_select_clip.clone();
+                                self.log.push(LogEntry::warning(self.text.err_select_clip.clone()));
                             }
                         }
                         if ui.button(&self.text.remove_clip).clicked() {
@@ -608,15 +1979,21 @@ impl eframe::App for VideoEditorApp {
                                 }
                             }
                         }
+                        ui.toggle_value(&mut self.show_clip_properties, &self.text.properties_button);
+                        if ui.button(&self.text.fill_gaps).clicked() {
+                            self.fill_gaps_with_black(ctx);
+                        }
+                        if ui.add_enabled(self.phase_thread.is_none(), egui::Button::new(&self.text.detect_phase)).clicked() {
+                            self.start_phase_analysis();
+                        }
                     });
                 });
             });
 
         // Panel boczny: Narzedzia
-        egui::SidePanel::left("tools_panel")
-
+        let tools_panel = egui::SidePanel::left("tools_panel")
             .resizable(true)
-            .default_width(300.0)
+            .default_width(self.settings.window_layout.tools_width)
             .show(ctx, |ui| {
                 ui.heading(&self.text.editor_title);
                 ui.separator();
@@ -635,109 +2012,64 @@ impl eframe::App for VideoEditorApp {
 
                      ui.label(&self.text.output_file);
                      ui.horizontal(|ui| {
-                         ui.text_edit_singleline(&mut self.output_path);
-                         if ui.button("...").clicked() {
-                             if let Some(path) = rfd::FileDialog::new().save_file() {
-                                 self.output_path = path.display().to_string();
-                             }
+                         if self.use_output_template {
+                             ui.text_edit_singleline(&mut self.output_template.template)
+                                 .on_hover_text(&self.text.template_hint);
+                         } else {
+                             ui.text_edit_singleline(&mut self.output_path);
+                             ui.menu_button("...", |ui| {
+                                 if ui.button(&self.text.choose_output_file).clicked() {
+                                     ui.close_menu();
+                                     if let Some(path) = rfd::FileDialog::new().save_file() {
+                                         self.output_path = path.display().to_string();
+                                     }
+                                 }
+                                 if ui.button(&self.text.choose_output_folder).clicked() {
+                                     ui.close_menu();
+                                     if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                         self.output_path = format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR);
+                                     }
+                                 }
+                             });
                          }
                      });
+                     if !self.use_output_template && is_output_dir(&self.output_path) {
+                         let output = self.output_path_preview();
+                         ui.label(
+                             egui::RichText::new(format!("{} {output}", self.text.will_render_to))
+                                 .small()
+                                 .color(egui::Color32::GRAY),
+                         );
+                     }
+                     ui.checkbox(&mut self.use_output_template, &self.text.use_template);
+                     if self.use_output_template {
+                         if self.output_template.template.contains("{time}") {
+                             // Podglad {time} nadaza za zegarem
+                             ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+                         }
+                         ui.label(
+                             egui::RichText::new(format!("→ {}", self.output_path_preview()))
+                                 .small()
+                                 .color(egui::Color32::GRAY),
+                         );
+                     }
+                     self.extra_outputs_ui(ui);
                 });
 
                 ui.separator();
                 ui.heading("Media Library");
-                if ui.button("📂 Import Media").clicked() {
-                    if let Some(paths) = rfd::FileDialog::new().pick_files() {
+                ui.horizontal(|ui| {
+                    if ui.button("📂 Import Media").clicked()
+                        && let Some(paths) = rfd::FileDialog::new().pick_files()
+                    {
                         for path in paths {
-                            let path_str = path.display().to_string();
-                            // Detect type using ffprobe logic or extension
-                            if let Ok((dur, w, h, _fps)) = get_video_info_ffprobe(&path_str) {
-                                let kind = if w == 0 && h == 0 {
-                                    MediaType::Audio 
-                                } else if dur < 0.1 && (path_str.ends_with(".png") || path_str.ends_with(".jpg") || path_str.ends_with(".jpeg") || path_str.ends_with(".webp")) {
-                                    MediaType::Image
-                                } else {
-                                    MediaType::Video
-                                };
-                                
-                                let idx = self.media_library.len();
-                                let asset = MediaAsset {
-                                    id: idx, 
-                                    path: path_str.clone(),
-                                    name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                                    kind,
-                                    duration: if kind == MediaType::Image { 
-                                        5.0 
-                                    } else if dur < 0.1 {
-                                        println!("WARNING: Detected duration 0.0s for {}, defaulting to 10.0s", path_str);
-                                        10.0 
-                                    } else { 
-                                        dur 
-                                    },
-                                };
-                                self.media_library.push(asset);
-                                
-                                // Generate thumbnail
-                                let thumb_result = match kind {
-                                    MediaType::Image => {
-                                        // Load image directly as thumbnail (scaled)
-                                        load_texture_from_path(ctx, Path::new(&path_str), &format!("lib_thumb_{}", idx))
-                                    },
-                                    MediaType::Video => {
-                                        // Extract frame at 10% of duration
-                                        let thumb_time = dur * 0.1;
-                                        if let Ok(data) = generate_frame_memory(&path_str, thumb_time, 80, 0) {
-                                            load_texture_from_memory(ctx, &data, &format!("lib_thumb_{}", idx))
-                                        } else {
-                                            Err(anyhow!("Failed to generate video thumbnail"))
-                                        }
-                                    },
-                                    MediaType::Audio => {
-                                        // No thumbnail for audio - will use icon
-                                        Err(anyhow!("Audio has no thumbnail"))
-                                    }
-                                };
-                                
-                                if let Ok(texture) = thumb_result {
-                                    self.media_thumbs.insert(idx, texture);
-                                }
-                                
-                                // Generate Filmstrip (5 thumbs for video)
-                                if kind == MediaType::Video {
-                                    let count = 5;
-                                    let step = if dur > 0.0 { dur / count as f32 } else { 1.0 };
-                                    let mut strips = Vec::new();
-                                    for i in 0..count {
-                                        let t = (i as f32 + 0.5) * step;
-                                        if let Ok(data) = generate_frame_memory(&path_str, t, 160, 0) { // Small width for memory efficiency
-                                            if let Ok(tex) = load_texture_from_memory(ctx, &data, &format!("film_{}_{}", idx, i)) {
-                                                strips.push((t, tex));
-                                            }
-                                        }
-                                    }
-                                    if !strips.is_empty() {
-                                        self.media_filmstrips.insert(idx, strips);
-                                    }
-                                }
-                                
-                                // Generate Waveform (Audio or Video)
-                                if kind == MediaType::Audio || kind == MediaType::Video {
-                                    if let Ok(_) = self.ensure_temp_dir() {
-                                        if let Some(temp) = &self.temp_dir {
-                                            let wave_path = temp.join(format!("wave_{}.png", idx));
-                                            if let Ok(_) = generate_waveform(&path_str, &wave_path) {
-                                                if let Ok(tex) = load_texture_from_path(ctx, &wave_path, &format!("wave_{}", idx)) {
-                                                    self.media_waveforms.insert(idx, tex);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                
-                            }
+                            self.import_media_file(ctx, &path);
                         }
                     }
-                }
+                    if ui.button(&self.text.import_folder).clicked() {
+                        self.import_folder_dialog();
+                    }
+                });
                 
                 ui.add_space(5.0);
                 egui::ScrollArea::vertical()
@@ -863,14 +2195,10 @@ impl eframe::App for VideoEditorApp {
                         let asset_name = asset.name.clone();
                         
                         self.clips.push(Clip {
-                            start: self.playhead,
-                            end: clip_end,
-                            fade_in: 0.0,
-                            fade_out: 0.0,
                             linked: asset_kind == MediaType::Video,
                             video_enabled: asset_kind != MediaType::Audio,
                             audio_enabled: asset_kind != MediaType::Image,
-                            asset_id: Some(idx),
+                            ..Clip::new(Some(idx), self.playhead, clip_end)
                         });
                         self.selected_clip = Some(self.clips.len() - 1);
                         
@@ -882,17 +2210,18 @@ impl eframe::App for VideoEditorApp {
                         // If this is first video, set video dimensions
                         if asset_kind == MediaType::Video || asset_kind == MediaType::Image {
                             if self.video_width == 0 || self.video_height == 0 {
-                                if let Ok((_, w, h, fps)) = get_video_info_ffprobe(&asset_path) {
+                                if let Ok((_, w, h, fps, _)) = get_video_info_ffprobe(&asset_path) {
                                     self.video_width = w;
                                     self.video_height = h;
                                     if fps > 0.0 {
                                         self.video_fps = fps;
+                                        self.drop_frame = is_drop_frame_rate(self.video_fps);
                                     }
                                 }
                             }
                         }
                         
-                        self.status = format!("Added clip: {}", asset_name);
+                        self.log.push(LogEntry::info(format!("Added clip: {}", asset_name)));
                     }
                 });
                 
@@ -929,19 +2258,10 @@ impl eframe::App for VideoEditorApp {
                     if ui.button(&self.text.create_full_clip).clicked() {
                         if self.duration > 0.0 {
                             self.clips.clear();
-                            self.clips.push(Clip {
-                                asset_id: None,
-                                start: 0.0,
-                                end: self.duration,
-                                fade_in: 0.0,
-                                fade_out: 0.0,
-                                linked: true,
-                                video_enabled: true,
-                                audio_enabled: true,
-                            });
+                            self.clips.push(Clip::new(None, 0.0, self.duration));
                             self.selected_clip = Some(0);
                         } else {
-                            self.status = self.text.err_set_duration.clone();
+                            self.log.push(LogEntry::warning(self.text.err_set_duration.clone()));
                         }
                     }
                 });
@@ -951,33 +2271,243 @@ impl eframe::App for VideoEditorApp {
                 ui.horizontal(|ui| {
                     ui.selectable_value(&mut self.tool, Tool::Hand, &self.text.tool_hand);
                     ui.selectable_value(&mut self.tool, Tool::Scissors, &self.text.tool_scissors);
+                    ui.selectable_value(&mut self.tool, Tool::Envelope, &self.text.tool_envelope)
+                        .on_hover_text("Click audio clip: add point / click point: remove");
                 });
-                ui.checkbox(&mut self.live_drag_preview, &self.text.live_preview);
-                ui.checkbox(&mut self.ripple_delete, &self.text.ripple_delete);
+                ui.checkbox(&mut self.settings.live_drag_preview, &self.text.live_preview);
+                ui.checkbox(&mut self.settings.ripple_delete, &self.text.ripple_delete);
 
-                ui.separator();
-                if ui.button(&self.text.render_button).clicked() {
-                    match render_video(&self.input_path, &self.output_path, &self.clips, &self.media_library) {
-                        Ok(()) => self.status = self.text.status_render_done.clone(),
-                        Err(err) => self.status = format!("Blad: {err:#}"),
-                    }
+                if self.media_info.is_some() {
+                    ui.separator();
+                    ui.collapsing(self.text.media_info.clone(), |ui| self.media_info_ui(ui));
                 }
-                
-                if !self.status.is_empty() {
+
+                ui.separator();
+                ui.collapsing(self.text.project_stats.clone(), |ui| self.project_stats_ui(ui));
+
+                ui.separator();
+                ui.collapsing(self.text.silence_label.clone(), |ui| self.silence_ui(ui));
+
+                ui.separator();
+                ui.collapsing(self.text.beats_label.clone(), |ui| self.beats_ui(ui));
+
+                ui.separator();
+                ui.collapsing(format!("{} ({})", self.text.markers_label, self.markers.len()), |ui| self.markers_ui(ui));
+
+                ui.separator();
+                ui.collapsing(self.text.render_settings_label.clone(), |ui| {
+                    ui.checkbox(&mut self.render_settings.burn_timecode, &self.text.burn_timecode);
+                    ui.add_enabled_ui(self.render_settings.burn_timecode, |ui| {
+                        egui::ComboBox::from_id_source("timecode_position")
+                            .selected_text(self.render_settings.timecode_position.to_string())
+                            .show_ui(ui, |ui| {
+                                for pos in [
+                                    TimecodePosition::TopLeft,
+                                    TimecodePosition::TopRight,
+                                    TimecodePosition::BottomLeft,
+                                    TimecodePosition::BottomRight,
+                                    TimecodePosition::Center,
+                                ] {
+                                    ui.selectable_value(&mut self.render_settings.timecode_position, pos, pos.to_string());
+                                }
+                            });
+                    });
+                    egui::Grid::new("render_output_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(&self.text.output_resolution);
+                        egui::ComboBox::from_id_source("output_resolution")
+                            .selected_text(self.render_settings.output_resolution.to_string())
+                            .show_ui(ui, |ui| {
+                                for res in OutputResolution::ALL {
+                                    ui.selectable_value(&mut self.render_settings.output_resolution, res, res.to_string());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(&self.text.aspect_mode);
+                        ui.add_enabled_ui(self.render_settings.output_resolution != OutputResolution::Source, |ui| {
+                            egui::ComboBox::from_id_source("aspect_mode")
+                                .selected_text(self.render_settings.aspect_mode.to_string())
+                                .show_ui(ui, |ui| {
+                                    for mode in AspectRatioMode::ALL {
+                                        ui.selectable_value(&mut self.render_settings.aspect_mode, mode, mode.to_string());
+                                    }
+                                });
+                        });
+                        ui.end_row();
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button(&self.text.import_srt).clicked()
+                            && let Some(path) = rfd::FileDialog::new().add_filter("SubRip", &["srt"]).pick_file()
+                        {
+                            match load_srt(&path) {
+                                Ok(subs) => {
+                                    self.log.push(LogEntry::info(format!("Wczytano {} napisow.", subs.len())));
+                                    self.subtitles = subs;
+                                }
+                                Err(err) => self.log.push(LogEntry::error(format!("{err:#}"))),
+                            }
+                        }
+                        if !self.subtitles.is_empty() {
+                            ui.label(egui::RichText::new(format!("{}", self.subtitles.len())).color(egui::Color32::GRAY));
+                        }
+                    });
+                    ui.add_enabled(
+                        !self.subtitles.is_empty(),
+                        egui::Checkbox::new(&mut self.render_settings.write_srt, &self.text.write_srt),
+                    );
+                    ui.add_enabled(
+                        !self.markers.is_empty(),
+                        egui::Checkbox::new(&mut self.render_settings.write_webvtt_chapters, &self.text.write_webvtt_chapters),
+                    );
+                    // Sam obraz / sam dzwiek wykluczaja sie; osobne pliki zastepuja oba
+                    let separate = self.render_settings.separate_track_files;
+                    for (track, label) in [(TrackType::Video, &self.text.export_video_only), (TrackType::Audio, &self.text.export_audio_only)] {
+                        let mut only = self.render_settings.export_tracks == track;
+                        if ui.add_enabled(!separate, egui::Checkbox::new(&mut only, label)).changed() {
+                            self.render_settings.export_tracks = if only { track } else { TrackType::Both };
+                        }
+                    }
+                    ui.checkbox(&mut self.render_settings.separate_track_files, &self.text.export_separate_tracks);
+                    ui.checkbox(&mut self.render_settings.auto_fill_gaps, &self.text.auto_fill_gaps);
+                    ui.checkbox(&mut self.render_settings.render_range_only, &self.text.render_range_only)
+                        .on_hover_text(&self.text.render_range_only_hint);
+                    self.watermark_ui(ui);
+                    ui.horizontal(|ui| {
+                        ui.label(&self.text.stretch_label);
+                        egui::ComboBox::from_id_source("stretch_algorithm")
+                            .selected_text(self.render_settings.stretch_algorithm.to_string())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.render_settings.stretch_algorithm, StretchAlgorithm::Atempo, "atempo");
+                                ui.add_enabled_ui(self.rubberband_available, |ui| {
+                                    ui.selectable_value(&mut self.render_settings.stretch_algorithm, StretchAlgorithm::Rubberband, "Rubberband");
+                                })
+                                .response
+                                .on_hover_text(&self.text.rubberband_note)
+                                .on_disabled_hover_text(&self.text.rubberband_note);
+                            })
+                            .response
+                            .on_hover_text(&self.text.rubberband_note);
+                    });
+                    ui.checkbox(&mut self.render_settings.two_pass, &self.text.two_pass);
+                    if self.render_settings.two_pass {
+                        ui.add(
+                            egui::DragValue::new(&mut self.render_settings.target_bitrate_kbps)
+                                .clamp_range(100..=100_000)
+                                .speed(50)
+                                .suffix(" kbps"),
+                        );
+                    } else {
+                        ui.add(egui::Slider::new(&mut self.render_settings.crf, 0..=51).text("CRF"));
+                    }
+                    ui.add(egui::Slider::new(&mut self.render_settings.audio_bitrate_kbps, 64..=320).text("Audio kbps"));
+                    let render_duration: f32 = self
+                        .clips
+                        .iter()
+                        .filter(|c| c.video_enabled || c.audio_enabled)
+                        .map(|c| c.output_duration())
+                        .sum();
+                    // Nieznany bitrate zrodla: przyjmujemy typowe 8 Mb/s dla 1080p
+                    let source_kbps = if self.source_bitrate_kbps > 0.0 { self.source_bitrate_kbps } else { 8000.0 };
+                    let estimate = if self.render_settings.two_pass {
+                        // Staly bitrate: rozmiar wynika wprost z kbps
+                        (self.render_settings.target_bitrate_kbps + self.render_settings.audio_bitrate_kbps) as f32
+                            * render_duration
+                            / 8.0
+                            / 1024.0
+                    } else {
+                        estimate_output_size_mb(
+                            render_duration,
+                            source_kbps,
+                            self.render_settings.crf,
+                            self.render_settings.audio_bitrate_kbps,
+                        )
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}: ~{estimate:.0} MB", self.text.estimated_size));
+                        ui.label(egui::RichText::new(&self.text.estimate_note).small().color(egui::Color32::GRAY));
+                    });
+                    ui.checkbox(&mut self.render_settings.force_iframe_seek, &self.text.iframe_seek);
+                    ui.label(egui::RichText::new(&self.text.seek_note).small().color(egui::Color32::GRAY));
+                });
+                ui.horizontal(|ui| {
+                    let probing = self.pending_probe.is_some();
+                    let idle = self.render_thread.is_none() && self.queue_thread.is_none();
+                    if ui.add_enabled(idle && !probing, egui::Button::new(&self.text.render_button)).clicked() {
+                        self.start_render();
+                    }
+                    if ui.button(&self.text.add_to_queue).on_hover_text(&self.text.add_to_queue_hint).clicked() {
+                        self.add_to_render_queue();
+                    }
+                    let queued = self.render_queue.lock().map(|q| q.len()).unwrap_or(0);
+                    ui.toggle_value(&mut self.show_render_queue, format!("{} ({queued})", self.text.render_queue_title));
+                    if probing {
+                        draw_arc_spinner(ui, self.probe_spinner_angle, ui.visuals().strong_text_color());
+                        ui.label(egui::RichText::new(&self.text.probing_media).small().color(egui::Color32::GRAY));
+                    }
+                });
+
+                if self.render_thread.is_some() {
                     ui.separator();
-                    ui.label(&self.status);
+                    let p = self.render_progress.lock().map(|p| p.clone()).unwrap_or_default();
+                    ui.add(egui::ProgressBar::new(p.percent / 100.0));
+                    ui.label(format!(
+                        "Rendering: {:.0}% | {:.0}fps | {:.2}x | ETA: {}",
+                        p.percent,
+                        p.fps,
+                        p.speed,
+                        format_eta(p.eta_secs)
+                    ));
+                } else if let Some(entry) = self.log.last() {
+                    ui.separator();
+                    ui.label(egui::RichText::new(&entry.message).color(entry.level.color(ui.visuals())));
                 }
+
+                // Historia komunikatow sesji
+                let error_count = self.log.iter().filter(|e| e.level == LogLevel::Error).count();
+                let logs_header = if error_count > 0 {
+                    format!("Logs  ⚠ {error_count}")
+                } else {
+                    "Logs".to_string()
+                };
+                egui::CollapsingHeader::new(logs_header)
+                    .id_source("logs_panel")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                            for entry in self.log.iter().rev() {
+                                let elapsed = entry.timestamp.duration_since(self.session_start).as_secs();
+                                ui.label(
+                                    egui::RichText::new(format!("[{:02}:{:02}] {}", elapsed / 60, elapsed % 60, entry.message))
+                                        .small()
+                                        .color(entry.level.color(ui.visuals())),
+                                );
+                            }
+                        });
+                    });
             });
+        self.remember_window_layout(ctx, timeline_panel.response.rect.height(), tools_panel.response.rect.width());
+
+        if self.show_secondary_preview {
+            self.secondary_preview_viewport(ctx);
+        }
 
         // Central Panel: Podglad (zajmuje reszte miejsca) + Sterowanie Playback
         egui::CentralPanel::default().show(ctx, |ui| {
+            if self.show_storyboard {
+                self.storyboard_ui(ui);
+                return;
+            }
             let available_size = ui.available_size();
             let controls_height = 40.0;
             let video_height = (available_size.y - controls_height).max(100.0);
             
             // Obszar wideo
             let video_rect_size = egui::vec2(available_size.x, video_height);
-            let (rect, _) = ui.allocate_exact_size(video_rect_size, egui::Sense::hover());
+            let preview_sense = if self.comparison_mode || self.settings.preview_fit == PreviewFit::Native {
+                egui::Sense::drag()
+            } else {
+                egui::Sense::hover()
+            };
+            let (rect, preview_resp) = ui.allocate_exact_size(video_rect_size, preview_sense);
             
             // Rysujemy czarne tlo
             ui.painter().rect_filled(rect, 0.0, egui::Color32::BLACK);
@@ -990,21 +2520,32 @@ impl eframe::App for VideoEditorApp {
                     16.0 / 9.0
                 };
                 
-                // Fit rect inside available rect maintaining aspect ratio
-                let mut draw_width = rect.width();
-                let mut draw_height = rect.width() / video_aspect;
-                
-                if draw_height > rect.height() {
-                    draw_height = rect.height();
-                    draw_width = draw_height * video_aspect;
+                // Tryb dopasowania (Fit/Fill/1:1/Stretch); w 1:1 przeciaganie przesuwa widoczny fragment
+                let fit = self.settings.preview_fit;
+                if fit == PreviewFit::Native && !self.comparison_mode && preview_resp.dragged() {
+                    self.preview_pan -= preview_resp.drag_delta();
+                }
+                let native_size = if self.video_width > 0 && self.video_height > 0 {
+                    egui::vec2(self.video_width as f32, self.video_height as f32) / ui.ctx().pixels_per_point()
+                } else {
+                    egui::vec2(rect.width(), rect.width() / video_aspect)
+                };
+                let (mut draw_rect, mut uv) = preview_fit_layout(fit, video_aspect, native_size, rect, &mut self.preview_pan);
+
+                // Podglad kadru wyjsciowego gdy rozdzielczosc renderu ma inne proporcje niz zrodlo
+                if fit == PreviewFit::Fit
+                    && let Some((out_w, out_h)) = self.render_settings.output_resolution.dimensions()
+                {
+                    let out_aspect = out_w as f32 / out_h as f32;
+                    if (out_aspect - video_aspect).abs() > 0.01 {
+                        let frame_rect = fit_aspect(rect, out_aspect);
+                        ui.painter().rect_stroke(frame_rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+                        (draw_rect, uv) = output_frame_layout(self.render_settings.aspect_mode, video_aspect, frame_rect);
+                    }
                 }
-                
-                let draw_rect = egui::Rect::from_center_size(rect.center(), egui::vec2(draw_width, draw_height));
 
                 // Check if playhead is inside any video clip
-                let current_clip = self.clips.iter().find(|c| 
-                    self.playhead >= c.start && self.playhead < c.end && c.video_enabled
-                );
+                let current_clip = self.top_clip_at(self.playhead);
 
                 if let Some(clip) = current_clip {
                     // Software Fade Logic
@@ -1013,7 +2554,7 @@ impl eframe::App for VideoEditorApp {
                     if rel < clip.fade_in {
                         alpha = rel / clip.fade_in.max(0.001);
                     }
-                    let end_rel = clip.end - self.playhead;
+                    let end_rel = clip.timeline_end() - self.playhead;
                     if end_rel < clip.fade_out {
                         alpha = alpha.min(end_rel / clip.fade_out.max(0.001));
                     }
@@ -1021,12 +2562,56 @@ impl eframe::App for VideoEditorApp {
                     let alpha = alpha.clamp(0.0, 1.0);
                     let tint = egui::Color32::from_white_alpha((alpha * 255.0) as u8);
 
-                    let image = egui::Image::new(SizedTexture::new(texture.id(), draw_rect.size())).tint(tint);
-                    egui::Image::paint_at(&image, ui, draw_rect);
+                    if let (true, false, Some(tex_a), Some(tex_b)) = (
+                        self.comparison_mode,
+                        self.is_playing,
+                        &self.comparison_a_texture,
+                        &self.comparison_b_texture,
+                    ) {
+                        // Przeciaganie linii podzialu A/B
+                        if preview_resp.dragged()
+                            && let Some(pos) = preview_resp.interact_pointer_pos()
+                        {
+                            self.comparison_split = ((pos.x - draw_rect.left()) / draw_rect.width()).clamp(0.0, 1.0);
+                        }
+                        let split = self.comparison_split;
+                        let split_x = draw_rect.left() + draw_rect.width() * split;
+                        let split_u = uv.left() + uv.width() * split;
+                        let left_rect = egui::Rect::from_min_max(draw_rect.min, egui::pos2(split_x, draw_rect.bottom()));
+                        let right_rect = egui::Rect::from_min_max(egui::pos2(split_x, draw_rect.top()), draw_rect.max);
+                        let left_uv = egui::Rect::from_min_max(uv.min, egui::pos2(split_u, uv.bottom()));
+                        let right_uv = egui::Rect::from_min_max(egui::pos2(split_u, uv.top()), uv.max);
+                        ui.painter().image(tex_a.id(), left_rect, left_uv, tint);
+                        ui.painter().image(tex_b.id(), right_rect, right_uv, tint);
+                        ui.painter().line_segment(
+                            [egui::pos2(split_x, draw_rect.top()), egui::pos2(split_x, draw_rect.bottom())],
+                            egui::Stroke::new(2.0, egui::Color32::WHITE),
+                        );
+                        let label_font = egui::FontId::proportional(14.0);
+                        ui.painter().text(draw_rect.left_top() + egui::vec2(6.0, 4.0), egui::Align2::LEFT_TOP, "A", label_font.clone(), egui::Color32::WHITE);
+                        ui.painter().text(draw_rect.right_top() + egui::vec2(-6.0, 4.0), egui::Align2::RIGHT_TOP, "B", label_font, egui::Color32::WHITE);
+                        if preview_resp.hovered() {
+                            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                        }
+                    } else {
+                        let image = egui::Image::new(SizedTexture::new(texture.id(), draw_rect.size())).uv(uv).tint(tint);
+                        egui::Image::paint_at(&image, ui, draw_rect);
+                    }
                 } else {
                     // No clip at playhead position -> Draw NOTHING (Black background remains)
                     // Optionally draw logo or placeholder
                 }
+
+                // Uchwyt nakladki zaklada caly kadr w draw_rect (bez przyciecia i z proporcjami)
+                if !self.comparison_mode && fit == PreviewFit::Fit {
+                    self.overlay_position_handle(ui, draw_rect);
+                }
+
+                if self.show_histogram
+                    && let Some(hist) = &self.preview_histogram
+                {
+                    draw_histogram(ui.painter(), draw_rect, hist);
+                }
             } else {
                  ui.painter().text(
                     rect.center(),
@@ -1053,14 +2638,7 @@ impl eframe::App for VideoEditorApp {
                         }
                         // Play
                         if ui.add_enabled(!self.is_playing, egui::Button::new("▶")).clicked() {
-                            if self.duration > 0.0 {
-                                self.is_playing = true;
-                                self.last_tick = Some(Instant::now());
-                                if let Err(err) = self.start_playback() {
-                                    self.status = format!("Blad odtwarzania: {err:#}");
-                                    self.is_playing = false;
-                                }
-                            }
+                            self.toggle_playback();
                         }
                         // >>
                         if ui.button("⏭").clicked() {
@@ -1068,6 +2646,40 @@ impl eframe::App for VideoEditorApp {
                             self.stop_playback();
                             user_seeked = true;
                         }
+                        ui.separator();
+                        if ui.toggle_value(&mut self.comparison_mode, &self.text.compare_ab).changed() {
+                            // Wymus przebudowe podgladu (dwie klatki w trybie A/B)
+                            self.last_preview_playhead = -1.0;
+                            self.last_preview_time = None;
+                            user_seeked = true;
+                        }
+                        if ui.toggle_value(&mut self.show_histogram, &self.text.histogram).changed() {
+                            self.preview_histogram = None;
+                            self.last_preview_playhead = -1.0;
+                            self.last_preview_time = None;
+                            user_seeked = true;
+                        }
+                        let fit = self.settings.preview_fit;
+                        if ui.button(format!("⛶ {fit}")).on_hover_text(&self.text.preview_fit_hint).clicked() {
+                            self.settings.preview_fit = fit.next();
+                            self.preview_pan = egui::Vec2::ZERO;
+                        }
+                        ui.separator();
+                        for quality in PreviewQuality::ALL {
+                            if ui.radio_value(&mut self.settings.preview_quality, quality, quality.to_string()).changed() {
+                                self.last_preview_playhead = -1.0;
+                                self.last_preview_time = None;
+                                user_seeked = true;
+                            }
+                        }
+                        // Podczas przeciagania podglad idzie w trybie Draft niezaleznie od ustawienia
+                        let (quality, width) = if self.dragging_playhead && self.settings.live_drag_preview {
+                            (PreviewQuality::Draft, PreviewQuality::Draft.width(self.video_width))
+                        } else {
+                            (self.settings.preview_quality, self.preview_width())
+                        };
+                        let height = (width * self.video_height).checked_div(self.video_width).unwrap_or(0);
+                        ui.label(egui::RichText::new(format!("{quality} {width}x{height}")).small().color(egui::Color32::GRAY));
                     });
                 });
             });
@@ -1081,11 +2693,15 @@ impl eframe::App for VideoEditorApp {
             }
         }
 
-        if self.was_dragging_playhead && !self.dragging_playhead && !self.is_playing {
-            self.maybe_update_preview(ctx);
+        if self.was_dragging_playhead && !self.dragging_playhead {
+            self.stop_audio_scrub();
+            if !self.is_playing {
+                self.maybe_update_preview(ctx);
+            }
         }
-        if self.dragging_playhead && !self.is_playing && self.live_drag_preview {
+        if self.dragging_playhead && !self.is_playing && self.settings.live_drag_preview {
             self.maybe_update_preview_drag(ctx);
+            self.maybe_scrub_audio();
             ctx.request_repaint();
         }
         self.was_dragging_playhead = self.dragging_playhead;
@@ -1105,8 +2721,74 @@ impl eframe::App for VideoEditorApp {
 
 
 
+/// Cala os czasu w jednym pasku nad timeline; ramka to widoczny fragment. Klik centruje widok,
+/// przeciagniecie zaznacza zakres, ktory po puszczeniu wypelnia cala szerokosc osi czasu.
+fn draw_timeline_minimap(ui: &mut egui::Ui, app: &mut VideoEditorApp) {
+    if app.duration <= 0.0 {
+        return;
+    }
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 18.0), egui::Sense::click_and_drag());
+    // Te same marginesy co w draw_timeline (kolumna etykiet sciezek 60 px), zeby szerokosci sie zgadzaly
+    let left = rect.left() + 8.0 + 60.0;
+    let right = rect.right() - 8.0;
+    let width = (right - left).max(1.0);
+    let bar = egui::Rect::from_min_max(egui::pos2(left, rect.top()), egui::pos2(right, rect.bottom()));
+    let scale = width / app.duration;
+    let time_at = |x: f32| ((x - left) / scale).clamp(0.0, app.duration);
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(bar, 2.0, egui::Color32::from_gray(22));
+    for clip in &app.clips {
+        let x0 = left + clip.start * scale;
+        let x1 = (left + clip.timeline_end() * scale).max(x0 + 1.0);
+        let color = clip.color_label.color32().unwrap_or(egui::Color32::from_rgb(70, 100, 150));
+        let clip_rect = egui::Rect::from_min_max(egui::pos2(x0, bar.top() + 3.0), egui::pos2(x1, bar.bottom() - 3.0));
+        painter.rect_filled(clip_rect, 1.0, color.linear_multiply(0.7));
+    }
+    let view_x0 = left + app.timeline_offset * scale;
+    let view_x1 = view_x0 + width / app.timeline_zoom.max(0.001) * scale;
+    let view = egui::Rect::from_min_max(egui::pos2(view_x0, bar.top()), egui::pos2(view_x1.min(right), bar.bottom()));
+    painter.rect_stroke(view, 2.0, egui::Stroke::new(1.5, app.settings.accent_color));
+    let playhead_x = left + app.playhead * scale;
+    painter.line_segment([egui::pos2(playhead_x, bar.top()), egui::pos2(playhead_x, bar.bottom())], egui::Stroke::new(1.0, egui::Color32::RED));
+
+    let pointer_x = ui.input(|i| i.pointer.interact_pos()).map(|p| p.x.clamp(left, right));
+    if response.drag_started() {
+        app.minimap_drag_start = pointer_x;
+    }
+    if let (Some(start_x), Some(x)) = (app.minimap_drag_start, pointer_x) {
+        let selection = egui::Rect::from_min_max(egui::pos2(start_x.min(x), bar.top()), egui::pos2(start_x.max(x), bar.bottom()));
+        painter.rect_filled(selection, 0.0, app.settings.accent_color.gamma_multiply(0.3));
+    }
+    if response.drag_stopped() {
+        if let (Some(start_x), Some(x)) = (app.minimap_drag_start.take(), pointer_x) {
+            let (range_start, range_end) = (time_at(start_x.min(x)), time_at(start_x.max(x)));
+            // Zakres krotszy niz klatka traktujemy jak klikniecie
+            if range_end - range_start > 1.0 / app.video_fps.max(1.0) {
+                app.timeline_zoom = width / (range_end - range_start);
+                app.timeline_offset = range_start;
+            }
+        }
+    } else if response.clicked()
+        && let Some(x) = pointer_x
+    {
+        let window = width / app.timeline_zoom.max(0.001);
+        app.timeline_offset = clamp_offset(time_at(x) - window * 0.5, app.duration, window);
+    }
+}
+
+/// Kolor waveformu i nazwy pliku zastepczego audio klipu
+const OVERRIDE_WAVEFORM_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 220, 160);
+
+/// Dlugosc klipu z zasobu upuszczonego na timeline (obraz bez dlugosci: 10 s, min. 5 s)
+fn library_drop_length(asset: &MediaAsset) -> f32 {
+    let duration = if asset.duration < 0.1 { 10.0 } else { asset.duration };
+    duration.max(5.0)
+}
+
 fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
-    let desired_height = 160.0;
+    // Timeline wypelnia panel (zostawiamy miejsce na pasek przyciskow pod spodem)
+    let desired_height = (ui.available_height() - 34.0).max(160.0);
     let (rect, response) = ui.allocate_exact_size(
         egui::vec2(ui.available_width(), desired_height),
         egui::Sense::click_and_drag(),
@@ -1116,7 +2798,9 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
 
     // Parametry Layoutu
     let ruler_height = 24.0;
-    let left = rect.left() + 8.0;
+    // Kolumna etykiet sciezek po lewej
+    let label_width = 60.0;
+    let left = rect.left() + 8.0 + label_width;
     let right = rect.right() - 8.0;
     let width = (right - left).max(1.0);
 
@@ -1146,18 +2830,13 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                  if let Some(asset) = app.media_library.get(asset_idx) {
                      println!("DEBUG: Dropping asset {} at time {}", asset.name, drop_time);
                      println!("DEBUG: Dropping asset {} at time {}", asset.name, drop_time);
-                     let asset_duration = if asset.duration < 0.1 { 10.0 } else { asset.duration };
-                     let clip_end = drop_time + asset_duration;
-                     
+                     let clip_end = drop_time + library_drop_length(asset);
+
                      app.clips.push(Clip {
-                         asset_id: Some(asset_idx),
-                         start: drop_time,
-                         end: drop_time + asset_duration.max(5.0), // Ensure at least 5s length
-                         fade_in: 0.0,
-                         fade_out: 0.0,
                          linked: asset.kind == MediaType::Video,
                          video_enabled: asset.kind != MediaType::Audio,
                          audio_enabled: asset.kind != MediaType::Image,
+                         ..Clip::new(Some(asset_idx), drop_time, clip_end)
                      });
                      app.selected_clip = Some(app.clips.len() - 1);
                      
@@ -1167,7 +2846,7 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                      }
                      // If first video, set dimensions
                      if (asset.kind == MediaType::Video || asset.kind == MediaType::Image) && (app.video_width == 0 || app.video_height == 0) {
-                        if let Ok((_, w, h, fps)) = get_video_info_ffprobe(&asset.path) {
+                        if let Ok((_, w, h, fps, _)) = get_video_info_ffprobe(&asset.path) {
                              app.video_width = w;
                              app.video_height = h;
                              if fps > 0.0 {
@@ -1176,7 +2855,7 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                         }
                      }
                      
-                     app.status = format!("Dropped: {}", asset.name);
+                     app.log.push(LogEntry::info(format!("Dropped: {}", asset.name)));
                      app.dragging_library_asset = None;
                      return true; // Input handled, return true
                  }
@@ -1200,20 +2879,143 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         egui::pos2(left, rect.top()),
         egui::pos2(right, rect.top() + ruler_height),
     );
-    // Klipy (przesunięte w dół)
+    // Pasek timecode'ow granic klipow pod linijka
+    let boundary_rect = egui::Rect::from_min_max(
+        egui::pos2(left, ruler_rect.bottom()),
+        egui::pos2(right, ruler_rect.bottom() + 14.0),
+    );
+    // Klipy (przesunięte w dół) - podzial miedzy sciezki wg track_height_*
+    let tracks_top = boundary_rect.bottom() + 2.0;
+    // Pasek timecode'ow poczatku/konca klipow pod sciezka audio
+    let clip_labels_height = 14.0;
+    let tracks_bottom = rect.bottom() - 2.0 - clip_labels_height;
+    let split_handle_height = 6.0;
+    let tracks_available = (tracks_bottom - tracks_top - split_handle_height).max(40.0);
+    let total_track_height = (app.track_height_video + app.track_height_audio).max(1.0);
+    let video_height = tracks_available * app.track_height_video / total_track_height;
     let video_rect = egui::Rect::from_min_max(
-        egui::pos2(left, ruler_rect.bottom() + 4.0),
-        egui::pos2(right, ruler_rect.bottom() + 4.0 + (rect.height() - ruler_height - 8.0) * 0.5),
+        egui::pos2(left, tracks_top),
+        egui::pos2(right, tracks_top + video_height),
+    );
+    let split_handle_rect = egui::Rect::from_min_max(
+        egui::pos2(left, video_rect.bottom()),
+        egui::pos2(right, video_rect.bottom() + split_handle_height),
     );
     let audio_rect = egui::Rect::from_min_max(
-        egui::pos2(left, video_rect.bottom() + 2.0),
-        egui::pos2(right, rect.bottom() - 2.0),
+        egui::pos2(left, split_handle_rect.bottom()),
+        egui::pos2(right, tracks_bottom),
     );
 
+    // Uchwyt zmiany wysokosci sciezek
+    let split_resp = ui.interact(split_handle_rect, ui.id().with("track_split"), egui::Sense::drag());
+    if split_resp.dragged()
+        && let Some(pos) = split_resp.interact_pointer_pos()
+    {
+        let max_video = 200.0f32.min(tracks_available - 20.0).max(20.0);
+        let new_video = (pos.y - tracks_top).clamp(20.0, max_video);
+        app.track_height_video = new_video;
+        app.track_height_audio = tracks_available - new_video;
+    }
+    let split_active = split_resp.hovered() || split_resp.dragged();
+    if split_active {
+        painter.rect_filled(split_handle_rect.shrink2(egui::vec2(0.0, 2.0)), 1.0, egui::Color32::from_gray(90));
+    }
+
     painter.rect_filled(ruler_rect, 0.0, egui::Color32::from_gray(25));
     painter.rect_filled(video_rect, 4.0, egui::Color32::from_gray(40));
     painter.rect_filled(audio_rect, 4.0, egui::Color32::from_gray(35));
 
+    // Wiersze sciezek wideo: V1 na dole, wyzsze sciezki nad nia
+    let video_track_count = app.clips.iter().map(|c| c.track_index).max().unwrap_or(0) + 1;
+    let track_row_height = video_rect.height() / video_track_count as f32;
+    let video_track_rect = |track: u32| {
+        let bottom = video_rect.bottom() - track as f32 * track_row_height;
+        egui::Rect::from_min_max(egui::pos2(video_rect.left(), bottom - track_row_height), egui::pos2(video_rect.right(), bottom))
+    };
+
+    // Etykiety sciezek z przyciskiem blokady
+    for (track_rect, name, locked, id) in [
+        (video_rect, app.track_video_name.clone(), &mut app.track_video_locked, "lock_video"),
+        (audio_rect, app.track_audio_name.clone(), &mut app.track_audio_locked, "lock_audio"),
+    ] {
+        let label_rect = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + 4.0, track_rect.top()),
+            egui::pos2(left - 4.0, track_rect.bottom()),
+        );
+        painter.rect_filled(label_rect, 4.0, egui::Color32::from_gray(45));
+        painter.text(
+            label_rect.left_top() + egui::vec2(4.0, 4.0),
+            egui::Align2::LEFT_TOP,
+            name,
+            egui::TextStyle::Small.resolve(ui.style()),
+            egui::Color32::from_gray(200),
+        );
+        let lock_rect = egui::Rect::from_min_size(label_rect.left_bottom() + egui::vec2(4.0, -20.0), egui::vec2(16.0, 16.0));
+        let lock_resp = ui.interact(lock_rect, ui.id().with(id), egui::Sense::click())
+            .on_hover_text("Lock track");
+        if lock_resp.clicked() {
+            *locked = !*locked;
+        }
+        let lock_color = if *locked { egui::Color32::from_rgb(230, 160, 60) } else { egui::Color32::from_gray(120) };
+        painter.text(
+            lock_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            if *locked { "🔒" } else { "🔓" },
+            egui::FontId::proportional(12.0),
+            lock_color,
+        );
+    }
+    // Przyciski M (mute) / S (solo) odsluchu dla kazdej sciezki
+    let track_count = video_track_count as usize;
+    for flags in [&mut app.track_muted, &mut app.track_soloed] {
+        if flags.len() < track_count {
+            flags.resize(track_count, false);
+        }
+    }
+    for track in 0..video_track_count {
+        let row = video_track_rect(track);
+        let y = row.center().y - 5.5;
+        for (offset, text, solo) in [(25.0, "M", false), (37.0, "S", true)] {
+            let button_rect = egui::Rect::from_min_size(egui::pos2(rect.left() + offset, y), egui::vec2(11.0, 11.0));
+            let flags = if solo { &mut app.track_soloed } else { &mut app.track_muted };
+            let on = flags[track as usize];
+            let hover = if solo { "Solo" } else { "Mute" };
+            let resp = ui
+                .interact(button_rect, ui.id().with("track_monitor").with(track).with(solo), egui::Sense::click())
+                .on_hover_text(format!("{hover} V{}", track + 1));
+            if resp.clicked() {
+                flags[track as usize] = !on;
+            }
+            let active = if solo { egui::Color32::from_rgb(220, 190, 60) } else { egui::Color32::from_rgb(220, 80, 70) };
+            painter.rect_filled(button_rect, 2.0, if on { active } else { egui::Color32::from_gray(60) });
+            painter.text(
+                button_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(9.0),
+                if on { egui::Color32::BLACK } else { egui::Color32::from_gray(190) },
+            );
+        }
+    }
+    if video_track_count > 1 {
+        for track in 0..video_track_count {
+            let row = video_track_rect(track);
+            if track > 0 {
+                painter.line_segment(
+                    [egui::pos2(rect.left() + 4.0, row.bottom()), egui::pos2(right, row.bottom())],
+                    egui::Stroke::new(1.0, egui::Color32::from_gray(60)),
+                );
+            }
+            painter.text(
+                egui::pos2(left - 8.0, row.center().y),
+                egui::Align2::RIGHT_CENTER,
+                format!("V{}", track + 1),
+                egui::TextStyle::Small.resolve(ui.style()),
+                egui::Color32::from_gray(170),
+            );
+        }
+    }
+
     // Zoom i Offset Logic
     let min_zoom = width / app.duration.max(0.01);
     if app.timeline_zoom <= 0.0 {
@@ -1221,6 +3023,7 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
     }
     let max_zoom = 800.0;
     app.timeline_zoom = app.timeline_zoom.clamp(min_zoom, max_zoom);
+    app.maybe_regenerate_waveform(ui.ctx(), app.timeline_zoom / min_zoom);
     let window = width / app.timeline_zoom;
     app.timeline_offset = clamp_offset(app.timeline_offset, app.duration, window);
 
@@ -1256,17 +3059,175 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
          t += step;
     }
 
+    // Siatka klatek w linijce przy duzym zblizeniu (min. 6 px na klatke)
+    let fps = app.video_fps;
+    if app.settings.timeline_snap_to_frames && fps > 0.0 && app.timeline_zoom / fps >= 6.0 {
+        let first = (app.timeline_offset * fps).floor() as i64;
+        let last = ((app.timeline_offset + window) * fps).ceil() as i64;
+        for frame in first..=last {
+            let x = left + (frame as f32 / fps - app.timeline_offset) * app.timeline_zoom;
+            if x >= left && x <= right {
+                painter.line_segment(
+                    [egui::pos2(x, ruler_rect.bottom()), egui::pos2(x, ruler_rect.bottom() - 3.0)],
+                    egui::Stroke::new(1.0, egui::Color32::from_gray(70)),
+                );
+            }
+        }
+    }
+
+    // Znaczniki mark in / mark out na linijce: klik = skok, PPM = usuniecie
+    let mut marker_seek = false;
+    let mark_x = |t: f32| left + (t - app.timeline_offset) * app.timeline_zoom;
+    if let (Some(mark_in), Some(mark_out)) = (app.mark_in, app.mark_out)
+        && mark_out > mark_in
+    {
+        let x0 = mark_x(mark_in).max(left);
+        let x1 = mark_x(mark_out).min(right);
+        if x1 > x0 {
+            // Przy renderze samego zakresu pelny zielony pas: widac, co trafi do pliku
+            let alpha = if app.render_settings.render_range_only { 170 } else { 40 };
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(x0, ruler_rect.top()), egui::pos2(x1, ruler_rect.bottom())),
+                0.0,
+                egui::Color32::from_rgba_unmultiplied(60, 200, 90, alpha),
+            );
+        }
+    }
+    // Znaczniki rozdzialow: zolte flagi (klik = skok, PPM = usuniecie, tytul w podpowiedzi)
+    let mut remove_marker = None;
+    for (i, marker) in app.markers.iter().enumerate() {
+        let x = mark_x(marker.time);
+        if x < left - 6.0 || x > right + 6.0 {
+            continue;
+        }
+        let top = ruler_rect.top() + 2.0;
+        let flag = vec![egui::pos2(x - 5.0, top), egui::pos2(x + 5.0, top), egui::pos2(x, top + 8.0)];
+        painter.add(egui::Shape::convex_polygon(flag, egui::Color32::from_rgb(240, 200, 60), egui::Stroke::NONE));
+        painter.line_segment(
+            [egui::pos2(x, top + 8.0), egui::pos2(x, ruler_rect.bottom())],
+            egui::Stroke::new(1.0, egui::Color32::from_rgb(240, 200, 60)),
+        );
+        let flag_rect = egui::Rect::from_min_max(egui::pos2(x - 6.0, top), egui::pos2(x + 6.0, top + 10.0));
+        let flag_resp = ui
+            .interact(flag_rect, ui.id().with("chapter_marker").with(i), egui::Sense::click())
+            .on_hover_text(&marker.title);
+        if flag_resp.clicked() {
+            app.playhead = marker.time;
+            marker_seek = true;
+        }
+        if flag_resp.secondary_clicked() {
+            remove_marker = Some(i);
+        }
+    }
+    if let Some(i) = remove_marker {
+        app.markers.remove(i);
+    }
+    // Wykryte uderzenia - krotkie pomaranczowe kreski u dolu linijki
+    for &beat in &app.beat_markers {
+        let x = mark_x(beat);
+        if x >= left && x <= right {
+            painter.line_segment(
+                [egui::pos2(x, ruler_rect.bottom() - 7.0), egui::pos2(x, ruler_rect.bottom())],
+                egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 150, 40)),
+            );
+        }
+    }
+    let marker_top = ruler_rect.top() + 2.0;
+    for is_in in [true, false] {
+        let Some(time) = (if is_in { app.mark_in } else { app.mark_out }) else { continue };
+        let x = mark_x(time);
+        if x < left - 8.0 || x > right + 8.0 {
+            continue;
+        }
+        // ▶ zaczyna sie w punkcie wejscia, ◀ konczy w punkcie wyjscia
+        let (points, color, marker_rect) = if is_in {
+            (
+                vec![egui::pos2(x, marker_top), egui::pos2(x + 8.0, marker_top + 5.0), egui::pos2(x, marker_top + 10.0)],
+                egui::Color32::from_rgb(60, 200, 90),
+                egui::Rect::from_min_max(egui::pos2(x - 2.0, marker_top), egui::pos2(x + 10.0, marker_top + 10.0)),
+            )
+        } else {
+            (
+                vec![egui::pos2(x, marker_top), egui::pos2(x, marker_top + 10.0), egui::pos2(x - 8.0, marker_top + 5.0)],
+                egui::Color32::from_rgb(220, 60, 60),
+                egui::Rect::from_min_max(egui::pos2(x - 10.0, marker_top), egui::pos2(x + 2.0, marker_top + 10.0)),
+            )
+        };
+        painter.add(egui::Shape::convex_polygon(points, color, egui::Stroke::NONE));
+        let marker_resp = ui.interact(marker_rect, ui.id().with("mark_marker").with(is_in), egui::Sense::click());
+        if marker_resp.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+        if marker_resp.clicked() {
+            app.playhead = time;
+            marker_seek = true;
+        }
+        let clear_label = if is_in { app.text.clear_mark_in.clone() } else { app.text.clear_mark_out.clone() };
+        marker_resp.context_menu(|ui| {
+            if ui.button(clear_label).clicked() {
+                if is_in {
+                    app.mark_in = None;
+                } else {
+                    app.mark_out = None;
+                }
+                ui.close_menu();
+            }
+        });
+    }
+
+    // Timecode'y na granicach klipow; nachodzace na siebie pomijamy (poza zaznaczonym klipem)
+    let mut boundaries: Vec<(f32, bool)> = Vec::new();
+    for (idx, clip) in app.clips.iter().enumerate() {
+        let selected = app.selected_clip == Some(idx);
+        for time in [clip.start, clip.timeline_end()] {
+            match boundaries.iter_mut().find(|(t, _)| (t - time).abs() < 0.001) {
+                Some(existing) => existing.1 |= selected,
+                None => boundaries.push((time, selected)),
+            }
+        }
+    }
+    boundaries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.total_cmp(&b.0)));
+    let font = egui::TextStyle::Small.resolve(ui.style());
+    let mut occupied: Vec<(f32, f32)> = Vec::new();
+    for (time, selected) in boundaries {
+        let x = left + (time - app.timeline_offset) * app.timeline_zoom;
+        if x < left || x > right {
+            continue;
+        }
+        let color = if selected { egui::Color32::YELLOW } else { egui::Color32::from_gray(150) };
+        let galley = painter.layout_no_wrap(secs_to_timecode(time, app.video_fps, app.drop_frame), font.clone(), color);
+        let half = galley.size().x * 0.5;
+        let span = ((x - half).max(left), (x + half).min(right));
+        if !selected && occupied.iter().any(|&(a, b)| span.0 < b + 4.0 && span.1 > a - 4.0) {
+            continue;
+        }
+        occupied.push(span);
+        let label_x = (x - half).clamp(left, (right - galley.size().x).max(left));
+        let pos = egui::pos2(label_x, boundary_rect.center().y - galley.size().y * 0.5);
+        painter.galley(pos, galley, color);
+    }
+
+
 
+    // Zoom z klawiatury: playhead zostaje w tym samym miejscu ekranu
+    if let Some(factor) = app.timeline_zoom_request.take() {
+        let play_x = (left + (app.playhead - app.timeline_offset) * app.timeline_zoom).clamp(left, right);
+        zoom_timeline_at(app, factor, play_x, left, width, min_zoom, max_zoom);
+    }
 
     if response.hovered() {
-        let (scroll_y, scroll_x, modifiers) = ui.ctx().input(|i| {
+        let (scroll_y, scroll_x, modifiers, pinch) = ui.ctx().input(|i| {
             (
                 i.smooth_scroll_delta.y,
                 i.smooth_scroll_delta.x,
                 i.modifiers,
+                i.zoom_delta(),
             )
         });
-        if scroll_y.abs() > 0.0 {
+        if pinch != 1.0 {
+            // Gest pinch na trackpadzie: zoom wzgledem srodka timeline
+            zoom_timeline_at(app, pinch, left + width * 0.5, left, width, min_zoom, max_zoom);
+        } else if scroll_y.abs() > 0.0 {
             let zoom_factor = if scroll_y > 0.0 { 1.1 } else { 0.9 };
             let mouse_x = ui.ctx().pointer_latest_pos().map(|p| p.x).unwrap_or(left);
             let t_at_mouse =
@@ -1293,7 +3254,7 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         );
     }
 
-    if app.thumb_textures.is_empty() {
+    if app.thumb_times.is_empty() {
         painter.text(
             video_rect.center(),
             egui::Align2::CENTER_CENTER,
@@ -1309,18 +3270,45 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
 
     let mut remove_clip_idx: Option<(usize, TrackType)> = None;
     let mut toggle_link_idx: Option<usize> = None;
+    // (klip, sciezka, lewa krawedz?, czas) - przyciecie obrazu/dzwieku rozlaczonego klipu
+    let mut track_trim_request: Option<(usize, TrackType, bool, f32)> = None;
+    let mut track_trim_started = false;
+    let mut move_request: Option<(usize, f32)> = None;
+    let mut cut_request: Option<(usize, f32)> = None;
+    // (klip, trafiony punkt do usuniecia, czas w klipie, wzmocnienie)
+    let mut envelope_request: Option<(usize, Option<usize>, f32, f32)> = None;
+    let mut batch_color_request: Option<ClipColor> = None;
+    let mut group_request: Option<GroupMenuAction> = None;
+    let mut clip_menu_request: Option<ClipMenuAction> = None;
+    // Obrys grup: id -> (suma prostokatow wideo, suma prostokatow audio)
+    let mut group_rects: HashMap<u32, (egui::Rect, egui::Rect)> = HashMap::new();
+    let mut hovered_clip: Option<usize> = None;
+    let selection_count = app.selected_indices().len();
 
     for (idx, clip) in app.clips.iter().enumerate() {
         let start_x = left + (clip.start - app.timeline_offset) * app.timeline_zoom;
-        let end_x = left + (clip.end - app.timeline_offset) * app.timeline_zoom;
+        let end_x = left + (clip.timeline_end() - app.timeline_offset) * app.timeline_zoom;
+        let track_rect = video_track_rect(clip.track_index);
+        // Rozlaczony klip moze miec obraz i dzwiek przyciete inaczej
+        let (video_t0, video_t1) = clip.video_range();
+        let (audio_t0, audio_t1) = clip.audio_range();
         let video_clip_rect = egui::Rect::from_min_max(
-            egui::pos2(start_x, video_rect.top()),
-            egui::pos2(end_x, video_rect.bottom()),
+            egui::pos2(left + (video_t0 - app.timeline_offset) * app.timeline_zoom, track_rect.top()),
+            egui::pos2(left + (video_t1 - app.timeline_offset) * app.timeline_zoom, track_rect.bottom()),
         );
         let audio_clip_rect = egui::Rect::from_min_max(
-            egui::pos2(start_x, audio_rect.top()),
-            egui::pos2(end_x, audio_rect.bottom()),
+            egui::pos2(left + (audio_t0 - app.timeline_offset) * app.timeline_zoom, audio_rect.top()),
+            egui::pos2(left + (audio_t1 - app.timeline_offset) * app.timeline_zoom, audio_rect.bottom()),
         );
+        if let Some(id) = clip.group_id {
+            group_rects
+                .entry(id)
+                .and_modify(|(v, a)| {
+                    *v = v.union(video_clip_rect);
+                    *a = a.union(audio_clip_rect);
+                })
+                .or_insert((video_clip_rect, audio_clip_rect));
+        }
 
         // Separate interactions for video and audio when unlinked
         let clip_sense = if app.tool == Tool::Hand {
@@ -1328,8 +3316,42 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         } else {
             egui::Sense::click()
         };
-        let video_resp = ui.interact(video_clip_rect, ui.id().with("clip_video").with(idx), clip_sense);
-        let audio_resp = ui.interact(audio_clip_rect, ui.id().with("clip_audio").with(idx), clip_sense);
+        // Zablokowane sciezki nie reaguja na klikniecia/przeciaganie
+        let video_sense = if app.track_video_locked { egui::Sense::hover() } else { clip_sense };
+        let audio_sense = if app.track_audio_locked { egui::Sense::hover() } else { clip_sense };
+        let video_resp = ui.interact(video_clip_rect, ui.id().with("clip_video").with(idx), video_sense);
+        let audio_resp = ui.interact(audio_clip_rect, ui.id().with("clip_audio").with(idx), audio_sense);
+
+        if video_resp.hovered() || audio_resp.hovered() {
+            hovered_clip = Some(idx);
+        }
+
+        // Krawedzie rozlaczonego klipu: przeciaganie przycina obraz albo dzwiek osobno
+        if !clip.linked && app.tool == Tool::Hand {
+            let tracks = [
+                (TrackType::Video, video_clip_rect, clip.video_enabled && !app.track_video_locked),
+                (TrackType::Audio, audio_clip_rect, clip.audio_enabled && !app.track_audio_locked),
+            ];
+            for (track, rect, editable) in tracks {
+                if !editable || rect.width() < 12.0 {
+                    continue;
+                }
+                for (is_start, x) in [(true, rect.left()), (false, rect.right())] {
+                    let edge = egui::Rect::from_x_y_ranges(x - 3.0..=x + 3.0, rect.y_range());
+                    let edge_id = ui.id().with("clip_track_edge").with((idx, track == TrackType::Video, is_start));
+                    let resp = ui.interact(edge, edge_id, egui::Sense::drag()).on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+                    if resp.drag_started() {
+                        track_trim_started = true;
+                    }
+                    if resp.dragged()
+                        && let Some(pos) = resp.interact_pointer_pos()
+                    {
+                        let t = app.timeline_offset + (pos.x - left) / app.timeline_zoom;
+                        track_trim_request = Some((idx, track, is_start, t));
+                    }
+                }
+            }
+        }
 
         // Get click position for cutting
         let click_pos = video_resp.interact_pointer_pos().or(audio_resp.interact_pointer_pos());
@@ -1350,8 +3372,8 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
             if let Some(pos) = click_pos {
                 let t = app.timeline_offset + ((pos.x - left) / app.timeline_zoom);
                 let new_start = (t - app.drag_clip_offset).max(0.0);
-                // Store the move request as status (will process after loop)
-                app.status = format!("MOVE:{}:{}", idx, new_start);
+                // Store the move request (will process after loop)
+                move_request = Some((idx, new_start));
             }
         }
 
@@ -1366,15 +3388,43 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                 // Blade Tool - cut the clip at mouse position
                 if let Some(pos) = click_pos {
                     let t = app.timeline_offset + ((pos.x - left) / app.timeline_zoom).clamp(0.0, window);
-                    if t > clip.start && t < clip.end {
-                        // We need to defer the cut to after the loop to avoid borrow issues
-                        // Store info for later
-                        // For now, we'll use a workaround - store cut request
-                        app.status = format!("CUT:{}:{}", idx, t);
+                    if t > clip.start && t < clip.timeline_end() {
+                        // Defer the cut to after the loop to avoid borrow issues
+                        cut_request = Some((idx, t));
                     }
                 }
+            } else if app.tool == Tool::Envelope {
+                // Envelope Tool - klik dodaje punkt, klik w punkt go usuwa
+                if audio_resp.clicked()
+                    && let Some(pos) = click_pos
+                {
+                    let clip_t = ((pos.x - start_x) / app.timeline_zoom).clamp(0.0, clip.end - clip.start);
+                    let gain = envelope_gain_from_y(audio_clip_rect, pos.y);
+                    let hit = clip.volume_keyframes.iter().position(|&(kt, kg)| {
+                        egui::pos2(start_x + kt * app.timeline_zoom, envelope_y(audio_clip_rect, kg)).distance(pos) < 6.0
+                    });
+                    envelope_request = Some((idx, hit, clip_t, gain));
+                }
             } else {
-                // Normal selection
+                // Normal selection (Ctrl+klik dodaje/usuwa z zaznaczenia)
+                if ui.input(|i| i.modifiers.command) {
+                    if app.selected_clips.is_empty()
+                        && let Some(prev) = app.selected_clip
+                    {
+                        app.selected_clips.push(prev);
+                    }
+                    if let Some(pos) = app.selected_clips.iter().position(|&i| i == idx) {
+                        app.selected_clips.remove(pos);
+                    } else {
+                        app.selected_clips.push(idx);
+                    }
+                } else {
+                    app.selected_clips.clear();
+                    // Klik w klip z grupy zaznacza cala grupe
+                    if clip.group_id.is_some() {
+                        group_request = Some(GroupMenuAction::Select(idx));
+                    }
+                }
                 if video_resp.clicked() {
                     app.selected_clip = Some(idx);
                     app.selected_track = if clip.linked { TrackType::Both } else { TrackType::Video };
@@ -1416,8 +3466,19 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                         }
                     }
                 }
+                if let Some(action) = clip_edit_menu(ui, &app.text, clip, idx, app.playhead, next_clip_start(&app.clips, idx)) {
+                    clip_menu_request = Some(action);
+                }
+                if selection_count >= 2
+                    && let Some(color) = batch_color_menu(ui, &app.text.apply_color_selected)
+                {
+                    batch_color_request = Some(color);
+                }
+                if let Some(action) = group_menu(ui, &app.text, clip, idx, selection_count) {
+                    group_request = Some(action);
+                }
                 ui.separator();
-                ui.label(if app.ripple_delete { format!("({} On)", app.text.ripple_delete) } else { format!("({} Off)", app.text.ripple_delete) });
+                ui.label(if app.settings.ripple_delete { format!("({} On)", app.text.ripple_delete) } else { format!("({} Off)", app.text.ripple_delete) });
             });
         }
 
@@ -1451,13 +3512,24 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                         ui.close_menu();
                     }
                 }
+                if let Some(action) = clip_edit_menu(ui, &app.text, clip, idx, app.playhead, next_clip_start(&app.clips, idx)) {
+                    clip_menu_request = Some(action);
+                }
+                if selection_count >= 2
+                    && let Some(color) = batch_color_menu(ui, &app.text.apply_color_selected)
+                {
+                    batch_color_request = Some(color);
+                }
+                if let Some(action) = group_menu(ui, &app.text, clip, idx, selection_count) {
+                    group_request = Some(action);
+                }
                 ui.separator();
-                ui.label(if app.ripple_delete { format!("({} On)", app.text.ripple_delete) } else { format!("({} Off)", app.text.ripple_delete) });
+                ui.label(if app.settings.ripple_delete { format!("({} On)", app.text.ripple_delete) } else { format!("({} Off)", app.text.ripple_delete) });
             });
         }
 
         // Visual styling based on selection and link status
-        let is_selected = Some(idx) == app.selected_clip;
+        let is_selected = Some(idx) == app.selected_clip || app.selected_clips.contains(&idx);
         let video_selected = is_selected && (app.selected_track == TrackType::Both || app.selected_track == TrackType::Video);
         let audio_selected = is_selected && (app.selected_track == TrackType::Both || app.selected_track == TrackType::Audio);
 
@@ -1465,22 +3537,24 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         let video_color = if !clip.video_enabled {
             egui::Color32::from_gray(60)
         } else if video_selected {
-            egui::Color32::from_rgb(80, 170, 255)
+            app.settings.accent_color
         } else {
-            egui::Color32::from_rgb(70, 120, 90)
+            clip.color_label.color32().unwrap_or(egui::Color32::from_rgb(70, 120, 90))
         };
 
         // Audio track colors
         let audio_color = if !clip.audio_enabled {
             egui::Color32::from_gray(60)
         } else if audio_selected {
-            egui::Color32::from_rgb(80, 170, 255)
+            app.settings.accent_color
         } else {
             egui::Color32::from_rgb(70, 120, 90)
         };
 
         // Draw thumbnails INSIDE clip bounds (video track)
         // Draw thumbnails (Filmstrip) INSIDE clip bounds
+        // Czas zrodla -> os czasu (predkosc skaluje szerokosc klipu)
+        let source_scale = clip.output_duration() / (clip.end - clip.start).max(0.001);
         if clip.video_enabled && app.duration > 0.0 {
             // Check for library asset filmstrip
             if let Some(asset_id) = clip.asset_id {
@@ -1493,10 +3567,10 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                          let asset_t = *t;
                          // Check if this thumb is within the visible clip duration relative to clip start
                          // Since we don't have trim_in yet, asset_t maps to clip.start + asset_t
-                         let timeline_t = clip.start + asset_t;
+                         let timeline_t = clip.start + (asset_t - clip.source_in) * source_scale;
                          
                          // Skip if outside clip bounds (redundant if clip length == asset length)
-                         if timeline_t < clip.start || timeline_t > clip.end { continue; }
+                         if timeline_t < clip.start || timeline_t > clip.timeline_end() { continue; }
 
                          let _thumb_w = texture.size_vec2().x as f32; // Use actual aspect?
                          let aspect = texture.size_vec2().x / texture.size_vec2().y;
@@ -1508,8 +3582,8 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                          let x1 = x0 + draw_w;
                          
                          // Clipping against clip bounds and view bounds
-                         let clip_x0 = left + (clip.start - app.timeline_offset) * app.timeline_zoom;
-                         let clip_x1 = left + (clip.end - app.timeline_offset) * app.timeline_zoom;
+                         let clip_x0 = start_x;
+                         let clip_x1 = end_x;
                          
                          let final_x0 = x0.max(clip_x0).max(video_rect.left());
                          let final_x1 = x1.min(clip_x1).min(video_rect.right());
@@ -1530,31 +3604,38 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                          }
                      }
                 }
-            } else if !app.thumb_textures.is_empty() {
+            } else if !app.thumb_times.is_empty() {
                  // Fallback to legacy single-file thumbs
-                 let chunk = app.duration / app.thumb_textures.len().max(1) as f32;
-                 let thumb_w = app.timeline_zoom * chunk;
-                 for (tidx, texture) in app.thumb_textures.iter().enumerate() {
+                 let chunk = app.duration / app.thumb_times.len().max(1) as f32;
+                 let thumb_w = app.timeline_zoom * chunk * source_scale;
+                 for (tidx, &t) in app.thumb_times.iter().enumerate() {
                     // (Legacy code preserved for single-file mode logic if needed, but simplified)
-                     let t = app.thumb_times[tidx];
+                     if t.is_nan() { continue; }
                      let thumb_start = t - chunk * 0.5;
                      let thumb_end = t + chunk * 0.5;
-                     if thumb_end < clip.start || thumb_start > clip.end { continue; }
+                     if thumb_end < clip.source_in || thumb_start > clip.source_out() { continue; }
                      
-                     let x0 = left + (thumb_start - app.timeline_offset) * app.timeline_zoom;
+                     let x0 = start_x + (thumb_start - clip.source_in) * source_scale * app.timeline_zoom;
                      let x1 = x0 + thumb_w;
                      
-                     let clip_x0 = left + (clip.start - app.timeline_offset) * app.timeline_zoom;
-                     let clip_x1 = left + (clip.end - app.timeline_offset) * app.timeline_zoom;
+                     let clip_x0 = start_x;
+                     let clip_x1 = end_x;
                      let draw_x0 = x0.max(clip_x0).max(video_rect.left());
                      let draw_x1 = x1.min(clip_x1).min(video_rect.right());
                      
                      if draw_x1 > draw_x0 {
+                        // Widoczny slot bez tekstury -> do kolejki (leniwe dekodowanie)
+                        let Some(texture) = &app.thumb_textures[tidx] else {
+                            if !app.thumb_requests.contains(&t) {
+                                app.thumb_requests.push(t);
+                            }
+                            continue;
+                        };
                         let u0 = ((draw_x0 - x0) / thumb_w).clamp(0.0, 1.0);
                         let u1 = ((draw_x1 - x0) / thumb_w).clamp(0.0, 1.0);
                         painter.image(
                             texture.id(),
-                            egui::Rect::from_min_max(egui::pos2(draw_x0, video_rect.top()), egui::pos2(draw_x1, video_rect.bottom())),
+                            egui::Rect::from_min_max(egui::pos2(draw_x0, video_clip_rect.top()), egui::pos2(draw_x1, video_clip_rect.bottom())),
                             egui::Rect::from_min_max(egui::pos2(u0, 0.0), egui::pos2(u1, 1.0)),
                             egui::Color32::WHITE,
                         );
@@ -1566,26 +3647,48 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         // Draw waveform INSIDE clip bounds (audio track)
         if clip.audio_enabled {
             let mut drawn = false;
-            // Check library waveform
-            if let Some(asset_id) = clip.asset_id {
-                if let Some(texture) = app.media_waveforms.get(&asset_id) {
-                     // Draw full asset waveform stretched over clip duration (since clip is full asset currently)
-                     // If we add trimming later, we'd need to adjust UVs: u0 = trim_in / asset.dur, etc.
-                     painter.image(
-                        texture.id(),
-                        audio_clip_rect,
-                        egui::Rect::from_min_max(egui::pos2(0.0,0.0), egui::pos2(1.0,1.0)),
-                        egui::Color32::WHITE
-                     );
-                     drawn = true;
+            // Zastepcze audio: waveform z pliku override, w innym kolorze
+            if let Some(path) = &clip.audio_override {
+                match app.override_waveforms.get(path) {
+                    Some(Some((texture, audio_duration))) => {
+                        let u0 = (clip.source_in / audio_duration).clamp(0.0, 1.0);
+                        let u1 = ((clip.source_in + clip.end - clip.start) / audio_duration).clamp(0.0, 1.0);
+                        painter.image(
+                            texture.id(),
+                            audio_clip_rect,
+                            egui::Rect::from_min_max(egui::pos2(u0, 0.0), egui::pos2(u1, 1.0)),
+                            OVERRIDE_WAVEFORM_COLOR,
+                        );
+                    }
+                    None if !app.override_waveform_requests.contains(path) => {
+                        app.override_waveform_requests.push(path.clone());
+                    }
+                    _ => {}
                 }
+                drawn = true;
+            }
+            // Check library waveform
+            if !drawn
+                && let Some(asset_id) = clip.asset_id
+                && let Some(texture) = app.media_waveforms.get(&asset_id)
+            {
+                 // Draw full asset waveform stretched over clip duration (since clip is full asset currently)
+                 // If we add trimming later, we'd need to adjust UVs: u0 = trim_in / asset.dur, etc.
+                 painter.image(
+                    texture.id(),
+                    audio_clip_rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0,0.0), egui::pos2(1.0,1.0)),
+                    egui::Color32::WHITE
+                 );
+                 drawn = true;
             }
             
             // Fallback / Legacy waveform
             if !drawn {
                 if let Some(texture) = &app.waveform_texture {
-                    let u0 = (clip.start / app.duration).clamp(0.0, 1.0);
-                    let u1 = (clip.end / app.duration).clamp(0.0, 1.0);
+                    // Zakres zrodla klipu, rozciagniety na szerokosc wyjsciowa
+                    let u0 = (clip.source_in / app.duration).clamp(0.0, 1.0);
+                    let u1 = (clip.source_out() / app.duration).clamp(0.0, 1.0);
                     painter.image(
                         texture.id(),
                         audio_clip_rect,
@@ -1598,12 +3701,21 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
 
         // Draw clip rectangles with filled background
         if clip.video_enabled {
-            // Fill background first
-            painter.rect_filled(video_clip_rect, 4.0, video_color.linear_multiply(0.5));
+            // Fill background first; nakladki malowane z alfa wg krycia
+            let fill = if clip.track_index > 0 {
+                let [r, g, b, _] = video_color.to_array();
+                egui::Color32::from_rgba_unmultiplied(r, g, b, (clip.opacity.clamp(0.0, 1.0) * 200.0) as u8)
+            } else {
+                video_color.linear_multiply(0.5)
+            };
+            painter.rect_filled(video_clip_rect, 4.0, fill);
             painter.rect_stroke(video_clip_rect, 4.0, egui::Stroke::new(2.0, video_color));
         } else {
-            // Disabled track - dim overlay
+            // Disabled track - dim overlay + krzyzyk
             painter.rect_filled(video_clip_rect, 4.0, egui::Color32::from_rgba_unmultiplied(50, 50, 50, 150));
+            let cross = egui::Stroke::new(1.5, egui::Color32::from_gray(130));
+            painter.line_segment([video_clip_rect.left_top(), video_clip_rect.right_bottom()], cross);
+            painter.line_segment([video_clip_rect.left_bottom(), video_clip_rect.right_top()], cross);
         }
 
         if clip.audio_enabled {
@@ -1612,18 +3724,107 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
             painter.rect_filled(audio_clip_rect, 4.0, audio_bg.linear_multiply(0.5));
             painter.rect_stroke(audio_clip_rect, 4.0, egui::Stroke::new(2.0, audio_color));
         } else {
-            painter.rect_filled(audio_clip_rect, 4.0, egui::Color32::from_rgba_unmultiplied(50, 50, 50, 150));
+            // Wyciszone audio - czerwony odcien + znaczek "M"
+            painter.rect_filled(audio_clip_rect, 4.0, egui::Color32::from_rgba_unmultiplied(160, 40, 40, 120));
+            if audio_clip_rect.width() > 18.0 {
+                let badge = egui::Rect::from_min_size(audio_clip_rect.left_top() + egui::vec2(4.0, 4.0), egui::vec2(14.0, 14.0));
+                painter.rect_filled(badge, 2.0, egui::Color32::from_rgb(200, 50, 50));
+                painter.text(badge.center(), egui::Align2::CENTER_CENTER, "M", egui::FontId::proportional(10.0), egui::Color32::WHITE);
+            }
         }
 
-        // Link indicator (line connecting video and audio when linked)
-        if clip.linked && clip.video_enabled && clip.audio_enabled {
-            let link_x = start_x + 10.0;
-            painter.line_segment(
-                [egui::pos2(link_x, video_clip_rect.bottom()), egui::pos2(link_x, audio_clip_rect.top())],
-                egui::Stroke::new(2.0, egui::Color32::from_rgb(200, 200, 200)),
+        // Nazwa klipu + dlugosc w lewym gornym rogu (przycieta do szerokosci klipu)
+        let label_font = egui::TextStyle::Small.resolve(ui.style());
+        let label_max_w = video_clip_rect.width() - 8.0;
+        if label_max_w > 12.0 {
+            let name = match clip.asset_id.and_then(|id| app.media_library.get(id)) {
+                Some(asset) => asset.name.clone(),
+                None => app.input_path.clone(),
+            };
+            let stem = Path::new(&name).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or(name);
+            let label = truncate_to_width(ui, &format!("{stem} ({:.1}s)", clip.output_duration()), &label_font, label_max_w);
+            let text_color = match clip.color_label.color32() {
+                Some(bg) if clip.video_enabled && !video_selected => contrast_text_color(bg),
+                _ => egui::Color32::WHITE,
+            };
+            painter.with_clip_rect(video_clip_rect.intersect(video_rect)).text(
+                video_clip_rect.left_top() + egui::vec2(4.0, 3.0),
+                egui::Align2::LEFT_TOP,
+                label,
+                label_font,
+                text_color,
+            );
+        }
+
+        // Panorama: znacznik L/R przy skrajnym ustawieniu (wartosc w tooltipie klipu)
+        if clip.audio_enabled && clip.audio_pan.abs() >= 0.99 && audio_clip_rect.width() > 18.0 {
+            let badge = egui::Rect::from_min_size(audio_clip_rect.right_top() + egui::vec2(-18.0, 4.0), egui::vec2(14.0, 14.0));
+            painter.rect_filled(badge, 2.0, egui::Color32::from_rgb(60, 110, 200));
+            let side = if clip.audio_pan < 0.0 { "L" } else { "R" };
+            painter.text(badge.center(), egui::Align2::CENTER_CENTER, side, egui::FontId::proportional(10.0), egui::Color32::WHITE);
+        }
+
+        // Krzywa rampy predkosci na dole klipu wideo
+        if let Some((s0, s1)) = clip.speed_ramp
+            && video_clip_rect.width() > 8.0
+        {
+            let curve_rect = egui::Rect::from_min_max(
+                egui::pos2(video_clip_rect.left(), video_clip_rect.bottom() - 14.0),
+                video_clip_rect.right_bottom(),
+            );
+            painter.rect_filled(curve_rect, 0.0, egui::Color32::from_black_alpha(90));
+            // Skala 0.25x - 4x jak w polu Speed
+            let to_y = |v: f32| curve_rect.bottom() - 1.0 - ((v - 0.25) / 3.75).clamp(0.0, 1.0) * (curve_rect.height() - 2.0);
+            painter.with_clip_rect(curve_rect.intersect(video_rect)).line_segment(
+                [egui::pos2(curve_rect.left(), to_y(s0)), egui::pos2(curve_rect.right(), to_y(s1))],
+                egui::Stroke::new(1.5, egui::Color32::from_rgb(120, 220, 255)),
+            );
+        }
+
+        // Obwiednia glosnosci (polyline na sciezce audio)
+        if clip.audio_enabled && (!clip.volume_keyframes.is_empty() || app.tool == Tool::Envelope) {
+            let env_color = egui::Color32::from_rgb(255, 200, 60);
+            let mut points: Vec<egui::Pos2> = clip
+                .volume_keyframes
+                .iter()
+                .map(|&(kt, kg)| egui::pos2(start_x + kt * app.timeline_zoom, envelope_y(audio_clip_rect, kg)))
+                .collect();
+            let first_y = points.first().map_or(envelope_y(audio_clip_rect, 1.0), |p| p.y);
+            let last_y = points.last().map_or(envelope_y(audio_clip_rect, 1.0), |p| p.y);
+            points.insert(0, egui::pos2(start_x, first_y));
+            points.push(egui::pos2(end_x, last_y));
+            let clipped = painter.with_clip_rect(audio_clip_rect.intersect(audio_rect));
+            clipped.add(egui::Shape::line(points, egui::Stroke::new(1.5, env_color)));
+            for &(kt, kg) in &clip.volume_keyframes {
+                clipped.circle_filled(egui::pos2(start_x + kt * app.timeline_zoom, envelope_y(audio_clip_rect, kg)), 3.5, env_color);
+            }
+        }
+
+        // Link indicator: ikona lancucha miedzy obrazem i dzwiekiem, klik przelacza linked
+        if clip.video_enabled && clip.audio_enabled {
+            let link_x = video_clip_rect.left().max(audio_clip_rect.left()) + 10.0;
+            let link_color = if clip.linked { egui::Color32::from_rgb(200, 200, 200) } else { egui::Color32::from_gray(110) };
+            if clip.linked {
+                painter.line_segment(
+                    [egui::pos2(link_x, video_clip_rect.bottom()), egui::pos2(link_x, audio_clip_rect.top())],
+                    egui::Stroke::new(2.0, link_color),
+                );
+            }
+            let icon_rect = egui::Rect::from_center_size(
+                egui::pos2(link_x, (video_clip_rect.bottom() + audio_clip_rect.top()) / 2.0),
+                egui::vec2(14.0, 14.0),
             );
-            // Small chain icon
-            painter.circle_filled(egui::pos2(link_x, (video_clip_rect.bottom() + audio_clip_rect.top()) / 2.0), 4.0, egui::Color32::from_rgb(200, 200, 200));
+            painter.rect_filled(icon_rect, 3.0, egui::Color32::from_gray(35));
+            painter.text(icon_rect.center(), egui::Align2::CENTER_CENTER, "🔗", egui::FontId::proportional(10.0), link_color);
+            if !clip.linked {
+                painter.line_segment([icon_rect.left_bottom(), icon_rect.right_top()], egui::Stroke::new(1.5, egui::Color32::from_rgb(220, 90, 90)));
+            }
+            let link_resp = ui
+                .interact(icon_rect, ui.id().with("clip_link").with(idx), egui::Sense::click())
+                .on_hover_text(if clip.linked { &app.text.unlink_clip } else { &app.text.link_clip });
+            if link_resp.clicked() {
+                toggle_link_idx = Some(idx);
+            }
         }
 
         let fade_in_w = (clip.fade_in * app.timeline_zoom).max(0.0);
@@ -1696,14 +3897,15 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         let handle_hit_a_in = egui::Rect::from_center_size(handle_in_a, egui::vec2(handle_size, handle_size));
         let handle_hit_a_out = egui::Rect::from_center_size(handle_out_a, egui::vec2(handle_size, handle_size));
 
+        let (video_open, audio_open) = (!app.track_video_locked, !app.track_audio_locked);
         if let Some(pos) = hover_pos {
-            if handle_hit_v_in.contains(pos) {
+            if video_open && handle_hit_v_in.contains(pos) {
                 hover_fade = Some(FadeDrag { clip_idx: idx, kind: FadeKind::In });
-            } else if handle_hit_v_out.contains(pos) {
+            } else if video_open && handle_hit_v_out.contains(pos) {
                 hover_fade = Some(FadeDrag { clip_idx: idx, kind: FadeKind::Out });
-            } else if handle_hit_a_in.contains(pos) {
+            } else if audio_open && handle_hit_a_in.contains(pos) {
                 hover_fade = Some(FadeDrag { clip_idx: idx, kind: FadeKind::In });
-            } else if handle_hit_a_out.contains(pos) {
+            } else if audio_open && handle_hit_a_out.contains(pos) {
                 hover_fade = Some(FadeDrag { clip_idx: idx, kind: FadeKind::Out });
             }
         }
@@ -1713,33 +3915,127 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         painter.circle_filled(handle_out_v, handle_size * 0.25, dot);
         painter.circle_filled(handle_in_a, handle_size * 0.25, dot);
         painter.circle_filled(handle_out_a, handle_size * 0.25, dot);
+
+        // Render zakresu: czesci klipu poza [mark in, mark out] przyciemnione (40% krycia)
+        if app.render_settings.render_range_only
+            && let (Some(mark_in), Some(mark_out)) = (app.mark_in, app.mark_out)
+            && mark_out > mark_in
+        {
+            let range_x0 = left + (mark_in - app.timeline_offset) * app.timeline_zoom;
+            let range_x1 = left + (mark_out - app.timeline_offset) * app.timeline_zoom;
+            let dim = egui::Color32::from_black_alpha(153);
+            for clip_rect in [video_clip_rect, audio_clip_rect] {
+                for (x0, x1) in [(clip_rect.left(), clip_rect.right().min(range_x0)), (clip_rect.left().max(range_x1), clip_rect.right())] {
+                    if x1 > x0 {
+                        let outside = egui::Rect::from_x_y_ranges(x0..=x1, clip_rect.y_range());
+                        painter.rect_filled(outside, 0.0, dim);
+                    }
+                }
+            }
+        }
+
+        // Timecode'y start/end pod klipem (osobny pasek, wiec nie nachodza na fade'y)
+        let visible_left = audio_clip_rect.left().max(left);
+        let visible_right = audio_clip_rect.right().min(right);
+        if visible_right - visible_left >= 60.0 {
+            let color = if is_selected { app.settings.accent_color } else { egui::Color32::from_gray(150) };
+            let font = egui::TextStyle::Small.resolve(ui.style());
+            let label_y = audio_rect.bottom() + 1.0;
+            let start = painter.layout_no_wrap(secs_to_timecode(clip.start, app.video_fps, app.drop_frame), font.clone(), color);
+            let end = painter.layout_no_wrap(secs_to_timecode(clip.timeline_end(), app.video_fps, app.drop_frame), font, color);
+            let start_right = visible_left + start.size().x;
+            let end_left = visible_right - end.size().x;
+            if audio_clip_rect.left() >= left && start_right <= visible_right {
+                painter.galley(egui::pos2(visible_left, label_y), start, color);
+            }
+            if audio_clip_rect.right() <= right && end_left >= start_right + 4.0 {
+                painter.galley(egui::pos2(end_left, label_y), end, color);
+            }
+        }
     }
 
-    // Toggle Link/Unlink
-    if let Some(idx) = toggle_link_idx {
-        if let Some(clip) = app.clips.get_mut(idx) {
-            clip.linked = !clip.linked;
+    // Przyciemnienie zablokowanych sciezek - po klipach, zeby ich nie zaslanialy
+    for (track_rect, locked) in [(video_rect, app.track_video_locked), (audio_rect, app.track_audio_locked)] {
+        if locked {
+            painter.rect_filled(track_rect, 4.0, egui::Color32::from_black_alpha(60));
         }
     }
 
-    // Handle clip removal
+    // Wymiar zaznaczonego klipu jak na rysunku technicznym: |<- 2.50 s ->|
+    if let Some(clip) = app.selected_clip.and_then(|idx| app.clips.get(idx)) {
+        let start_x = left + (clip.start - app.timeline_offset) * app.timeline_zoom;
+        let end_x = left + (clip.timeline_end() - app.timeline_offset) * app.timeline_zoom;
+        if end_x - start_x >= 50.0 && end_x > left && start_x < right {
+            let track_rect = video_track_rect(clip.track_index);
+            let y = track_rect.top() + 14.0;
+            let shadow = egui::Color32::from_black_alpha(200);
+            let offset = egui::vec2(1.0, 1.0);
+            let arrow = 5.0;
+            let segments = [
+                [egui::pos2(start_x, y - 5.0), egui::pos2(start_x, y + 5.0)],
+                [egui::pos2(end_x, y - 5.0), egui::pos2(end_x, y + 5.0)],
+                [egui::pos2(start_x, y), egui::pos2(end_x, y)],
+                [egui::pos2(start_x, y), egui::pos2(start_x + arrow, y - arrow * 0.6)],
+                [egui::pos2(start_x, y), egui::pos2(start_x + arrow, y + arrow * 0.6)],
+                [egui::pos2(end_x, y), egui::pos2(end_x - arrow, y - arrow * 0.6)],
+                [egui::pos2(end_x, y), egui::pos2(end_x - arrow, y + arrow * 0.6)],
+            ];
+            for (color, shift) in [(shadow, offset), (egui::Color32::WHITE, egui::Vec2::ZERO)] {
+                for [a, b] in segments {
+                    painter.line_segment([a + shift, b + shift], egui::Stroke::new(1.0, color));
+                }
+            }
+            let label = format!("{:.2} s", clip.output_duration());
+            let font = egui::TextStyle::Small.resolve(ui.style());
+            let center = egui::pos2((start_x.max(left) + end_x.min(right)) * 0.5, y - 2.0);
+            painter.text(center + offset, egui::Align2::CENTER_BOTTOM, &label, font.clone(), shadow);
+            painter.text(center, egui::Align2::CENTER_BOTTOM, &label, font, egui::Color32::WHITE);
+        }
+    }
+
+    // Toggle Link/Unlink - ponowne polaczenie przywraca wspolne krawedzie obrazu i dzwieku
+    if let Some(idx) = toggle_link_idx
+        && idx < app.clips.len()
+    {
+        app.push_undo();
+        let clip = &mut app.clips[idx];
+        clip.linked = !clip.linked;
+        if clip.linked {
+            clip.clear_track_trims();
+        }
+    }
+
+    // Niezalezne przyciecie obrazu/dzwieku rozlaczonego klipu (min. 0.05 s)
+    if track_trim_started {
+        app.push_undo();
+    }
+    if let Some((idx, track, is_start, t)) = track_trim_request
+        && let t = app.snap(t)
+        && let Some(clip) = app.clips.get_mut(idx)
+    {
+        let clip_start = clip.start;
+        let offset = (t - clip_start).clamp(0.0, clip.output_duration());
+        let (from, to) = if track == TrackType::Audio { clip.audio_range() } else { clip.video_range() };
+        let (start, end) = if track == TrackType::Audio {
+            (&mut clip.audio_start, &mut clip.audio_end)
+        } else {
+            (&mut clip.video_start, &mut clip.video_end)
+        };
+        if is_start {
+            *start = Some(offset.min(to - clip_start - 0.05).max(0.0));
+        } else {
+            *end = Some(offset.max(from - clip_start + 0.05));
+        }
+    }
+
+    // Handle clip removal
     if let Some((idx, track_type)) = remove_clip_idx {
         match track_type {
             TrackType::Both => {
-                // Remove entire clip (Ripple Delete if enabled)
-                if app.ripple_delete {
-                    if let Some(clip) = app.clips.get(idx) {
-                        let duration = clip.end - clip.start;
-                        app.clips.remove(idx);
-                        for other in app.clips.iter_mut().skip(idx) {
-                            other.start -= duration;
-                            other.end -= duration;
-                        }
-                    }
-                } else {
-                    app.clips.remove(idx);
+                // Remove entire clip with its group (Ripple Delete if enabled)
+                if idx < app.clips.len() {
+                    app.remove_clip_with_group(idx);
                 }
-                app.selected_clip = None;
             }
             TrackType::Video => {
                 // Disable video track only
@@ -1766,38 +4062,137 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         }
     }
 
-    // Handle clip MOVE (live dragging)
-    if app.status.starts_with("MOVE:") {
-        let parts: Vec<&str> = app.status.split(':').collect();
-        if parts.len() == 3 {
-            if let (Ok(idx), Ok(new_start)) = (parts[1].parse::<usize>(), parts[2].parse::<f32>()) {
-                if let Some(clip) = app.clips.get_mut(idx) {
-                    let clip_duration = clip.end - clip.start;
-                    clip.start = new_start;
-                    clip.end = new_start + clip_duration;
-                }
+    // Handle clip MOVE (live dragging) - klipy z grupy przesuwaja sie o to samo;
+    // w trybie ripple kolejne klipy (wieksze indeksy) jada razem z przeciaganym
+    if let Some((idx, new_start)) = move_request
+        && let new_start = app.snap(new_start)
+        && idx < app.clips.len()
+    {
+        if app.settings.ripple_delete && app.clips[idx].group_id.is_none() {
+            let min_start = app.clips[idx..].iter().map(|c| c.start).fold(f32::MAX, f32::min);
+            let delta = (new_start - app.clips[idx].start).max(-min_start);
+            ripple_move(&mut app.clips, idx, delta);
+        } else {
+            let members = group_members(&app.clips, idx);
+            let min_start = members.iter().map(|&i| app.clips[i].start).fold(f32::MAX, f32::min);
+            let delta = (new_start - app.clips[idx].start).max(-min_start);
+            for i in members {
+                app.clips[i].start += delta;
+                app.clips[i].end += delta;
             }
         }
-        app.status.clear();
     }
 
-    // Handle Blade Tool cuts (deferred from inside the loop)
-    if app.status.starts_with("CUT:") {
-        let parts: Vec<&str> = app.status.split(':').collect();
-        if parts.len() == 3 {
-            if let (Ok(idx), Ok(t)) = (parts[1].parse::<usize>(), parts[2].parse::<f32>()) {
-                if let Some(split_idx) = split_clip_at(&mut app.clips, idx, t) {
-                    app.selected_clip = Some(split_idx);
-                    app.playhead = t;
-                    app.status.clear();
-                } else {
-                    app.status = "Nie mozna uciac na granicy klipu.".to_string();
-                }
-            } else {
-                app.status.clear();
+    // Ghost klipu przeciaganego z biblioteki: przerywana ramka w miejscu upuszczenia
+    if let Some(asset) = app.dragging_library_asset.and_then(|i| app.media_library.get(i))
+        && let Some(pos) = ui.input(|i| i.pointer.latest_pos())
+        && rect.contains(pos)
+        && ui.input(|i| i.pointer.any_down())
+    {
+        let drop_time = app.timeline_offset + ((pos.x - left) / app.timeline_zoom).clamp(0.0, window);
+        let x0 = left + (drop_time - app.timeline_offset) * app.timeline_zoom;
+        let x1 = x0 + library_drop_length(asset) * app.timeline_zoom;
+        let ghost_painter = painter.with_clip_rect(egui::Rect::from_x_y_ranges(left..=right, rect.y_range()));
+        let stroke = egui::Stroke::new(1.5, app.settings.accent_color);
+        let ghost_tracks = [
+            (asset.kind != MediaType::Audio, video_track_rect(0)),
+            (asset.kind != MediaType::Image, audio_rect),
+        ];
+        for (_, track) in ghost_tracks.into_iter().filter(|(shown, _)| *shown) {
+            let ghost = egui::Rect::from_x_y_ranges(x0..=x1, track.y_range());
+            ghost_painter.rect_filled(ghost, 4.0, egui::Color32::from_white_alpha(25));
+            let outline = [ghost.left_top(), ghost.right_top(), ghost.right_bottom(), ghost.left_bottom(), ghost.left_top()];
+            ghost_painter.extend(egui::Shape::dashed_line(&outline, stroke, 6.0, 4.0));
+        }
+        ghost_painter.text(
+            egui::pos2(x0 + 4.0, video_track_rect(0).top() + 3.0),
+            egui::Align2::LEFT_TOP,
+            &asset.name,
+            egui::FontId::proportional(11.0),
+            egui::Color32::from_white_alpha(200),
+        );
+    }
+
+    // Obrys grup klipow
+    for (id, (video_union, audio_union)) in &group_rects {
+        let stroke = egui::Stroke::new(1.5, group_color(*id));
+        painter.rect_stroke(video_union.expand(1.5), 3.0, stroke);
+        painter.rect_stroke(audio_union.expand(1.5), 3.0, stroke);
+        if let Some(name) = app.groups.get(id) {
+            painter.text(
+                video_union.left_top() + egui::vec2(4.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                name,
+                egui::FontId::proportional(10.0),
+                group_color(*id),
+            );
+        }
+    }
+
+    if let Some(action) = clip_menu_request {
+        app.apply_clip_menu_action(action);
+    }
+
+    match group_request {
+        Some(GroupMenuAction::Group) => app.group_selected(),
+        Some(GroupMenuAction::Select(idx)) => app.select_group(idx),
+        Some(GroupMenuAction::Rename(id)) => {
+            let name = app.groups.get(&id).cloned().unwrap_or_default();
+            app.renaming_group = Some((id, name));
+        }
+        Some(GroupMenuAction::Ungroup(id)) => app.ungroup(id),
+        None => {}
+    }
+
+    // Tooltip ze szczegolami klipu po 500 ms bez ruchu z klipu
+    let busy = app.dragging_clip.is_some() || app.dragging_fade.is_some() || app.dragging_playhead;
+    app.hover_start = match (hovered_clip, app.hover_start) {
+        (Some(idx), Some((prev, since))) if prev == idx => Some((idx, since)),
+        (Some(idx), _) => Some((idx, Instant::now())),
+        (None, _) => None,
+    };
+    if let Some((idx, since)) = app.hover_start
+        && !busy
+        && let Some(clip) = app.clips.get(idx)
+    {
+        let delay = std::time::Duration::from_millis(500);
+        if since.elapsed() >= delay {
+            clip_tooltip(ui.ctx(), app, clip, idx);
+        } else {
+            ui.ctx().request_repaint_after(delay - since.elapsed());
+        }
+    }
+
+    // Kolor dla wszystkich zaznaczonych (z menu kontekstowego)
+    if let Some(color) = batch_color_request {
+        app.apply_color_to_selected(color);
+    }
+
+    // Handle Envelope Tool clicks (deferred from inside the loop)
+    if let Some((idx, hit, clip_t, gain)) = envelope_request
+        && idx < app.clips.len()
+    {
+        app.push_undo();
+        let keys = &mut app.clips[idx].volume_keyframes;
+        match hit {
+            Some(point) => {
+                keys.remove(point);
             }
+            None => {
+                keys.push((clip_t, gain));
+                keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+            }
+        }
+    }
+
+    // Handle Blade Tool cuts (deferred from inside the loop)
+    if let Some((idx, t)) = cut_request {
+        let t = app.snap(t);
+        if let Some(split_idx) = split_clip_at(&mut app.clips, idx, t) {
+            app.selected_clip = Some(split_idx);
+            app.playhead = t;
         } else {
-            app.status.clear();
+            app.log.push(LogEntry::warning("Nie mozna uciac na granicy klipu."));
         }
     }
 
@@ -1805,7 +4200,9 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
     let hover_hit = hover_pos
         .map(|pos| rect.contains(pos) && (pos.x - play_x).abs() <= 10.0)
         .unwrap_or(false);
-    if let Some(fade) = hover_fade.or(app.dragging_fade) {
+    if split_active {
+        ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::ResizeVertical);
+    } else if let Some(fade) = hover_fade.or(app.dragging_fade) {
         ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
         if let Some(pos) = ui.ctx().pointer_latest_pos() {
             let size = 12.0;
@@ -1856,6 +4253,93 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
         }
     }
 
+    // Przenikania audio: pomaranczowy pas na sciezce audio miedzy kolejnymi klipami V1
+    let audio_painter = painter.with_clip_rect(audio_rect);
+    let base: Vec<&Clip> = app.clips.iter().filter(|c| c.track_index == 0).collect();
+    for pair in base.windows(2) {
+        let (clip, next) = (pair[0], pair[1]);
+        if clip.audio_crossfade_out <= 0.0 {
+            continue;
+        }
+        let x0 = left + (next.start - app.timeline_offset) * app.timeline_zoom;
+        let x1 = x0 + clip.audio_crossfade_out.min(next.end - next.start) * app.timeline_zoom;
+        let band = egui::Rect::from_min_max(egui::pos2(x0, audio_rect.top() + 2.0), egui::pos2(x1, audio_rect.bottom() - 2.0));
+        audio_painter.rect_filled(band, 2.0, egui::Color32::from_rgba_unmultiplied(255, 140, 0, 90));
+        audio_painter.line_segment([band.left_top(), band.right_bottom()], egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 140, 0)));
+    }
+
+    // Przejscia obrazu: strefa na koncu klipu V1 (kreskowanie = przenikanie, strzalka = wipe)
+    let v1_rect = video_track_rect(0);
+    let transition_painter = painter.with_clip_rect(v1_rect);
+    for pair in base.windows(2) {
+        let Some(transition) = transition_between(pair[0], pair[1]) else { continue };
+        let x1 = left + (pair[0].timeline_end() - app.timeline_offset) * app.timeline_zoom;
+        let x0 = x1 - transition.duration * app.timeline_zoom;
+        let zone = egui::Rect::from_min_max(egui::pos2(x0, v1_rect.top() + 2.0), egui::pos2(x1, v1_rect.bottom() - 2.0));
+        let color = egui::Color32::from_rgb(120, 220, 160);
+        transition_painter.rect_filled(zone, 2.0, egui::Color32::from_rgba_unmultiplied(120, 220, 160, 60));
+        transition_painter.rect_stroke(zone, 2.0, egui::Stroke::new(1.0, color));
+        let arrow = match transition.kind {
+            TransitionKind::WipeLeft => Some("⬅"),
+            TransitionKind::WipeRight => Some("➡"),
+            TransitionKind::WipeUp => Some("⬆"),
+            TransitionKind::WipeDown => Some("⬇"),
+            _ => None,
+        };
+        match (transition.kind, arrow) {
+            (_, Some(arrow)) => {
+                transition_painter.text(zone.center(), egui::Align2::CENTER_CENTER, arrow, egui::FontId::proportional(14.0), color);
+            }
+            (TransitionKind::FadeToBlack, _) => {
+                transition_painter.rect_filled(zone.shrink(3.0), 2.0, egui::Color32::from_black_alpha(180));
+            }
+            _ => {
+                // Ukosne kreskowanie co 6 px
+                let hatch = zone.intersect(v1_rect);
+                let mut x = hatch.left() - hatch.height();
+                while x < hatch.right() {
+                    transition_painter.with_clip_rect(hatch).line_segment(
+                        [egui::pos2(x, hatch.bottom()), egui::pos2(x + hatch.height(), hatch.top())],
+                        egui::Stroke::new(1.0, color),
+                    );
+                    x += 6.0;
+                }
+            }
+        }
+    }
+
+    // Kursor podgladu czasu: przerywana linia pod myszka (ukryta podczas przeciagania)
+    let any_drag = app.dragging_playhead
+        || app.dragging_timeline
+        || app.dragging_clip.is_some()
+        || app.dragging_fade.is_some()
+        || app.dragging_library_asset.is_some()
+        || ui.input(|i| i.pointer.any_down());
+    if response.hovered()
+        && !any_drag
+        && let Some(pos) = hover_pos
+        && pos.x >= left
+    {
+        let stroke = egui::Stroke::new(1.0, egui::Color32::GRAY);
+        let mut y = ruler_rect.bottom();
+        while y < rect.bottom() {
+            let y_end = (y + 4.0).min(rect.bottom());
+            painter.line_segment([egui::pos2(pos.x, y), egui::pos2(pos.x, y_end)], stroke);
+            y += 7.0;
+        }
+        let hover_time = app.timeline_offset + (pos.x - left) / app.timeline_zoom;
+        let galley = painter.layout_no_wrap(
+            secs_to_timecode(hover_time, app.video_fps, app.drop_frame),
+            egui::FontId::monospace(10.0),
+            egui::Color32::WHITE,
+        );
+        let label_size = galley.size() + egui::vec2(8.0, 4.0);
+        let label_x = (pos.x - label_size.x * 0.5).clamp(rect.left(), rect.right() - label_size.x);
+        let label_rect = egui::Rect::from_min_size(egui::pos2(label_x, rect.top() + 1.0), label_size);
+        painter.rect_filled(label_rect, 3.0, egui::Color32::from_black_alpha(180));
+        painter.galley(label_rect.min + egui::vec2(4.0, 2.0), galley, egui::Color32::WHITE);
+    }
+
     // Playhead Drawing
     painter.line_segment(
         [
@@ -1912,8 +4396,11 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
             if !in_ruler {
                 for (idx, clip) in app.clips.iter().enumerate() {
                     let start_x = left + (clip.start - app.timeline_offset) * app.timeline_zoom;
-                    let end_x = left + (clip.end - app.timeline_offset) * app.timeline_zoom;
-                    if pos.x >= start_x && pos.x <= end_x {
+                    let end_x = left + (clip.timeline_end() - app.timeline_offset) * app.timeline_zoom;
+                    // Wiersz sciezki klipu (V1, V2...) albo wspolny wiersz audio
+                    let in_row = video_track_rect(clip.track_index).y_range().contains(pos.y)
+                        || (clip.audio_enabled && audio_rect.y_range().contains(pos.y));
+                    if in_row && pos.x >= start_x && pos.x <= end_x {
                         selected = Some(idx);
                         break;
                     }
@@ -1941,33 +4428,33 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
                 }
             } else if let Some(drag_idx) = app.dragging_clip {
                 // Clip dragging - move the clip in time
+                let new_start = app.snap((t - app.drag_clip_offset).max(0.0));
                 if let Some(clip) = app.clips.get_mut(drag_idx) {
                     let clip_duration = clip.end - clip.start;
-                    let new_start = (t - app.drag_clip_offset).max(0.0);
                     clip.start = new_start;
                     clip.end = new_start + clip_duration;
                     changed = true;
                 }
             } else if app.dragging_playhead || (in_ruler && (response.clicked() || response.dragged())) {
                 // Scrubbing via Ruler or Playhead Drag
-                app.playhead = snap_time(t, app.timeline_zoom);
+                app.playhead = app.snap(t);
                 app.dragging_playhead = true;
                 changed = true;
             } else if response.clicked() {
                 if app.tool == Tool::Scissors {
                      // Cut logic
-                     let by_time = app.clips.iter().position(|clip| t > clip.start && t < clip.end);
+                     let t = app.snap(t);
+                     let by_time = app.clips.iter().position(|clip| t > clip.start && t < clip.timeline_end());
                      if let Some(idx) = selected.or(by_time) {
                          if let Some(split) = split_clip_at(&mut app.clips, idx, t) {
                              app.selected_clip = Some(split);
                              app.playhead = t;
-                             app.status.clear();
                              changed = true;
                          } else {
-                             app.status = "Nie mozna uciac na granicy klipu.".to_string();
+                             app.log.push(LogEntry::warning("Nie mozna uciac na granicy klipu."));
                          }
                      } else {
-                         app.status = "Brak klipu pod kursorem.".to_string();
+                         app.log.push(LogEntry::warning("Brak klipu pod kursorem."));
                      }
                 } else {
                     // Regular Selection
@@ -1995,344 +4482,3879 @@ fn draw_timeline(ui: &mut egui::Ui, app: &mut VideoEditorApp) -> bool {
     }
 
 
-    changed
+    changed || marker_seek
+}
+
+/// Pokretlo panoramy: przeciaganie w poziomie zmienia wartosc, dwuklik = srodek
+fn pan_knob(ui: &mut egui::Ui, pan: &mut f32) -> egui::Response {
+    let (rect, mut response) = ui.allocate_exact_size(egui::vec2(28.0, 28.0), egui::Sense::click_and_drag());
+    if response.dragged() {
+        *pan = (*pan + response.drag_delta().x * 0.01).clamp(-1.0, 1.0);
+        response.mark_changed();
+    }
+    if response.double_clicked() {
+        *pan = 0.0;
+        response.mark_changed();
+    }
+    let painter = ui.painter();
+    let radius = rect.width() * 0.5 - 2.0;
+    let visuals = ui.style().interact(&response);
+    painter.circle(rect.center(), radius, visuals.bg_fill, visuals.fg_stroke);
+    // -135 st. (lewo) .. +135 st. (prawo), 0 = gora
+    let angle = (*pan * 135.0f32).to_radians();
+    let tip = rect.center() + egui::vec2(angle.sin(), -angle.cos()) * (radius - 2.0);
+    painter.line_segment([rect.center(), tip], egui::Stroke::new(2.0, visuals.fg_stroke.color));
+    response.on_hover_text(format!("Pan: {:+.2}", pan))
+}
+
+/// Graficzny korektor 5-pasmowy: przeciaganie uchwytow w pionie zmienia wzmocnienie,
+/// dwuklik zeruje pasmo. Same zera czyszcza liste (brak filtra w renderze).
+fn eq_graph_ui(ui: &mut egui::Ui, bands: &mut Vec<EqBand>) -> egui::Response {
+    let (rect, mut response) = ui.allocate_exact_size(egui::vec2(200.0, 60.0), egui::Sense::hover());
+    let (min_hz, max_hz) = (20.0f32, 20_000.0f32);
+    let x_of = |hz: f32| rect.left() + rect.width() * (hz / min_hz).log10() / (max_hz / min_hz).log10();
+    let y_of = |db: f32| rect.center().y - db / EqBand::MAX_GAIN_DB * (rect.height() * 0.5 - 4.0);
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 3.0, egui::Color32::from_gray(25));
+    painter.hline(rect.x_range(), rect.center().y, egui::Stroke::new(1.0, egui::Color32::from_gray(60)));
+
+    let mut edited: Vec<EqBand> = if bands.is_empty() {
+        EqBand::DEFAULT_FREQUENCIES.iter().map(|&f| EqBand::flat(f)).collect()
+    } else {
+        bands.clone()
+    };
+    let mut changed = false;
+    for (i, band) in edited.iter_mut().enumerate() {
+        let handle = egui::pos2(x_of(band.frequency_hz).clamp(rect.left() + 5.0, rect.right() - 5.0), y_of(band.gain_db));
+        let hit = egui::Rect::from_center_size(egui::pos2(handle.x, rect.center().y), egui::vec2(14.0, rect.height()));
+        let handle_resp = ui.interact(hit, ui.id().with(("eq_band", i)), egui::Sense::click_and_drag());
+        if handle_resp.dragged() {
+            let db_per_px = EqBand::MAX_GAIN_DB / (rect.height() * 0.5 - 4.0);
+            band.gain_db = (band.gain_db - handle_resp.drag_delta().y * db_per_px).clamp(-EqBand::MAX_GAIN_DB, EqBand::MAX_GAIN_DB);
+            changed = true;
+        }
+        if handle_resp.double_clicked() {
+            band.gain_db = 0.0;
+            changed = true;
+        }
+        let active = handle_resp.hovered() || handle_resp.dragged();
+        let color = if active { egui::Color32::WHITE } else { egui::Color32::from_rgb(120, 200, 255) };
+        painter.circle_filled(egui::pos2(handle.x, y_of(band.gain_db)), if active { 5.0 } else { 4.0 }, color);
+        if active {
+            let hz = if band.frequency_hz >= 1000.0 { format!("{:.0} kHz", band.frequency_hz / 1000.0) } else { format!("{:.0} Hz", band.frequency_hz) };
+            handle_resp.on_hover_text(format!("{hz}: {:+.1} dB", band.gain_db));
+        }
+    }
+
+    // Krzywa: suma przyblizonych odpowiedzi pasm w skali logarytmicznej
+    let points: Vec<egui::Pos2> = (0..=100)
+        .map(|step| {
+            let hz = min_hz * (max_hz / min_hz).powf(step as f32 / 100.0);
+            let db: f32 = edited.iter().map(|b| b.response_db(hz)).sum();
+            egui::pos2(x_of(hz), y_of(db.clamp(-EqBand::MAX_GAIN_DB, EqBand::MAX_GAIN_DB)))
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, egui::Color32::from_rgb(120, 200, 255))));
+
+    if changed {
+        *bands = if edited.iter().all(|b| b.gain_db.abs() < 0.05) { Vec::new() } else { edited };
+        response.mark_changed();
+    }
+    response
+}
+
+/// Liczba klatek renderu do paska postepu: dlugosc V1 po zawezeniu do zakresu renderu
+fn render_total_frames(clips: &[Clip], settings: &RenderSettings, fps: f32) -> u64 {
+    let mut clips = clips.to_vec();
+    if let Some((start, end)) = settings.active_range() {
+        restrict_to_range(&mut clips, start, end);
+    }
+    let total_duration: f32 = clips
+        .iter()
+        .filter(|c| c.track_index == 0 && (c.video_enabled || c.audio_enabled))
+        .map(|c| c.output_duration())
+        .sum();
+    (total_duration * fps.max(1.0)).round() as u64
+}
+
+/// Watek kolejki: bierze kolejne oczekujace pozycje, wczytuje projekt z dysku i renderuje
+/// (postep biezacej pozycji w `progress`), az kolejka nie ma pozycji Pending
+fn run_render_queue(queue: &Mutex<RenderQueue>, progress: &Arc<Mutex<RenderProgress>>) {
+    loop {
+        let job = {
+            let Ok(mut queue) = queue.lock() else { return };
+            let Some(entry) = queue.iter_mut().find(|e| e.status == QueueStatus::Pending) else { return };
+            entry.status = QueueStatus::Running;
+            (entry.project_path.clone(), entry.output_path.clone(), entry.settings.clone())
+        };
+        let (project_path, output_path, settings) = job;
+        let started = Instant::now();
+        let result = load_project_file(&project_path).and_then(|data| {
+            if let Ok(mut p) = progress.lock() {
+                *p = RenderProgress {
+                    total_frames: render_total_frames(&data.clips, &settings, data.video_fps),
+                    ..RenderProgress::default()
+                };
+            }
+            let output = output_path.to_string_lossy();
+            render_deliverables(&data.input_path, &output, &data.clips, &data.media_library, &settings, &data.subtitles, Some(progress))?;
+            if settings.write_webvtt_chapters {
+                write_chapters_for_output(&data.markers, &data.clips, &output)?;
+            }
+            Ok(())
+        });
+        let status = match result {
+            Ok(()) => QueueStatus::Done(started.elapsed()),
+            Err(err) => QueueStatus::Failed(format!("{err:#}")),
+        };
+        if let Ok(mut queue) = queue.lock()
+            && let Some(entry) = queue.iter_mut().find(|e| e.status == QueueStatus::Running)
+        {
+            entry.status = status;
+        }
+    }
+}
+
+/// Edytor skrotow: "Record shortcut" przechwytuje nastepny klawisz (Esc anuluje)
+fn keybindings_ui(
+    ui: &mut egui::Ui,
+    keybindings: &mut KeyBindings,
+    recording_shortcut: &mut Option<&'static str>,
+    text: &TextResources,
+) {
+    if let Some(action) = *recording_shortcut {
+        let captured = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+        if let Some((key, modifiers)) = captured {
+            if key != egui::Key::Escape
+                && let Some((_, combo)) = keybindings.entries_mut().into_iter().find(|(name, _)| *name == action)
+            {
+                *combo = KeyCombo::new(key, modifiers);
+            }
+            *recording_shortcut = None;
+        }
+    }
+    egui::Grid::new("settings_keybindings").num_columns(2).striped(true).show(ui, |ui| {
+        for (name, combo) in keybindings.entries_mut() {
+            ui.label(name);
+            let recording = *recording_shortcut == Some(name);
+            let label = if recording { text.press_key.clone() } else { combo.to_string() };
+            if ui.selectable_label(recording, label).on_hover_text(&text.record_shortcut).clicked() {
+                *recording_shortcut = if recording { None } else { Some(name) };
+            }
+            ui.end_row();
+        }
+    });
+    if ui.button(&text.reset_shortcuts).clicked() {
+        *keybindings = KeyBindings::default();
+        *recording_shortcut = None;
+    }
+}
+
+/// Formularz wlasciwosci klipu (okno Clip Properties); zwraca true gdy kliknieto "Test filter"
+fn clip_properties_ui(ui: &mut egui::Ui, clip: &mut Clip, snap_fps: Option<f32>) -> bool {
+    let snap = |t: f32| snap_fps.map_or(t, |fps| snap_to_frame(t, fps));
+    let mut test_filter = false;
+    egui::Grid::new("clip_props_grid")
+        .num_columns(2)
+        .spacing(egui::vec2(10.0, 6.0))
+        .show(ui, |ui| {
+            // Trim: zmiana punktu wejscia/wyjscia zmienia dlugosc klipu, start zostaje
+            ui.label("Source In");
+            let mut source_in = clip.source_in;
+            let source_out = clip.source_out();
+            if ui
+                .add(egui::DragValue::new(&mut source_in).speed(0.01).suffix(" s").clamp_range(0.0..=(source_out - 0.04).max(0.0)))
+                .changed()
+            {
+                clip.source_in = snap(source_in);
+                clip.end = clip.start + (source_out - clip.source_in);
+            }
+            ui.end_row();
+
+            ui.label("Source Out");
+            let mut source_out = clip.source_out();
+            if ui
+                .add(egui::DragValue::new(&mut source_out).speed(0.01).suffix(" s").clamp_range((clip.source_in + 0.04)..=f32::MAX))
+                .changed()
+            {
+                clip.end = clip.start + (snap(source_out) - clip.source_in);
+            }
+            ui.end_row();
+
+            let duration = clip.output_duration();
+            ui.label("Duration");
+            ui.label(format!("{duration:.2} s"));
+            ui.end_row();
+
+            ui.label("Fade In");
+            ui.add(egui::Slider::new(&mut clip.fade_in, 0.0..=(duration - clip.fade_out).max(0.0)).suffix(" s"));
+            ui.end_row();
+
+            ui.label("Fade Out");
+            ui.add(egui::Slider::new(&mut clip.fade_out, 0.0..=(duration - clip.fade_in).max(0.0)).suffix(" s"));
+            ui.end_row();
+
+            ui.label("Transition")
+                .on_hover_text("Video transition into the next V1 clip (clips overlap, the render gets shorter)");
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("transition_kind")
+                    .selected_text(clip.transition_out.kind.to_string())
+                    .show_ui(ui, |ui| {
+                        for kind in TransitionKind::ALL {
+                            ui.selectable_value(&mut clip.transition_out.kind, kind, kind.to_string());
+                        }
+                    });
+                if clip.transition_out.kind != TransitionKind::Cut {
+                    ui.add(egui::Slider::new(&mut clip.transition_out.duration, 0.0..=(duration * 0.5).max(0.1)).suffix(" s"));
+                }
+            });
+            ui.end_row();
+
+            ui.label("Audio X-fade")
+                .on_hover_text("Audio continues past the cut and mixes into the next clip (needs its Fade In)");
+            ui.add(egui::Slider::new(&mut clip.audio_crossfade_out, 0.0..=5.0).suffix(" s"));
+            ui.end_row();
+
+            ui.label("Volume");
+            ui.add(egui::Slider::new(&mut clip.volume, 0.0..=2.0));
+            ui.end_row();
+
+            ui.label("Pan");
+            ui.horizontal(|ui| {
+                pan_knob(ui, &mut clip.audio_pan);
+                ui.add(egui::DragValue::new(&mut clip.audio_pan).speed(0.01).clamp_range(-1.0..=1.0));
+            });
+            ui.end_row();
+
+            ui.label("Phase");
+            ui.checkbox(&mut clip.audio_phase_inverted, "Invert phase");
+            ui.end_row();
+
+            ui.label("EQ");
+            eq_graph_ui(ui, &mut clip.eq_bands);
+            ui.end_row();
+
+            ui.label("Speed");
+            ui.add(egui::DragValue::new(&mut clip.speed).speed(0.01).suffix("x").clamp_range(0.25..=4.0));
+            ui.end_row();
+
+            // Rampa: rowne wartosci (== speed) wylaczaja ja
+            ui.label("Speed Ramp");
+            ui.horizontal(|ui| {
+                let (mut ramp_start, mut ramp_end) = clip.speed_ramp.unwrap_or((clip.speed, clip.speed));
+                let start_changed = ui
+                    .add(egui::DragValue::new(&mut ramp_start).speed(0.01).prefix("Start speed ").suffix("x").clamp_range(0.25..=4.0))
+                    .changed();
+                let end_changed = ui
+                    .add(egui::DragValue::new(&mut ramp_end).speed(0.01).prefix("End speed ").suffix("x").clamp_range(0.25..=4.0))
+                    .changed();
+                if start_changed || end_changed {
+                    let flat = (ramp_start - clip.speed).abs() < 0.001 && (ramp_end - clip.speed).abs() < 0.001;
+                    clip.speed_ramp = (!flat).then_some((ramp_start, ramp_end));
+                }
+            });
+            ui.end_row();
+
+            ui.label("Color Label");
+            egui::ComboBox::from_id_source("clip_color_label")
+                .selected_text(clip.color_label.to_string())
+                .show_ui(ui, |ui| {
+                    for color in ClipColor::ALL {
+                        ui.selectable_value(&mut clip.color_label, color, color.to_string());
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Video track");
+            let mut track = clip.track_index + 1;
+            if ui.add(egui::DragValue::new(&mut track).prefix("V").clamp_range(1..=8)).changed() {
+                clip.track_index = track - 1;
+            }
+            ui.end_row();
+
+            if clip.track_index > 0 {
+                ui.label("Opacity");
+                ui.add(egui::Slider::new(&mut clip.opacity, 0.0..=1.0).fixed_decimals(2));
+                ui.end_row();
+            }
+
+            ui.label("Tracks");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut clip.video_enabled, "Video");
+                ui.checkbox(&mut clip.audio_enabled, "Audio");
+            });
+            ui.end_row();
+
+            ui.label("Audio source");
+            ui.horizontal(|ui| {
+                if ui.button("Replace audio...").clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Audio", &["wav", "mp3", "aac", "m4a", "flac", "ogg", "opus"])
+                        .pick_file()
+                {
+                    clip.audio_override = Some(path.display().to_string());
+                }
+                if let Some(path) = &clip.audio_override {
+                    let name = Path::new(path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                    ui.label(egui::RichText::new(name).color(OVERRIDE_WAVEFORM_COLOR)).on_hover_text(path);
+                    if ui.small_button("✖").on_hover_text("Restore original audio").clicked() {
+                        clip.audio_override = None;
+                    }
+                }
+            });
+            ui.end_row();
+        });
+
+    ui.label("Effects");
+    effect_stack_ui(ui, &mut clip.effects);
+
+    ui.label("Notes");
+    ui.add(egui::TextEdit::multiline(&mut clip.notes).desired_rows(3).desired_width(f32::INFINITY));
+
+    ui.collapsing("Advanced Filters", |ui| {
+        let escape_help = "Filters separated by ','. Escape , : ' \\ [ ] with \\ or quote the value in '...'. ';' is not allowed.";
+        egui::Grid::new("clip_custom_filters").num_columns(2).show(ui, |ui| {
+            for (label, value) in [("Video (-vf)", &mut clip.custom_vf), ("Audio (-af)", &mut clip.custom_af)] {
+                ui.label(label);
+                let mut text = value.clone().unwrap_or_default();
+                let valid = validate_filter_expr(&text).is_ok();
+                let edit = egui::TextEdit::singleline(&mut text)
+                    .hint_text("e.g. eq=contrast=1.2")
+                    .text_color_opt((!valid).then_some(egui::Color32::from_rgb(255, 90, 90)));
+                if ui.add(edit).on_hover_text(escape_help).changed() {
+                    *value = (!text.trim().is_empty()).then_some(text);
+                }
+                ui.end_row();
+            }
+        });
+        if ui.button("Test filter").clicked() {
+            test_filter = true;
+        }
+    });
+    test_filter
+}
+
+/// Stos efektow klipu: kolejnosc (filtry nakladane od gory), parametry, usuwanie i menu dodawania
+fn effect_stack_ui(ui: &mut egui::Ui, effects: &mut Vec<ClipEffect>) {
+    let count = effects.len();
+    let mut move_up = None;
+    let mut remove = None;
+    for (i, effect) in effects.iter_mut().enumerate() {
+        ui.push_id(("clip_effect", i), |ui| {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() {
+                    move_up = Some(i);
+                }
+                if ui.add_enabled(i + 1 < count, egui::Button::new("⬇").small()).clicked() {
+                    move_up = Some(i + 1);
+                }
+                if ui.small_button("🗑").on_hover_text("Remove effect").clicked() {
+                    remove = Some(i);
+                }
+                ui.label(effect.label());
+                effect_params_ui(ui, effect);
+            });
+        });
+    }
+    if let Some(i) = move_up {
+        effects.swap(i - 1, i);
+    }
+    if let Some(i) = remove {
+        effects.remove(i);
+    }
+    ui.menu_button("➕ Add effect", |ui| {
+        for effect in ClipEffect::ALL {
+            if ui.button(effect.label()).clicked() {
+                effects.push(effect);
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+fn effect_params_ui(ui: &mut egui::Ui, effect: &mut ClipEffect) {
+    match effect {
+        ClipEffect::Sharpness(sharpness) => {
+            ui.add(egui::DragValue::new(sharpness).speed(0.05).clamp_range(-5.0..=5.0))
+                .on_hover_text("-5.0 .. 5.0: > 0 sharpens (unsharp), < 0 blurs (boxblur), 0 = off");
+        }
+        ClipEffect::Denoise(preset) => {
+            egui::ComboBox::from_id_source("clip_denoise")
+                .selected_text(preset.label())
+                .show_ui(ui, |ui| {
+                    for option in [DenoisePreset::Mild, DenoisePreset::Medium, DenoisePreset::Strong] {
+                        ui.selectable_value(preset, option, option.label());
+                    }
+                    let custom = match *preset {
+                        DenoisePreset::Custom(v) => v,
+                        _ => 4.0,
+                    };
+                    ui.selectable_value(preset, DenoisePreset::Custom(custom), "Custom");
+                });
+            if let DenoisePreset::Custom(luma) = preset {
+                ui.add(egui::DragValue::new(luma).speed(0.1).clamp_range(0.0..=16.0))
+                    .on_hover_text("Luma spatial (chroma/temporal derived)");
+            }
+        }
+        ClipEffect::ColorCorrection { brightness, contrast, saturation } => {
+            ui.add(egui::DragValue::new(brightness).speed(0.01).prefix("B ").clamp_range(-1.0..=1.0))
+                .on_hover_text("Brightness");
+            ui.add(egui::DragValue::new(contrast).speed(0.01).prefix("C ").clamp_range(0.0..=3.0))
+                .on_hover_text("Contrast");
+            ui.add(egui::DragValue::new(saturation).speed(0.01).prefix("S ").clamp_range(0.0..=3.0))
+                .on_hover_text("Saturation");
+        }
+        ClipEffect::Vignette(angle) => {
+            ui.add(egui::Slider::new(angle, 0.0..=std::f32::consts::FRAC_PI_2).fixed_decimals(2))
+                .on_hover_text("Vignette angle (radians)");
+        }
+    }
+}
+
+/// Nakladka histogramu w prawym dolnym rogu kadru; kazdy kanal normalizowany do swojego maksimum
+fn draw_histogram(painter: &egui::Painter, draw_rect: egui::Rect, hist: &Histogram) {
+    let size = egui::vec2(256.0f32.min(draw_rect.width() * 0.4), 100.0f32.min(draw_rect.height() * 0.3));
+    let area = egui::Rect::from_min_size(draw_rect.right_bottom() - size - egui::vec2(8.0, 8.0), size);
+    painter.rect_filled(area, 4.0, egui::Color32::from_black_alpha(150));
+    let colors = [
+        egui::Color32::from_rgba_unmultiplied(255, 60, 60, 200),
+        egui::Color32::from_rgba_unmultiplied(60, 255, 60, 200),
+        egui::Color32::from_rgba_unmultiplied(80, 120, 255, 200),
+        egui::Color32::from_rgba_unmultiplied(255, 255, 255, 220),
+    ];
+    for (bins, color) in hist.iter().zip(colors) {
+        let max = bins.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let points = bins
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| {
+                egui::pos2(
+                    area.left() + area.width() * i as f32 / 255.0,
+                    area.bottom() - area.height() * count as f32 / max,
+                )
+            })
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+    }
+}
+
+/// Największy prostokąt o danych proporcjach wpisany w `rect` (na środku)
+fn fit_aspect(rect: egui::Rect, aspect: f32) -> egui::Rect {
+    let mut size = egui::vec2(rect.width(), rect.width() / aspect);
+    if size.y > rect.height() {
+        size = egui::vec2(rect.height() * aspect, rect.height());
+    }
+    egui::Rect::from_center_size(rect.center(), size)
+}
+
+/// Prostokąt i UV podglądu w panelu `rect` dla trybu dopasowania. W trybie Native obraz
+/// `native_size` większy od panelu jest przycinany; `pan` (przesunięcie od środka) jest ograniczany do krawędzi.
+fn preview_fit_layout(
+    fit: PreviewFit,
+    video_aspect: f32,
+    native_size: egui::Vec2,
+    rect: egui::Rect,
+    pan: &mut egui::Vec2,
+) -> (egui::Rect, egui::Rect) {
+    let full_uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    match fit {
+        PreviewFit::Fit => (fit_aspect(rect, video_aspect), full_uv),
+        PreviewFit::Fill => output_frame_layout(AspectRatioMode::Crop, video_aspect, rect),
+        PreviewFit::Stretch => (rect, full_uv),
+        PreviewFit::Native => {
+            let visible = native_size.min(rect.size());
+            let max_pan = ((native_size - visible) / 2.0).max(egui::Vec2::ZERO);
+            *pan = pan.clamp(-max_pan, max_pan);
+            let frac = visible / native_size;
+            let min = (egui::vec2(0.5, 0.5) - frac / 2.0) + *pan / native_size;
+            let uv = egui::Rect::from_min_size(min.to_pos2(), frac);
+            (egui::Rect::from_center_size(rect.center(), visible), uv)
+        }
+    }
+}
+
+/// Gdzie narysować obraz źródła w kadrze wyjściowym (prostokąt + UV) dla danego trybu proporcji
+fn output_frame_layout(mode: AspectRatioMode, src_aspect: f32, frame: egui::Rect) -> (egui::Rect, egui::Rect) {
+    let full_uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    let frame_aspect = frame.width() / frame.height();
+    match mode {
+        AspectRatioMode::Stretch => (frame, full_uv),
+        AspectRatioMode::Letterbox => {
+            let size = egui::vec2(frame.width(), frame.width() / src_aspect);
+            (egui::Rect::from_center_size(frame.center(), size).intersect(frame), full_uv)
+        }
+        AspectRatioMode::Pillarbox => {
+            let size = egui::vec2(frame.height() * src_aspect, frame.height());
+            (egui::Rect::from_center_size(frame.center(), size).intersect(frame), full_uv)
+        }
+        AspectRatioMode::Crop => {
+            // Przycinamy UV zrodla tak, by wypelnilo caly kadr
+            let uv = if src_aspect > frame_aspect {
+                let w = frame_aspect / src_aspect;
+                egui::Rect::from_min_max(egui::pos2((1.0 - w) / 2.0, 0.0), egui::pos2((1.0 + w) / 2.0, 1.0))
+            } else {
+                let h = src_aspect / frame_aspect;
+                egui::Rect::from_min_max(egui::pos2(0.0, (1.0 - h) / 2.0), egui::pos2(1.0, (1.0 + h) / 2.0))
+            };
+            (frame, uv)
+        }
+    }
+}
+
+/// Glosnosc klipu audio w danym czasie (0.0 = przerwa miedzy klipami)
+fn interval_gain(intervals: &[(f32, f32, f32)], time: f32) -> f32 {
+    intervals
+        .iter()
+        .find(|(s, e, _)| time >= *s && time < *e)
+        .map(|(_, _, gain)| *gain)
+        .unwrap_or(0.0)
+}
+
+/// Szczegoly klipu w dymku przy kursorze
+fn clip_tooltip(ctx: &egui::Context, app: &VideoEditorApp, clip: &Clip, idx: usize) {
+    let fps = app.video_fps;
+    let source = match clip.asset_id.and_then(|id| app.media_library.get(id)) {
+        Some(asset) => asset.path.clone(),
+        None => app.input_path.clone(),
+    };
+    let on_off = |b: bool| if b { "on" } else { "off" };
+    egui::show_tooltip(ctx, egui::Id::new("clip_hover_tooltip").with(idx), |ui| {
+        egui::Grid::new("clip_tooltip_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Source");
+            ui.label(source);
+            ui.end_row();
+            ui.label("Start");
+            ui.label(secs_to_timecode(clip.start, fps, app.drop_frame));
+            ui.end_row();
+            ui.label("End");
+            ui.label(secs_to_timecode(clip.end, fps, app.drop_frame));
+            ui.end_row();
+            ui.label("Duration");
+            ui.label(format!("{:.2} s", clip.output_duration()));
+            ui.end_row();
+            ui.label("Source Duration");
+            ui.label(format!("{:.2} s", clip.end - clip.start));
+            ui.end_row();
+            ui.label("Fade In / Out");
+            ui.label(format!("{:.2} s / {:.2} s", clip.fade_in, clip.fade_out));
+            ui.end_row();
+            ui.label("Volume");
+            ui.label(format!("{:.2}", clip.volume));
+            ui.end_row();
+            ui.label("Pan");
+            ui.label(format!("{:+.2}", clip.audio_pan));
+            ui.end_row();
+            ui.label("Speed");
+            ui.label(format!("{:.2}x", clip.speed));
+            ui.end_row();
+            ui.label("Color Label");
+            ui.label(clip.color_label.to_string());
+            ui.end_row();
+            ui.label("Video / Audio");
+            ui.label(format!("{} / {}", on_off(clip.video_enabled), on_off(clip.audio_enabled)));
+            ui.end_row();
+        });
+    });
+}
+
+/// Skraca tekst z "…" tak, by miescil sie w `max_width`
+fn truncate_to_width(ui: &egui::Ui, text: &str, font: &egui::FontId, max_width: f32) -> String {
+    let width_of = |s: &str| ui.fonts(|f| f.layout_no_wrap(s.to_owned(), font.clone(), egui::Color32::WHITE).size().x);
+    if width_of(text) <= max_width {
+        return text.to_owned();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().collect::<String>() + "…";
+        if width_of(&candidate) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// Bialy lub ciemny tekst zaleznie od jasnosci tla
+fn contrast_text_color(bg: egui::Color32) -> egui::Color32 {
+    let luminance = 0.299 * bg.r() as f32 + 0.587 * bg.g() as f32 + 0.114 * bg.b() as f32;
+    if luminance > 150.0 { egui::Color32::from_gray(20) } else { egui::Color32::WHITE }
+}
+
+/// Podmenu kolorow dla wielu zaznaczonych klipow
+fn batch_color_menu(ui: &mut egui::Ui, label: &str) -> Option<ClipColor> {
+    let mut picked = None;
+    ui.separator();
+    ui.menu_button(format!("🎨 {label}"), |ui| {
+        for color in ClipColor::ALL {
+            let swatch = color.color32().unwrap_or(egui::Color32::GRAY);
+            if ui.button(egui::RichText::new(color.to_string()).color(swatch)).clicked() {
+                picked = Some(color);
+                ui.close_menu();
+            }
+        }
+    });
+    picked
+}
+
+const ABOUT_REPOSITORY: &str = "https://github.com/szansky/RustyCut-";
+
+/// Kluczowe zaleznosci (wersje z Cargo.lock) pokazywane w oknie "O programie"
+const ABOUT_DEPENDENCIES: [(&str, &str); 5] = [
+    ("eframe", "0.27.2"),
+    ("cpal", "0.15.3"),
+    ("serde", "1.0.228"),
+    ("image", "0.25.9"),
+    ("anyhow", "1.0.100"),
+];
+
+/// Operacje edycyjne z menu kontekstowego klipu (indeks klipu w kazdej akcji)
+#[derive(Clone, Copy)]
+enum ClipMenuAction {
+    Split(usize),
+    Duplicate(usize),
+    Reverse(usize),
+    Properties(usize),
+    Color(usize, ClipColor),
+    TrimLeft(usize),
+    TrimRight(usize),
+    FillToNext(usize, f32),
+    MoveToTrack(usize, u32),
+}
+
+/// Pozycje edycyjne menu kontekstowego klipu; niedostepne akcje sa wyszarzone
+fn clip_edit_menu(
+    ui: &mut egui::Ui,
+    text: &TextResources,
+    clip: &Clip,
+    idx: usize,
+    playhead: f32,
+    next_start: Option<f32>,
+) -> Option<ClipMenuAction> {
+    let mut action = None;
+    let mut item = |ui: &mut egui::Ui, enabled: bool, label: &str, value: ClipMenuAction| {
+        if ui.add_enabled(enabled, egui::Button::new(label)).clicked() {
+            action = Some(value);
+            ui.close_menu();
+        }
+    };
+    let inside = playhead > clip.start && playhead < clip.timeline_end();
+    ui.separator();
+    item(ui, inside, &format!("✂ {}", text.split_at_playhead), ClipMenuAction::Split(idx));
+    item(ui, true, &format!("⧉ {}", text.duplicate_clip), ClipMenuAction::Duplicate(idx));
+    let reverse_label = if clip.reversed { &text.unreverse_clip } else { &text.reverse_clip };
+    item(ui, clip.video_enabled || clip.audio_enabled, &format!("⟲ {reverse_label}"), ClipMenuAction::Reverse(idx));
+    item(ui, inside, &format!("⇤ {}", text.trim_to_playhead_left), ClipMenuAction::TrimLeft(idx));
+    item(ui, inside, &format!("⇥ {}", text.trim_to_playhead_right), ClipMenuAction::TrimRight(idx));
+    // Przedluzenie tylko dla stalej predkosci (bez rampy), gdy za klipem jest luka
+    let fill = next_start.filter(|&next| next > clip.timeline_end() + 0.01 && clip.active_speed_ramp().is_none());
+    item(ui, fill.is_some(), &format!("→| {}", text.fill_to_next_clip), ClipMenuAction::FillToNext(idx, fill.unwrap_or_default()));
+    ui.menu_button(format!("🎨 {}", text.color_label), |ui| {
+        for color in ClipColor::ALL {
+            let swatch = color.color32().unwrap_or(egui::Color32::GRAY);
+            let label = egui::RichText::new(color.to_string()).color(swatch);
+            if ui.add_enabled(color != clip.color_label, egui::Button::new(label)).clicked() {
+                action = Some(ClipMenuAction::Color(idx, color));
+                ui.close_menu();
+            }
+        }
+    });
+    ui.menu_button(format!("☰ {}", text.move_to_track), |ui| {
+        for track in 0..8u32 {
+            if ui.add_enabled(track != clip.track_index, egui::Button::new(format!("V{}", track + 1))).clicked() {
+                action = Some(ClipMenuAction::MoveToTrack(idx, track));
+                ui.close_menu();
+            }
+        }
+    });
+    if ui.button(format!("⚙ {}", text.clip_properties_title)).clicked() {
+        action = Some(ClipMenuAction::Properties(idx));
+        ui.close_menu();
+    }
+    action
+}
+
+/// Akcje grup z menu kontekstowego klipu
+#[derive(Clone, Copy)]
+enum GroupMenuAction {
+    Group,
+    Select(usize),
+    Rename(u32),
+    Ungroup(u32),
+}
+
+/// Pozycje menu kontekstowego dotyczace grup
+fn group_menu(ui: &mut egui::Ui, text: &TextResources, clip: &Clip, idx: usize, selection_count: usize) -> Option<GroupMenuAction> {
+    let mut action = None;
+    if selection_count < 2 && clip.group_id.is_none() {
+        return None;
+    }
+    ui.separator();
+    if selection_count >= 2 && ui.button(format!("{} (Ctrl+G)", text.group_clips)).clicked() {
+        action = Some(GroupMenuAction::Group);
+        ui.close_menu();
+    }
+    if let Some(id) = clip.group_id {
+        if ui.button(&text.select_group).clicked() {
+            action = Some(GroupMenuAction::Select(idx));
+            ui.close_menu();
+        }
+        if ui.button(&text.rename_group).clicked() {
+            action = Some(GroupMenuAction::Rename(id));
+            ui.close_menu();
+        }
+        if ui.button(&text.ungroup).clicked() {
+            action = Some(GroupMenuAction::Ungroup(id));
+            ui.close_menu();
+        }
+    }
+    action
+}
+
+/// Kolor obramowania grupy (stały dla danego id)
+fn group_color(id: u32) -> egui::Color32 {
+    egui::ecolor::Hsva::new((id as f32 * 0.618_034).fract(), 0.6, 0.95, 1.0).into()
+}
+
+/// Wysokosc punktu obwiedni na pasku audio (wzmocnienie 0.0 - 2.0)
+fn envelope_y(rect: egui::Rect, gain: f32) -> f32 {
+    rect.bottom() - (gain / 2.0).clamp(0.0, 1.0) * rect.height()
+}
+
+/// Obracajacy sie luk (spinner) na slabym okregu; kat zwiekszany co klatke przez wywolujacego
+fn draw_arc_spinner(ui: &mut egui::Ui, angle: f32, color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+    let radius = rect.width() * 0.5 - 2.0;
+    ui.painter().circle_stroke(rect.center(), radius, egui::Stroke::new(2.0, color.gamma_multiply(0.25)));
+    let points: Vec<egui::Pos2> = (0..=16)
+        .map(|i| {
+            let a = angle + i as f32 / 16.0 * std::f32::consts::PI * 1.5;
+            rect.center() + egui::vec2(a.cos(), a.sin()) * radius
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(2.0, color)));
+}
+
+/// Zmienia zoom osi czasu tak, by czas pod `anchor_x` zostal w tym samym miejscu ekranu
+fn zoom_timeline_at(app: &mut VideoEditorApp, factor: f32, anchor_x: f32, left: f32, width: f32, min_zoom: f32, max_zoom: f32) {
+    let t_at_anchor = app.timeline_offset + (anchor_x - left) / app.timeline_zoom;
+    app.timeline_zoom = (app.timeline_zoom * factor).clamp(min_zoom, max_zoom);
+    let new_window = width / app.timeline_zoom;
+    app.timeline_offset = clamp_offset(t_at_anchor - (anchor_x - left) / app.timeline_zoom, app.duration, new_window);
+}
+
+fn envelope_gain_from_y(rect: egui::Rect, y: f32) -> f32 {
+    ((rect.bottom() - y) / rect.height() * 2.0).clamp(0.0, 2.0)
 }
 
-fn split_clip_at(clips: &mut Vec<Clip>, idx: usize, t: f32) -> Option<usize> {
-    let clip = clips.get(idx)?;
-    if t <= clip.start || t >= clip.end {
-        return None;
+
+
+
+
+/// Urzadzenie wyjsciowe audio o podanej nazwie; puste lub nieznalezione = domyslne systemowe
+fn output_device(name: &str) -> Result<cpal::Device> {
+    let host = cpal::default_host();
+    if !name.is_empty()
+        && let Ok(mut devices) = host.output_devices()
+        && let Some(device) = devices.find(|d| d.name().is_ok_and(|n| n == name))
+    {
+        return Ok(device);
+    }
+    host.default_output_device().ok_or_else(|| anyhow!("Brak urzadzenia audio"))
+}
+
+/// Nazwy dostepnych urzadzen wyjsciowych audio (do wyboru w ustawieniach)
+fn output_device_names() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Czy czas `rel` od poczatku klipu miesci sie w przycieciu sciezki (brak przyciecia = caly klip)
+fn trim_contains(trim: Option<(f32, f32)>, rel: f32) -> bool {
+    trim.is_none_or(|(a, b)| rel >= a && rel < b)
+}
+
+/// Strumien wyjsciowy 44.1 kHz stereo zasilany probkami scrub z `buffer`
+fn build_scrub_stream(buffer: &Arc<Mutex<VecDeque<i16>>>, device_name: &str) -> Result<cpal::Stream> {
+    let device = output_device(device_name)?;
+    let format = device.default_output_config()?.sample_format();
+    let config = cpal::StreamConfig {
+        channels: SCRUB_CHANNELS,
+        sample_rate: cpal::SampleRate(SCRUB_SAMPLE_RATE),
+        buffer_size: cpal::BufferSize::Default,
+    };
+    let stream = match format {
+        cpal::SampleFormat::I16 => scrub_output_stream::<i16>(&device, &config, buffer)?,
+        cpal::SampleFormat::U16 => scrub_output_stream::<u16>(&device, &config, buffer)?,
+        cpal::SampleFormat::F32 => scrub_output_stream::<f32>(&device, &config, buffer)?,
+        other => return Err(anyhow!("Nieobslugiwany format audio: {other:?}")),
+    };
+    stream.play()?;
+    Ok(stream)
+}
+
+fn scrub_output_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    buffer: &Arc<Mutex<VecDeque<i16>>>,
+) -> Result<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<i16>,
+{
+    let buffer = Arc::clone(buffer);
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            let mut q = buffer.lock().ok();
+            for sample in data.iter_mut() {
+                let value = q.as_mut().and_then(|q| q.pop_front()).unwrap_or(0);
+                *sample = T::from_sample(value);
+            }
+        },
+        |err| eprintln!("Audio error: {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// Gra cichy ton testowy i porownuje zegar audio (probki oddane do urzadzenia) z zegarem
+/// systemowym; zwraca mnoznik korekty dla synchronizacji A/V
+fn measure_audio_clock_correction(duration: std::time::Duration, device_name: &str) -> Result<f32> {
+    let device = output_device(device_name)?;
+    let config = device.default_output_config()?;
+    let rate = config.sample_rate().0 as f32;
+    let channels = config.channels().max(1) as f32;
+    let played = Arc::new(AtomicU64::new(0));
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::I16 => test_tone_stream::<i16>(&device, &config.into(), rate, channels as usize, &played)?,
+        cpal::SampleFormat::U16 => test_tone_stream::<u16>(&device, &config.into(), rate, channels as usize, &played)?,
+        cpal::SampleFormat::F32 => test_tone_stream::<f32>(&device, &config.into(), rate, channels as usize, &played)?,
+        other => return Err(anyhow!("Nieobslugiwany format audio: {other:?}")),
+    };
+    stream.play()?;
+
+    // Start pomiaru od pierwszego callbacku (pomijamy rozruch urzadzenia)
+    let wait_start = Instant::now();
+    while played.load(Ordering::Relaxed) == 0 {
+        if wait_start.elapsed() > std::time::Duration::from_secs(2) {
+            return Err(anyhow!("Urzadzenie audio nie pobiera probek"));
+        }
+        thread::sleep(std::time::Duration::from_millis(5));
+    }
+    let samples_start = played.load(Ordering::Relaxed);
+    let start = Instant::now();
+    thread::sleep(duration);
+    let samples = played.load(Ordering::Relaxed) - samples_start;
+    let elapsed = start.elapsed().as_secs_f32();
+    drop(stream);
+
+    let audio_time = samples as f32 / (rate * channels);
+    if audio_time <= 0.0 {
+        return Err(anyhow!("Brak postepu zegara audio"));
+    }
+    Ok((elapsed / audio_time).clamp(0.8, 1.2))
+}
+
+/// Strumien z tonem 440 Hz (cicho), liczacy oddane probki
+fn test_tone_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    rate: f32,
+    channels: usize,
+    played: &Arc<AtomicU64>,
+) -> Result<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let played = Arc::clone(played);
+    let mut phase = 0.0f32;
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            for frame in data.chunks_mut(channels) {
+                let value = (phase * std::f32::consts::TAU).sin() * 0.1;
+                phase = (phase + 440.0 / rate).fract();
+                for sample in frame.iter_mut() {
+                    *sample = T::from_sample(value);
+                }
+            }
+            played.fetch_add(data.len() as u64, Ordering::Relaxed);
+        },
+        |err| eprintln!("Audio error: {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+impl VideoEditorApp {
+    fn save_project_as(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Rust Video Editor Project", &["rev", "json"])
+            .save_file() 
+        {
+            if self.project_read_only && self.project_path.as_ref() == Some(&path) {
+                self.set_status(StatusKind::Warning, self.text.project_read_only.clone());
+                return;
+            }
+            if ProjectLock::holder(&path).is_some() {
+                self.set_status(StatusKind::Error, format!("{}: {}", self.text.project_locked_title, path.display()));
+                return;
+            }
+            let data = self.project_data();
+            match serde_json::to_string_pretty(&data) {
+                Ok(json) => {
+                    if let Err(e) = fs::write(&path, json) {
+                        self.log.push(LogEntry::error(format!("Blad zapisu projektu: {e}")));
+                    } else {
+                        // Zapis pod nowa nazwa konczy tryb tylko do odczytu; blokada idzie za plikiem
+                        if self.project_path.as_ref() != Some(&path) || self.project_lock.is_none() {
+                            self.project_lock = ProjectLock::acquire(&path).ok();
+                        }
+                        self.project_read_only = false;
+                        self.project_path = Some(path);
+                        self.set_status(StatusKind::Success, self.text.status_project_saved.clone());
+                    }
+                }
+                Err(e) => {
+                    self.log.push(LogEntry::error(format!("Blad serializacji: {e}")));
+                }
+            }
+        }
+    }
+
+    /// Co `auto_save_interval_secs` zapisuje kopie projektu obok pliku .rev ({nazwa}.autosave.rev)
+    fn maybe_auto_save(&mut self) {
+        let interval = self.settings.auto_save_interval_secs;
+        if interval == 0 || self.project_read_only || self.last_auto_save.elapsed().as_secs() < interval {
+            return;
+        }
+        self.last_auto_save = Instant::now();
+        let Some(path) = self.project_path.as_ref().map(|p| p.with_extension("autosave.rev")) else { return };
+        if self.clips.is_empty() {
+            return;
+        }
+        let result = serde_json::to_string_pretty(&self.project_data())
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&path, json).map_err(anyhow::Error::from));
+        if let Err(err) = result {
+            self.log.push(LogEntry::error(format!("Blad autozapisu: {err:#}")));
+        }
+    }
+
+    /// Migawka stanu projektu do zapisu
+    fn project_data(&self) -> ProjectData {
+        ProjectData {
+            version: CURRENT_PROJECT_VERSION,
+            input_path: self.input_path.clone(),
+            output_path: self.output_path.clone(),
+            playhead: self.playhead,
+            clips: self.clips.clone(),
+            media_library: self.media_library.clone(),
+            subtitles: self.subtitles.clone(),
+            groups: self.groups.clone(),
+            markers: self.markers.clone(),
+            watermark: self.render_settings.watermark.clone(),
+            duration: self.duration,
+            video_width: self.video_width,
+            video_height: self.video_height,
+            video_fps: self.video_fps,
+        }
+    }
+
+    fn load_project_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Rust Video Editor Project", &["rev", "json"])
+            .pick_file()
+        else {
+            return;
+        };
+        // Projekt otwarty w innej instancji - decyzja w oknie show_project_locked_window
+        if let Some(info) = ProjectLock::holder(&path) {
+            self.pending_locked_project = Some((path, info));
+            return;
+        }
+        self.open_project(ctx, path, false);
+    }
+
+    /// Okno "projekt w uzyciu": tylko do odczytu, wymuszenie albo rezygnacja
+    fn show_project_locked_window(&mut self, ctx: &egui::Context) {
+        let Some((path, info)) = self.pending_locked_project.clone() else { return };
+        let mut open = true;
+        let mut choice = None;
+        egui::Window::new(&self.text.project_locked_title)
+            .collapsible(false)
+            .resizable(false)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.screen_rect().center())
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(&self.text.project_locked_message);
+                let since = chrono::DateTime::from_timestamp(info.timestamp, 0)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                ui.label(egui::RichText::new(format!("{} (PID {}, {since})", path.display(), info.pid)).small().weak());
+                ui.horizontal(|ui| {
+                    if ui.button(&self.text.open_read_only).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(&self.text.force_open).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if let Some(read_only) = choice {
+            self.pending_locked_project = None;
+            self.open_project(ctx, path, read_only);
+        } else if !open {
+            self.pending_locked_project = None;
+        }
+    }
+
+    /// Wczytuje projekt; bez `read_only` zaklada (lub przejmuje) blokade pliku
+    fn open_project(&mut self, ctx: &egui::Context, path: PathBuf, read_only: bool) {
+        match load_project_file(&path) {
+            Ok(data) => {
+                self.missing_media = check_missing_media(&data, &data.media_library);
+                self.input_path = data.input_path;
+                self.output_path = data.output_path;
+                self.clips = data.clips;
+                self.media_library = data.media_library;
+                self.subtitles = data.subtitles;
+                self.groups = data.groups;
+                self.markers = data.markers;
+                if data.watermark.is_some() {
+                    self.render_settings.watermark = data.watermark;
+                }
+                self.duration = data.duration;
+                self.video_width = data.video_width;
+                self.video_height = data.video_height;
+                self.video_fps = data.video_fps;
+                self.drop_frame = is_drop_frame_rate(self.video_fps);
+                self.playhead = data.playhead;
+                self.project_path = Some(path.clone());
+                self.project_read_only = read_only;
+                self.project_lock = None;
+                if read_only {
+                    self.log.push(LogEntry::warning(self.text.project_read_only.clone()));
+                } else {
+                    match ProjectLock::force_acquire(&path) {
+                        Ok(lock) => self.project_lock = Some(lock),
+                        Err(err) => self.log.push(LogEntry::warning(format!("{err:#}"))),
+                    }
+                }
+                
+                // Reset stanu UI
+                self.selected_clip = None;
+                self.selected_clips.clear();
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+                self.stop_playback();
+                
+                // Przywrocenie zasobow (podglady, waveform)
+                if !self.input_path.is_empty() && Path::new(&self.input_path).exists() {
+                    self.prepare_media_assets(ctx);
+                }
+                
+                self.rebuild_library_thumbs(ctx);
+                self.set_status(StatusKind::Success, self.text.status_project_loaded.clone());
+            }
+            Err(e) if e.downcast_ref::<ProjectTooNew>().is_some() => {
+                self.log.push(LogEntry::error(self.text.project_too_new.clone()));
+            }
+            Err(e) => {
+                self.log.push(LogEntry::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    /// Pływające okno właściwości zaznaczonego klipu
+    fn show_clip_properties_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_clip_properties;
+        let selected = self.selected_clip.filter(|&idx| idx < self.clips.len());
+        let snap_fps = (self.settings.timeline_snap_to_frames && self.video_fps > 0.0).then_some(self.video_fps);
+        let no_clip_label = self.text.no_clip_selected.clone();
+        let effects_before = selected.and_then(|idx| clip_video_effects(&self.clips[idx]));
+        let clips = &mut self.clips;
+        let mut test_filter = false;
+        let mut trim_request: Option<Vec<usize>> = None;
+        let mut chop_request = false;
+        let chop_parts = &mut self.chop_parts;
+        let (chop_label, chop_button) = (self.text.chop_parts.clone(), self.text.chop_clip.clone());
+        let trim_busy = self.trim_silence_thread.is_some();
+        let (trim_label, trim_all_label) = (self.text.trim_silence.clone(), self.text.trim_all_silence.clone());
+        let window = egui::Window::new(&self.text.clip_properties_title)
+            .id(egui::Id::new("clip_properties"))
+            .default_pos(self.settings.clip_props_pos)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                // Bez zaznaczenia pokazujemy wyszarzony formularz
+                let mut placeholder = Clip::new(None, 0.0, 0.0);
+                let clip = match selected {
+                    Some(idx) => &mut clips[idx],
+                    None => {
+                        ui.label(egui::RichText::new(no_clip_label).italics());
+                        &mut placeholder
+                    }
+                };
+                ui.add_enabled_ui(selected.is_some(), |ui| {
+                    test_filter = clip_properties_ui(ui, clip, snap_fps);
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!trim_busy && selected.is_some(), egui::Button::new(&trim_label)).clicked() {
+                        trim_request = selected.map(|idx| vec![idx]);
+                    }
+                    if ui.add_enabled(!trim_busy && !clips.is_empty(), egui::Button::new(&trim_all_label)).clicked() {
+                        trim_request = Some((0..clips.len()).collect());
+                    }
+                    if trim_busy {
+                        ui.spinner();
+                    }
+                });
+                ui.add_enabled_ui(selected.is_some(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(&chop_label);
+                        ui.add(egui::DragValue::new(chop_parts).clamp_range(2..=100));
+                        if ui.button(&chop_button).clicked() {
+                            chop_request = true;
+                        }
+                    });
+                });
+            });
+        self.show_clip_properties = open;
+        if let Some(indices) = trim_request {
+            self.start_silence_trim(indices);
+        }
+        if chop_request && let Some(idx) = selected {
+            self.push_undo();
+            let parts = chop_clip(&mut self.clips, idx, self.chop_parts);
+            self.selected_clip = Some(idx);
+            self.selected_clips.clear();
+            self.log.push(LogEntry::info(format!("Pocieto klip na {} czesci", parts.len())));
+        }
+        if test_filter && let Some(idx) = selected {
+            self.test_clip_filter(idx);
+        }
+        // Zmiana efektow obrazu (np. wyostrzenie) od razu odswieza podglad
+        if let Some(idx) = selected
+            && clip_video_effects(&self.clips[idx]) != effects_before
+            && let Err(err) = self.request_preview(ctx)
+        {
+            self.log.push(LogEntry::error(format!("Blad podgladu: {err:#}")));
+        }
+
+        // Zapamietanie pozycji okna (po puszczeniu myszy)
+        if let Some(window) = window {
+            let pos = window.response.rect.min;
+            if pos != self.settings.clip_props_pos && !ctx.input(|i| i.pointer.any_down()) {
+                self.settings.clip_props_pos = pos;
+                if let Err(err) = save_settings(&self.settings) {
+                    self.log.push(LogEntry::error(format!("Blad zapisu ustawien: {err:#}")));
+                }
+            }
+        }
+    }
+
+    /// Probna klatka z wlasnym filtrem wideo klipu - wynik trafia do logu
+    fn test_clip_filter(&mut self, idx: usize) {
+        let clip = &self.clips[idx];
+        let vf = clip.custom_vf.clone().unwrap_or_default();
+        if let Err(err) = validate_filter_expr(&vf) {
+            self.log.push(LogEntry::error(format!("{err:#}")));
+            return;
+        }
+        let path = match clip.asset_id.and_then(|id| self.media_library.get(id)) {
+            Some(asset) => asset.path.clone(),
+            None => self.input_path.clone(),
+        };
+        let filter = match (clip_video_effects(clip), vf.trim()) {
+            (Some(effects), "") => Some(effects),
+            (Some(effects), custom) => Some(format!("{effects},{custom}")),
+            (None, "") => None,
+            (None, custom) => Some(custom.to_string()),
+        };
+        match generate_frame_memory_filtered(&path, clip.source_in, 320, 0, filter.as_deref()) {
+            Ok(_) => self.log.push(LogEntry::info("Filtr OK.")),
+            Err(err) => self.log.push(LogEntry::error(format!("Blad filtra: {err:#}"))),
+        }
+    }
+
+    /// Drugie okno (viewport egui) pokazujace ta sama teksture podgladu co okno glowne
+    fn secondary_preview_viewport(&mut self, ctx: &egui::Context) {
+        let aspect = if self.video_height > 0 {
+            self.video_width as f32 / self.video_height as f32
+        } else {
+            16.0 / 9.0
+        };
+        let texture = self.preview_texture.clone();
+        let mut close = false;
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("secondary_preview"),
+            egui::ViewportBuilder::default()
+                .with_title("RustyCut - Preview")
+                .with_inner_size([960.0, 540.0]),
+            |ctx, _class| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::none().fill(egui::Color32::BLACK))
+                    .show(ctx, |ui| {
+                        if let Some(texture) = &texture {
+                            let rect = fit_aspect(ui.max_rect(), aspect);
+                            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                            ui.painter().image(texture.id(), rect, uv, egui::Color32::WHITE);
+                        }
+                    });
+                close = ctx.input(|i| i.viewport().close_requested());
+            },
+        );
+        if close {
+            self.show_secondary_preview = false;
+        }
+    }
+
+    /// Siatka klipow z klatkami pogladowymi; klik = zaznacz i przewin, przeciagniecie = zmiana kolejnosci
+    fn storyboard_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading(&self.text.storyboard);
+            ui.add(egui::Slider::new(&mut self.storyboard_columns, 1..=10).text(&self.text.storyboard_columns));
+        });
+        ui.separator();
+
+        let cols = self.storyboard_columns.max(1);
+        let spacing = ui.spacing().item_spacing.x;
+        let cell_w = ((ui.available_width() - spacing * (cols - 1) as f32) / cols as f32 - 8.0).max(60.0);
+        let thumb_h = cell_w * 9.0 / 16.0;
+        let fps = self.video_fps;
+        let clip_count = self.clips.len();
+        let mut clicked = None;
+        let mut reorder = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for row_start in (0..clip_count).step_by(cols) {
+                ui.horizontal(|ui| {
+                    for idx in row_start..(row_start + cols).min(clip_count) {
+                        let id = egui::Id::new("storyboard_cell").with(idx);
+                        let cell = ui.dnd_drag_source(id, idx, |ui| self.storyboard_cell(ui, idx, cell_w, thumb_h, fps));
+                        let resp = cell.response;
+                        if let Some(from) = resp.dnd_release_payload::<usize>() {
+                            reorder = Some((*from, idx));
+                        }
+                        if ui.interact(resp.rect, id.with("click"), egui::Sense::click()).clicked() {
+                            clicked = Some(idx);
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(idx) = clicked {
+            self.selected_clip = Some(idx);
+            self.selected_clips.clear();
+            self.playhead = self.clips[idx].start;
+        }
+        if let Some((from, to)) = reorder {
+            self.move_clip(from, to);
+        }
+    }
+
+    /// Przenosi klip na inna pozycje listy (storyboard, lista klipow) i uklada os czasu od nowa
+    fn move_clip(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.clips.len() || to >= self.clips.len() {
+            return;
+        }
+        self.push_undo();
+        let clip = self.clips.remove(from);
+        self.clips.insert(to, clip);
+        // Uklad na osi czasu musi odpowiadac kolejnosci renderu - klipy jeden za drugim
+        let mut t = self.clips.iter().map(|c| c.start).fold(f32::INFINITY, f32::min);
+        for clip in &mut self.clips {
+            let span = clip.end - clip.start;
+            clip.start = t;
+            clip.end = t + span;
+            t = clip.timeline_end();
+        }
+        self.selected_clip = Some(to);
+        self.selected_clips.clear();
+    }
+
+    /// Klipy jako lista (nr, start/koniec, dlugosc, etykieta); przeciagniecie = zmiana kolejnosci,
+    /// dwuklik = playhead na poczatek klipu. Zwraca true przy przewinieciu.
+    fn clip_list_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let fps = self.video_fps;
+        let mut clicked = None;
+        let mut seek = None;
+        let mut reorder = None;
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            for (idx, clip) in self.clips.iter().enumerate() {
+                let selected = self.selected_clip == Some(idx) || self.selected_clips.contains(&idx);
+                let id = egui::Id::new("clip_list_row").with(idx);
+                let row = ui.dnd_drag_source(id, idx, |ui| {
+                    ui.horizontal(|ui| {
+                        let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 14.0), egui::Sense::hover());
+                        let color = clip.color_label.color32().unwrap_or(egui::Color32::DARK_GRAY);
+                        ui.painter().rect_filled(swatch, 2.0, color);
+                        let text = format!(
+                            "#{:<3} {} - {}   {:.2}s",
+                            idx + 1,
+                            secs_to_timecode(clip.start, fps, self.drop_frame),
+                            secs_to_timecode(clip.end, fps, self.drop_frame),
+                            clip.output_duration()
+                        );
+                        let mut label = egui::RichText::new(text).monospace();
+                        if selected {
+                            label = label.color(egui::Color32::YELLOW);
+                        }
+                        ui.label(label);
+                        if clip.color_label != ClipColor::None {
+                            ui.label(egui::RichText::new(clip.color_label.to_string()).small().weak());
+                        }
+                    });
+                });
+                let resp = row.response;
+                if let Some(from) = resp.dnd_release_payload::<usize>() {
+                    reorder = Some((*from, idx));
+                }
+                let click = ui.interact(resp.rect, id.with("click"), egui::Sense::click());
+                if click.double_clicked() {
+                    seek = Some(idx);
+                } else if click.clicked() {
+                    clicked = Some(idx);
+                }
+            }
+        });
+
+        if let Some(idx) = clicked {
+            self.selected_clip = Some(idx);
+            self.selected_clips.clear();
+        }
+        if let Some((from, to)) = reorder {
+            self.move_clip(from, to);
+        }
+        if let Some(idx) = seek {
+            self.selected_clip = Some(idx);
+            self.playhead = self.clips[idx].start;
+            return true;
+        }
+        false
+    }
+
+    fn storyboard_cell(&mut self, ui: &mut egui::Ui, idx: usize, cell_w: f32, thumb_h: f32, fps: f32) {
+        let selected = self.selected_clip == Some(idx) || self.selected_clips.contains(&idx);
+        let stroke = if selected {
+            egui::Stroke::new(2.0, egui::Color32::YELLOW)
+        } else {
+            egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)
+        };
+        egui::Frame::group(ui.style()).stroke(stroke).show(ui, |ui| {
+            ui.set_width(cell_w);
+            ui.vertical(|ui| {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(cell_w, thumb_h), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, egui::Color32::BLACK);
+                if let Some(texture) = self.storyboard_poster(idx) {
+                    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                    ui.painter().image(texture.id(), rect, uv, egui::Color32::WHITE);
+                }
+                let clip = &self.clips[idx];
+                if let Some(color) = clip.color_label.color32() {
+                    let stripe = egui::Rect::from_min_size(rect.left_top(), egui::vec2(rect.width(), 4.0));
+                    ui.painter().rect_filled(stripe, 0.0, color);
+                }
+                ui.label(egui::RichText::new(format!("#{}  {}", idx + 1, secs_to_timecode(clip.start, fps, self.drop_frame))).strong());
+                ui.label(egui::RichText::new(format!("{:.2}s", clip.output_duration())).small());
+            });
+        });
+    }
+
+    /// Klatka pogladowa klipu: ikona assetu z biblioteki albo najblizsza miniatura osi czasu
+    fn storyboard_poster(&mut self, idx: usize) -> Option<egui::TextureHandle> {
+        let clip = &self.clips[idx];
+        if let Some(asset_id) = clip.asset_id {
+            return self.media_thumbs.get(&asset_id).cloned();
+        }
+        let source_in = clip.source_in;
+        let slot = self
+            .thumb_times
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.is_nan())
+            .min_by(|(_, a), (_, b)| (*a - source_in).abs().total_cmp(&(*b - source_in).abs()))
+            .map(|(i, &t)| (i, t))?;
+        let texture = self.thumb_textures[slot.0].clone();
+        if texture.is_none() && !self.thumb_requests.contains(&slot.1) {
+            self.thumb_requests.push(slot.1);
+        }
+        texture
+    }
+
+    /// Skrypt uzytkownika dostaje klipy jako JSON na stdin; zwrocone klipy zastepuja obecne
+    fn run_script_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Script", &["js", "lua"])
+            .pick_file()
+        else {
+            return;
+        };
+        let request = serde_json::json!({
+            "clips": self.clips,
+            "duration": self.duration,
+            "input_path": self.input_path,
+        });
+        let library_len = self.media_library.len();
+        let script = path.clone();
+        // Skrypt w tle (limit czasu w run_script); wynik odbiera poll_script_result
+        let handle = thread::spawn(move || {
+            let response = parse_script_response(&run_script(&script, &request.to_string())?)?;
+            validate_script_clips(&response.clips, library_len)?;
+            Ok(response)
+        });
+        self.script_thread = Some((path, handle));
+    }
+
+    /// Wstawia klipy zwrocone przez skrypt, gdy watek skonczyl
+    fn poll_script_result(&mut self, ctx: &egui::Context) {
+        let Some((_, handle)) = &self.script_thread else { return };
+        if !handle.is_finished() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        let Some((path, handle)) = self.script_thread.take() else { return };
+        match handle.join() {
+            Ok(Ok(response)) => {
+                self.push_undo();
+                self.clips = response.clips;
+                self.selected_clip = None;
+                self.selected_clips.clear();
+                let status = if response.status.is_empty() { "OK".to_string() } else { response.status };
+                self.log.push(LogEntry::info(format!("Skrypt {}: {status}", path.display())));
+            }
+            Ok(Err(err)) => self.log.push(LogEntry::error(format!("Blad skryptu: {err:#}"))),
+            Err(_) => self.log.push(LogEntry::error("Blad: watek skryptu przerwany.")),
+        }
+    }
+
+    /// File > Export render script: zapis .sh / .bat z komendami renderu
+    fn export_render_script_dialog(&mut self) {
+        let (name, ext) = if cfg!(windows) { ("render.bat", "bat") } else { ("render.sh", "sh") };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Render script", &[ext])
+            .set_file_name(name)
+            .save_file()
+        else {
+            return;
+        };
+        let project_path = self.project_path.clone().unwrap_or_default();
+        let render_output = self.resolved_output_path();
+        match export_render_script(
+            &project_path,
+            &self.render_settings_snapshot(),
+            &self.clips,
+            &self.media_library,
+            &self.input_path,
+            &render_output,
+            &path,
+        ) {
+            Ok(()) => self.log.push(LogEntry::info(format!("Zapisano skrypt: {}", path.display()))),
+            Err(err) => self.log.push(LogEntry::error(format!("Blad eksportu skryptu: {err:#}"))),
+        }
+    }
+
+    /// Pakuje projekt z mediami do ZIP w osobnym watku (postep w archive_progress)
+    fn export_project_archive_dialog(&mut self) {
+        if self.archive_thread.is_some() {
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("ZIP", &["zip"])
+            .set_file_name("project.zip")
+            .save_file()
+        else {
+            return;
+        };
+        let data = self.project_data();
+        let library = self.media_library.clone();
+        let progress = self.archive_progress.clone();
+        if let Ok(mut p) = progress.lock() {
+            *p = (0, 0);
+        }
+        self.archive_thread = Some(thread::spawn(move || {
+            archive_project(&data, &library, &path, Some(&progress))?;
+            Ok(path)
+        }));
+    }
+
+    /// Uruchamia render w osobnym watku (postep w render_progress)
+    fn start_render(&mut self) {
+        if self.render_thread.is_some() {
+            return;
+        }
+        // Render liczy sciezke od nowa; podglad tez, bo zaraz powstanie nowy plik
+        self.output_path_preview = None;
+        let output_path = self.resolved_output_path();
+        if Path::new(&output_path).exists() {
+            self.pending_render_confirmation = Some(PendingRenderConfirmation { output_path });
+            return;
+        }
+        self.launch_render(output_path);
+    }
+
+    fn launch_render(&mut self, output_path: String) {
+        let settings = self.render_settings_snapshot();
+        if let Ok(mut p) = self.render_progress.lock() {
+            *p = RenderProgress {
+                total_frames: render_total_frames(&self.clips, &settings, self.video_fps),
+                ..RenderProgress::default()
+            };
+        }
+
+        let input_path = self.cached_source(&self.input_path);
+        let clips = self.clips.clone();
+        let mut assets = self.media_library.clone();
+        for asset in &mut assets {
+            asset.path = self.cached_source(&asset.path);
+        }
+        let subtitles = self.subtitles.clone();
+        let markers = self.markers.clone();
+        let progress = Arc::clone(&self.render_progress);
+        self.set_status(StatusKind::Working, self.text.status_rendering.clone());
+        self.render_thread = Some(thread::spawn(move || {
+            render_deliverables(&input_path, &output_path, &clips, &assets, &settings, &subtitles, Some(&progress))?;
+            if settings.write_webvtt_chapters {
+                write_chapters_for_output(&markers, &clips, &output_path)?;
+            }
+            Ok(())
+        }));
+    }
+
+    /// Znacznik rozdzialu na playheadzie (bez duplikatow w tym samym miejscu)
+    fn add_marker(&mut self) {
+        let time = self.snap(self.playhead);
+        if self.markers.iter().any(|m| (m.time - time).abs() < 0.001) {
+            return;
+        }
+        let title = format!("{} {}", self.text.marker_default_name, self.markers.len() + 1);
+        self.markers.push(Marker { time, title });
+        self.markers.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    /// Lista znacznikow: klik w czas = skok, edycja tytulu, usuwanie
+    fn markers_ui(&mut self, ui: &mut egui::Ui) {
+        if self.markers.is_empty() {
+            ui.label(egui::RichText::new(&self.text.markers_empty).weak());
+            return;
+        }
+        let (fps, drop_frame) = (self.video_fps, self.drop_frame);
+        let mut seek = None;
+        let mut remove = None;
+        egui::ScrollArea::vertical().id_source("markers_list").max_height(160.0).show(ui, |ui| {
+            for (i, marker) in self.markers.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.link(secs_to_timecode(marker.time, fps, drop_frame)).clicked() {
+                        seek = Some(marker.time);
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut marker.title).desired_width(140.0));
+                    if ui.small_button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+        });
+        if let Some(time) = seek {
+            self.playhead = time;
+            self.stop_playback();
+            self.maybe_update_preview(ui.ctx());
+        }
+        if let Some(i) = remove {
+            self.markers.remove(i);
+        }
+    }
+
+    /// Ustawienia renderu z zakresem mark in / mark out z chwili startu (lub dodania do kolejki)
+    fn render_settings_snapshot(&self) -> RenderSettings {
+        let range = self.mark_in.zip(self.mark_out).filter(|(start, end)| end > start);
+        RenderSettings { render_range: range, ..self.render_settings.clone() }
+    }
+
+    /// Dodaje zapisany projekt do kolejki renderu (wyjscie i ustawienia z chwili dodania)
+    fn add_to_render_queue(&mut self) {
+        let Some(project_path) = self.project_path.clone() else {
+            self.log.push(LogEntry::warning("Zapisz projekt przed dodaniem do kolejki renderu."));
+            return;
+        };
+        let entry = QueueEntry {
+            project_path,
+            output_path: PathBuf::from(self.resolved_output_path()),
+            settings: self.render_settings_snapshot(),
+            status: QueueStatus::Pending,
+        };
+        if let Ok(mut queue) = self.render_queue.lock() {
+            queue.push(entry);
+        }
+        self.log.push(LogEntry::info("Dodano projekt do kolejki renderu."));
+    }
+
+    /// Przetwarza oczekujace pozycje kolejki po kolei w watku w tle
+    fn start_render_queue(&mut self) {
+        if self.queue_thread.is_some() || self.render_thread.is_some() {
+            return;
+        }
+        let queue = Arc::clone(&self.render_queue);
+        let progress = Arc::clone(&self.render_progress);
+        self.queue_thread = Some(thread::spawn(move || run_render_queue(&queue, &progress)));
+        self.set_status(StatusKind::Working, self.text.status_queue_running.clone());
+    }
+
+    fn show_render_queue_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut remove = None;
+        let mut start = false;
+        let mut clear_finished = false;
+        let running = self.queue_thread.is_some();
+        let entries = self.render_queue.lock().map(|q| q.clone()).unwrap_or_default();
+        egui::Window::new(&self.text.render_queue_title)
+            .default_width(520.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if entries.is_empty() {
+                    ui.label(egui::RichText::new(&self.text.render_queue_empty).weak());
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("render_queue_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        for (i, entry) in entries.iter().enumerate() {
+                            let file_name = |p: &Path| p.file_name().unwrap_or_default().to_string_lossy().to_string();
+                            ui.label(file_name(&entry.project_path)).on_hover_text(entry.project_path.display().to_string());
+                            ui.label(format!("→ {}", file_name(&entry.output_path))).on_hover_text(entry.output_path.display().to_string());
+                            match &entry.status {
+                                QueueStatus::Pending => ui.label(&self.text.queue_pending),
+                                QueueStatus::Running => {
+                                    let percent = self.render_progress.lock().map(|p| p.percent).unwrap_or(0.0);
+                                    ui.add(egui::ProgressBar::new(percent / 100.0).desired_width(120.0).show_percentage())
+                                }
+                                QueueStatus::Done(took) => ui.label(
+                                    egui::RichText::new(format!("{} ({})", self.text.queue_done, format_eta(took.as_secs_f64())))
+                                        .color(egui::Color32::from_rgb(100, 200, 100)),
+                                ),
+                                QueueStatus::Failed(err) => ui
+                                    .label(egui::RichText::new(&self.text.queue_failed).color(egui::Color32::from_rgb(255, 90, 90)))
+                                    .on_hover_text(err),
+                            };
+                            if ui.add_enabled(entry.status != QueueStatus::Running, egui::Button::new("🗑").small()).clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let has_pending = entries.iter().any(|e| e.status == QueueStatus::Pending);
+                    let can_start = !running && self.render_thread.is_none() && has_pending;
+                    if ui.add_enabled(can_start, egui::Button::new(&self.text.start_queue)).clicked() {
+                        start = true;
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                    if ui.button(&self.text.clear_finished).clicked() {
+                        clear_finished = true;
+                    }
+                });
+            });
+        if let Ok(mut queue) = self.render_queue.lock() {
+            if let Some(i) = remove
+                && queue.get(i).is_some_and(|e| e.status != QueueStatus::Running)
+            {
+                queue.remove(i);
+            }
+            if clear_finished {
+                queue.retain(|e| matches!(e.status, QueueStatus::Pending | QueueStatus::Running));
+            }
+        }
+        if start {
+            self.start_render_queue();
+        }
+        self.show_render_queue = open;
+    }
+
+    /// Interpretuje wpis z okna "Go to": numer klipu albo timecode -> (klip, czas)
+    fn parse_goto_input(&self) -> Option<(Option<usize>, f32)> {
+        let input = self.goto_input.trim();
+        if let Ok(n) = input.parse::<usize>() {
+            return self.clips.get(n).map(|clip| (Some(n), clip.start));
+        }
+        timecode_to_secs(input, self.video_fps, self.drop_frame).map(|t| (None, t.min(self.duration.max(0.0))))
+    }
+
+    /// Nadaje wszystkim zaznaczonym klipom wspolny, nowy identyfikator grupy
+    fn group_selected(&mut self) {
+        let indices = self.selected_indices();
+        if indices.len() < 2 {
+            return;
+        }
+        self.push_undo();
+        let id = next_group_id(&self.clips);
+        for &idx in &indices {
+            self.clips[idx].group_id = Some(id);
+        }
+        self.groups.insert(id, format!("{} {id}", self.text.group_default_name));
+        self.selected_clips = indices;
+    }
+
+    fn ungroup(&mut self, id: u32) {
+        self.push_undo();
+        for clip in self.clips.iter_mut().filter(|c| c.group_id == Some(id)) {
+            clip.group_id = None;
+        }
+        self.groups.remove(&id);
+    }
+
+    /// Zaznacza wszystkie klipy grupy (pierwszy zostaje selected_clip, jesli nic z grupy nie bylo zaznaczone)
+    fn select_group(&mut self, idx: usize) {
+        let members = group_members(&self.clips, idx);
+        if !self.selected_clip.is_some_and(|sel| members.contains(&sel)) {
+            self.selected_clip = members.first().copied();
+        }
+        self.selected_clips = members;
+    }
+
+    /// Usuwa klip razem z cala jego grupa (z Ripple Delete, jesli wlaczony)
+    fn remove_clip_with_group(&mut self, idx: usize) {
+        let mut members = group_members(&self.clips, idx);
+        // Od konca, zeby indeksy pozostalych czlonkow sie nie przesuwaly
+        members.sort_unstable_by(|a, b| b.cmp(a));
+        for idx in members {
+            let duration = self.clips[idx].end - self.clips[idx].start;
+            self.clips.remove(idx);
+            if self.settings.ripple_delete {
+                // Ripple Delete - przesun pozostale klipy
+                for clip in self.clips.iter_mut().skip(idx) {
+                    clip.start -= duration;
+                    clip.end -= duration;
+                }
+            }
+        }
+        self.selected_clip = None;
+        self.selected_clips.clear();
+    }
+
+    fn show_rename_group_window(&mut self, ctx: &egui::Context) {
+        let Some((id, mut name)) = self.renaming_group.take() else { return };
+        let mut open = true;
+        let mut done = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        egui::Window::new(&self.text.rename_group)
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .pivot(egui::Align2::CENTER_TOP)
+            .default_pos(ctx.screen_rect().center_top() + egui::vec2(0.0, 80.0))
+            .show(ctx, |ui| {
+                let edit = ui.text_edit_singleline(&mut name);
+                if !edit.has_focus() && !edit.lost_focus() {
+                    edit.request_focus();
+                }
+                if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    let trimmed = name.trim();
+                    if !trimmed.is_empty() {
+                        self.groups.insert(id, trimmed.to_string());
+                    }
+                    done = true;
+                }
+            });
+        if open && !done {
+            self.renaming_group = Some((id, name));
+        }
+    }
+
+    /// Tekst okna "O programie" (wersje aplikacji, narzedzi i zaleznosci) - tez do schowka
+    fn about_report(&mut self) -> String {
+        let (ffmpeg, ffprobe) = self
+            .about_tool_versions
+            .get_or_insert_with(|| {
+                let version = |result: Result<String>| result.unwrap_or_else(|e| format!("not found ({e})"));
+                (version(check_ffmpeg_version()), version(check_ffprobe_version()))
+            })
+            .clone();
+        let mut report = format!(
+            "RustyCut {}\nffmpeg: {ffmpeg}\nffprobe: {ffprobe}\nrustc: {}\nOS: {} {}\n\n",
+            env!("CARGO_PKG_VERSION"),
+            option_env!("RUSTC_VERSION").unwrap_or("unknown (edition 2024)"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        );
+        for (name, version) in ABOUT_DEPENDENCIES {
+            report.push_str(&format!("{name} {version}\n"));
+        }
+        report
+    }
+
+    fn show_about_window(&mut self, ctx: &egui::Context) {
+        let report = self.about_report();
+        let mut open = true;
+        egui::Window::new(&self.text.about_title)
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.screen_rect().center())
+            .show(ctx, |ui| {
+                ui.heading(format!("RustyCut {}", env!("CARGO_PKG_VERSION")));
+                ui.label(egui::RichText::new(&self.text.about_tagline).weak());
+                ui.add_space(6.0);
+                egui::Grid::new("about_grid").num_columns(2).striped(true).show(ui, |ui| {
+                    for line in report.lines().skip(1).filter(|l| !l.is_empty()) {
+                        let (name, value) = line.split_once(": ").or_else(|| line.split_once(' ')).unwrap_or((line, ""));
+                        ui.label(egui::RichText::new(name).strong());
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(6.0);
+                ui.hyperlink_to(&self.text.about_source, ABOUT_REPOSITORY);
+                ui.label(format!("{}: MIT / Apache-2.0", self.text.about_license));
+                ui.add_space(6.0);
+                if ui.button(&self.text.copy_to_clipboard).clicked() {
+                    ui.output_mut(|o| o.copied_text = report.clone());
+                }
+            });
+        if !open {
+            self.show_about = false;
+        }
+    }
+
+    /// Okno "Go to" - zwraca true gdy nastapil skok
+    fn show_goto_window(&mut self, ctx: &egui::Context) -> bool {
+        let mut seeked = false;
+        let target = self.parse_goto_input();
+        let fps = self.video_fps;
+        let mut close = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        egui::Window::new(&self.text.goto_title)
+            .collapsible(false)
+            .resizable(false)
+            .pivot(egui::Align2::CENTER_TOP)
+            .default_pos(ctx.screen_rect().center_top() + egui::vec2(0.0, 80.0))
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&self.text.goto_hint).small().weak());
+                let edit = ui.text_edit_singleline(&mut self.goto_input);
+                if !edit.has_focus() && !edit.lost_focus() {
+                    edit.request_focus();
+                }
+                let preview = match target {
+                    Some((Some(n), t)) => format!("→ Clip #{n} @ {}", secs_to_timecode(t, fps, self.drop_frame)),
+                    Some((None, t)) => format!("→ {}", secs_to_timecode(t, fps, self.drop_frame)),
+                    None if self.goto_input.trim().is_empty() => String::new(),
+                    None => "—".to_string(),
+                };
+                ui.label(egui::RichText::new(preview).color(egui::Color32::GRAY));
+
+                if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    if let Some((clip, t)) = target {
+                        if clip.is_some() {
+                            self.selected_clip = clip;
+                            self.selected_track = TrackType::Both;
+                        }
+                        self.playhead = t;
+                        seeked = true;
+                    }
+                    close = true;
+                }
+            });
+        if close {
+            self.show_goto = false;
+        }
+        seeked
+    }
+
+    /// Nazwa projektu dla szablonu wyjścia (nazwa pliku bez rozszerzenia)
+    fn project_name(&self) -> String {
+        self.project_path
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+    }
+
+    /// resolved_output_path dla UI, przeliczana tylko po zmianie danych wejsciowych
+    fn output_path_preview(&mut self) -> String {
+        let key = (
+            self.use_output_template,
+            self.output_template.template.clone(),
+            self.project_name(),
+            self.output_path.clone(),
+            self.input_path.clone(),
+            chrono::Local::now().format("%Y%m%d%H%M%S").to_string(),
+        );
+        if let Some(preview) = &self.output_path_preview
+            && preview.key == key
+        {
+            return preview.path.clone();
+        }
+        let path = self.resolved_output_path();
+        self.output_path_preview = Some(OutputPathPreview { key, path: path.clone() });
+        path
+    }
+
+    /// Ścieżka wyjściowa po rozwinięciu szablonu (jeśli włączony)
+    fn resolved_output_path(&self) -> String {
+        if self.use_output_template {
+            expand_output_template(&self.output_template.template, &self.project_name())
+        } else if is_output_dir(&self.output_path) {
+            // Katalog: nazwa z pliku zrodlowego (projektu, gdy brak glownego wejscia)
+            let source = if self.input_path.is_empty() { self.project_name() } else { self.input_path.clone() };
+            auto_output_path(&source, Path::new(&self.output_path), "mp4").display().to_string()
+        } else {
+            self.output_path.clone()
+        }
+    }
+
+    /// Regeneracja miniatur biblioteki
+    fn rebuild_library_thumbs(&mut self, ctx: &egui::Context) {
+        self.media_thumbs.clear();
+        for (idx, asset) in self.media_library.iter().enumerate() {
+             let mut thumb = None;
+             let path = Path::new(&asset.path);
+             if asset.kind == MediaType::Image {
+                 if let Ok(t) = load_texture_from_path(ctx, path, &format!("thumb_{}", idx)) {
+                     thumb = Some(t);
+                 }
+             } else {
+                 // Video thumb
+                 if let Ok(t) = self.cached_thumbnail(ctx, &asset.path, asset.duration * 0.1, 128, 0, &format!("thumb_{}", idx)) {
+                     thumb = Some(t);
+                 }
+             }
+             if let Some(t) = thumb {
+                 self.media_thumbs.insert(idx, t); 
+             }
+        }
+    }
+
+    /// Miniatura, filmstrip i waveform dla nowo dodanego assetu biblioteki
+    fn prepare_library_asset(&mut self, ctx: &egui::Context, idx: usize, dur: f32) {
+        let Some(asset) = self.media_library.get(idx) else { return };
+        let path_str = asset.path.clone();
+        let kind = asset.kind;
+        // Generate thumbnail
+        let thumb_result = match kind {
+            MediaType::Image => {
+                // Load image directly as thumbnail (scaled)
+                load_texture_from_path(ctx, Path::new(&path_str), &format!("lib_thumb_{}", idx))
+            },
+            MediaType::Video => {
+                // Extract frame at 10% of duration
+                let thumb_time = dur * 0.1;
+                self.cached_thumbnail(ctx, &path_str, thumb_time, 80, 0, &format!("lib_thumb_{}", idx))
+            },
+            MediaType::Audio => {
+                // No thumbnail for audio - will use icon
+                Err(anyhow!("Audio has no thumbnail"))
+            }
+        };
+
+        if let Ok(texture) = thumb_result {
+            self.media_thumbs.insert(idx, texture);
+        }
+
+        // Generate Filmstrip (5 thumbs for video)
+        if kind == MediaType::Video {
+            let count = 5;
+            let step = if dur > 0.0 { dur / count as f32 } else { 1.0 };
+            let mut strips = Vec::new();
+            for i in 0..count {
+                let t = (i as f32 + 0.5) * step;
+                // Small width for memory efficiency
+                if let Ok(tex) = self.cached_thumbnail(ctx, &path_str, t, 160, 0, &format!("film_{}_{}", idx, i)) {
+                    strips.push((t, tex));
+                }
+            }
+            if !strips.is_empty() {
+                self.media_filmstrips.insert(idx, strips);
+            }
+        }
+
+        // Generate Waveform (Audio or Video)
+        if kind == MediaType::Audio || kind == MediaType::Video {
+            self.build_library_waveform(ctx, idx, &path_str);
+        }
+    }
+
+    /// Waveform assetu biblioteki (rysowany na klipach z tego assetu)
+    fn build_library_waveform(&mut self, ctx: &egui::Context, idx: usize, path: &str) {
+        if let Ok(cache) = self.cache_dir() {
+            let wave_path = cache.join(format!("wave_{}.png", idx));
+            if generate_waveform(path, &wave_path, self.settings.waveform_amplitude).is_ok()
+                && let Ok(tex) = load_texture_from_path(ctx, &wave_path, &format!("wave_{}", idx))
+            {
+                self.media_waveforms.insert(idx, tex);
+            }
+            self.enforce_cache_limit();
+        }
+    }
+
+    /// Menu skalowania amplitudy waveformu; true, gdy tryb sie zmienil
+    fn waveform_amplitude_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.settings.waveform_amplitude;
+        let mut threshold = match before {
+            WaveformAmplitude::Dbfs(threshold) => threshold,
+            _ => WaveformAmplitude::DEFAULT_DBFS,
+        };
+        let mut changed = false;
+        ui.menu_button(format!("〰 {before}"), |ui| {
+            ui.label(&self.text.waveform_amplitude);
+            for mode in [WaveformAmplitude::Auto, WaveformAmplitude::Normalized, WaveformAmplitude::Dbfs(threshold)] {
+                let selected = std::mem::discriminant(&mode) == std::mem::discriminant(&before);
+                let label = match mode {
+                    WaveformAmplitude::Dbfs(_) => "dBFS".to_owned(),
+                    _ => mode.to_string(),
+                };
+                if ui.radio(selected, label).clicked() && !selected {
+                    self.settings.waveform_amplitude = mode;
+                    changed = true;
+                    ui.close_menu();
+                }
+            }
+            if matches!(before, WaveformAmplitude::Dbfs(_)) {
+                let response = ui.add(egui::Slider::new(&mut threshold, -60.0..=0.0).suffix(" dBFS"));
+                // Regeneracja dopiero po puszczeniu suwaka
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    self.settings.waveform_amplitude = WaveformAmplitude::Dbfs(threshold);
+                    changed = true;
+                }
+            }
+        })
+        .response
+        .on_hover_text(&self.text.waveform_amplitude_hint);
+        changed
+    }
+
+    /// Po zmianie skalowania amplitudy: waveform glownego pliku, biblioteki i audio zastepczego od nowa
+    fn regenerate_waveforms(&mut self, ctx: &egui::Context) {
+        if !self.input_path.is_empty()
+            && let Err(err) = self.build_waveform(ctx)
+        {
+            self.set_status(StatusKind::Error, format!("Blad waveform: {err:#}"));
+        }
+        let assets: Vec<(usize, String)> = self
+            .media_library
+            .iter()
+            .enumerate()
+            .filter(|(_, asset)| matches!(asset.kind, MediaType::Audio | MediaType::Video))
+            .map(|(idx, asset)| (idx, asset.path.clone()))
+            .collect();
+        for (idx, path) in assets {
+            self.build_library_waveform(ctx, idx, &path);
+        }
+        // Rysowanie osi czasu zglosi je ponownie; wynik trwajacego generowania (stara amplituda) jest porzucany
+        self.override_waveforms.clear();
+        self.override_waveform_thread = None;
+    }
+
+    /// Dodaje plik do biblioteki (typ wg ffprobe i rozszerzenia); false, gdy ffprobe go nie odczytal
+    fn import_media_file(&mut self, ctx: &egui::Context, path: &Path) -> bool {
+        let path_str = path.display().to_string();
+        // Detect type using ffprobe logic or extension
+        let Ok((dur, w, h, _fps, _)) = get_video_info_ffprobe(&path_str) else { return false };
+        let kind = if w == 0 && h == 0 {
+            MediaType::Audio
+        } else if dur < 0.1 && (path_str.ends_with(".png") || path_str.ends_with(".jpg") || path_str.ends_with(".jpeg") || path_str.ends_with(".webp")) {
+            MediaType::Image
+        } else {
+            MediaType::Video
+        };
+
+        let idx = self.media_library.len();
+        let asset = MediaAsset {
+            id: idx,
+            path: path_str.clone(),
+            name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            kind,
+            duration: if kind == MediaType::Image {
+                5.0
+            } else if dur < 0.1 {
+                println!("WARNING: Detected duration 0.0s for {}, defaulting to 10.0s", path_str);
+                10.0
+            } else {
+                dur
+            },
+        };
+        self.media_library.push(asset);
+        self.prepare_library_asset(ctx, idx, dur);
+        true
+    }
+
+    /// Pliki przeciagane nad oknem: ffprobe w tle i podglad informacji; upuszczone trafiaja do biblioteki
+    fn handle_file_drag_and_drop(&mut self, ctx: &egui::Context) {
+        let (hovered, dropped) = ctx.input(|i| (i.raw.hovered_files.clone(), i.raw.dropped_files.clone()));
+        let hovered: Vec<PathBuf> = hovered.into_iter().filter_map(|f| f.path).collect();
+        for path in &hovered {
+            let key = path.display().to_string();
+            if self.drop_probes.contains_key(&key) || self.drop_probe_threads.iter().any(|(p, _)| *p == key) {
+                continue;
+            }
+            let probe_path = key.clone();
+            let handle = thread::spawn(move || {
+                let (duration, width, height, ..) = get_video_info_ffprobe(&probe_path)?;
+                Ok(DropProbe {
+                    duration,
+                    width,
+                    height,
+                    info: get_media_info_ffprobe(&probe_path).unwrap_or_default(),
+                    size_bytes: fs::metadata(&probe_path).map(|m| m.len()).unwrap_or(0),
+                })
+            });
+            self.drop_probe_threads.push((key, handle));
+        }
+
+        let (finished, running): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.drop_probe_threads).into_iter().partition(|(_, handle)| handle.is_finished());
+        self.drop_probe_threads = running;
+        for (path, handle) in finished {
+            let result = match handle.join() {
+                Ok(result) => result.map_err(|err| format!("{err:#}")),
+                Err(_) => Err("watek ffprobe przerwany".to_owned()),
+            };
+            self.drop_probes.insert(path, result);
+        }
+
+        if !hovered.is_empty() {
+            self.drop_probe_tooltip(ctx, &hovered);
+        }
+        let mut imported = 0;
+        for path in dropped.into_iter().filter_map(|f| f.path) {
+            if self.import_media_file(ctx, &path) {
+                imported += 1;
+            } else {
+                self.log.push(LogEntry::warning(format!("Nie mozna zaimportowac {}", path.display())));
+            }
+        }
+        if imported > 0 {
+            self.set_status(StatusKind::Success, format!("Zaimportowano plikow: {imported}"));
+        }
+    }
+
+    /// Panel z informacjami o przeciaganych plikach (nad srodkiem okna)
+    fn drop_probe_tooltip(&mut self, ctx: &egui::Context, paths: &[PathBuf]) {
+        self.probe_spinner_angle = (self.probe_spinner_angle + 0.15) % std::f32::consts::TAU;
+        let angle = self.probe_spinner_angle;
+        let text = &self.text;
+        egui::Area::new(egui::Id::new("drop_probe_tooltip"))
+            .order(egui::Order::Tooltip)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for (i, path) in paths.iter().enumerate() {
+                        if i > 0 {
+                            ui.separator();
+                        }
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        ui.label(egui::RichText::new(name).strong());
+                        match self.drop_probes.get(&path.display().to_string()) {
+                            None => {
+                                ui.horizontal(|ui| {
+                                    draw_arc_spinner(ui, angle, ui.visuals().text_color());
+                                    ui.label(&text.drop_probing);
+                                });
+                            }
+                            Some(Err(err)) => {
+                                ui.colored_label(egui::Color32::from_rgb(230, 110, 110), err);
+                            }
+                            Some(Ok(probe)) => {
+                                let or_dash = |codec: &str| if codec.is_empty() { "-".to_owned() } else { codec.to_owned() };
+                                egui::Grid::new(("drop_probe_grid", i)).num_columns(2).show(ui, |ui| {
+                                    ui.label(&text.drop_duration);
+                                    ui.label(format_eta(probe.duration as f64));
+                                    ui.end_row();
+                                    ui.label(&text.drop_video);
+                                    if probe.width > 0 && probe.height > 0 {
+                                        ui.label(format!("{} {}x{}", or_dash(&probe.info.video_codec), probe.width, probe.height));
+                                    } else {
+                                        ui.label("-");
+                                    }
+                                    ui.end_row();
+                                    ui.label(&text.drop_audio);
+                                    ui.label(or_dash(&probe.info.audio_codec));
+                                    ui.end_row();
+                                    ui.label(&text.drop_file_size);
+                                    ui.label(format!("{:.1} MB", probe.size_bytes as f64 / 1_048_576.0));
+                                    ui.end_row();
+                                });
+                            }
+                        }
+                    }
+                });
+            });
+        // Spinner i odbior wynikow wymagaja odswiezania, bo podczas przeciagania nie ma zdarzen myszy
+        let delay = if self.drop_probe_threads.is_empty() { 100 } else { 16 };
+        ctx.request_repaint_after(std::time::Duration::from_millis(delay));
+    }
+
+    /// Import wszystkich plikow wideo z katalogu (kolejnosc wg nazwy)
+    fn import_folder_dialog(&mut self) {
+        if self.import_folder_thread.is_some() {
+            return;
+        }
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else { return };
+        self.set_status(StatusKind::Working, self.text.status_importing_folder.clone());
+        // ffprobe kazdego pliku trwa - skanowanie w tle, wynik odbiera poll_import_folder
+        self.import_folder_thread = Some(thread::spawn(move || {
+            let result = import_folder(&dir);
+            (dir, result)
+        }));
+    }
+
+    /// Dodaje do biblioteki media z zakonczonego importu katalogu i proponuje ulozenie na osi
+    fn poll_import_folder(&mut self, ctx: &egui::Context) {
+        let Some(handle) = &self.import_folder_thread else { return };
+        if !handle.is_finished() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        let Some(handle) = self.import_folder_thread.take() else { return };
+        let Ok((dir, result)) = handle.join() else {
+            self.set_status(StatusKind::Error, "Blad: watek importu katalogu przerwany.");
+            return;
+        };
+        match result {
+            Ok(assets) if assets.is_empty() => self.set_status(StatusKind::Warning, "Brak plikow wideo w katalogu."),
+            Ok(assets) => {
+                let mut imported = Vec::new();
+                for mut asset in assets {
+                    let idx = self.media_library.len();
+                    asset.id = idx;
+                    let dur = asset.duration;
+                    self.media_library.push(asset);
+                    self.prepare_library_asset(ctx, idx, dur);
+                    imported.push(idx);
+                }
+                self.set_status(StatusKind::Success, format!("Zaimportowano {} plikow z {}", imported.len(), dir.display()));
+                self.pending_auto_arrange = Some(imported);
+            }
+            Err(err) => self.set_status(StatusKind::Error, format!("Blad importu katalogu: {err:#}")),
+        }
+    }
+
+    /// Uklada assety jeden za drugim na V1, zaczynajac od konca obecnego montazu
+    fn auto_arrange_assets(&mut self, assets: &[usize]) {
+        self.push_undo();
+        let mut t = self.clips.iter().filter(|c| c.track_index == 0).map(|c| c.timeline_end()).fold(0.0, f32::max);
+        for &idx in assets {
+            let Some(asset) = self.media_library.get(idx) else { continue };
+            self.clips.push(Clip::new(Some(idx), t, t + asset.duration));
+            t += asset.duration;
+        }
+        self.duration = self.duration.max(t);
+        if self.video_width == 0
+            && let Some(first) = assets.first().and_then(|&idx| self.media_library.get(idx))
+            && let Ok((_, w, h, fps, _)) = get_video_info_ffprobe(&first.path)
+        {
+            self.video_width = w;
+            self.video_height = h;
+            if fps > 0.0 {
+                self.video_fps = fps;
+                self.drop_frame = is_drop_frame_rate(self.video_fps);
+            }
+        }
+        self.selected_clip = None;
+    }
+
+    /// Okno z lista brakujacych plikow i przyciskami "Locate..."
+    fn show_missing_media_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut relocate: Option<(String, String)> = None;
+        egui::Window::new(&self.text.missing_media_title)
+            .collapsible(false)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.screen_rect().center())
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(&self.text.missing_media_info);
+                ui.add_space(4.0);
+                for path in &self.missing_media {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(path).color(egui::Color32::from_rgb(230, 120, 100)));
+                        if ui.button(&self.text.locate_button).clicked() {
+                            let file_name = Path::new(path).file_name().unwrap_or_default().to_string_lossy().to_string();
+                            if let Some(new_path) = rfd::FileDialog::new().set_file_name(file_name).pick_file() {
+                                relocate = Some((path.clone(), new_path.display().to_string()));
+                            }
+                        }
+                    });
+                }
+            });
+
+        if let Some((old_path, new_path)) = relocate {
+            self.apply_media_relocation(ctx, &old_path, &new_path);
+        }
+        if !open {
+            self.missing_media.clear();
+        }
+    }
+
+    /// Podmienia sciezke zrodla we wszystkich miejscach (glowne wejscie, biblioteka) i odswieza podglady
+    fn apply_media_relocation(&mut self, ctx: &egui::Context, old_path: &str, new_path: &str) {
+        let mut data = self.project_data();
+        let input_changed = data.input_path == old_path;
+        relocate_media(&mut data, &mut self.media_library, old_path, new_path);
+        self.input_path = data.input_path;
+        self.missing_media.retain(|p| p != old_path);
+        self.remap_not_found.remove(old_path);
+        if input_changed && Path::new(&self.input_path).exists() {
+            self.prepare_media_assets(ctx);
+        }
+        self.rebuild_library_thumbs(ctx);
+        self.log.push(LogEntry::info(format!("{old_path} -> {new_path}")));
+    }
+
+    /// Wszystkie sciezki zrodlowe projektu (glowne wejscie + biblioteka), bez powtorzen
+    fn source_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = Vec::new();
+        let all = std::iter::once(&self.input_path).chain(self.media_library.iter().map(|a| &a.path));
+        for path in all {
+            if !path.is_empty() && !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
+
+    /// Szuka w tle plikow o tych samych nazwach w katalogu projektu i katalogach wybranych
+    /// przez uzytkownika - jedno przejscie drzewa dla wszystkich sciezek
+    fn start_media_search(&mut self, old_paths: Vec<String>) {
+        if self.media_search_thread.is_some() || old_paths.is_empty() {
+            return;
+        }
+        let roots: Vec<PathBuf> = self
+            .project_path
+            .as_ref()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .into_iter()
+            .chain(self.remap_search_roots.iter().cloned())
+            .collect();
+        self.media_search_thread = Some(thread::spawn(move || {
+            let names: HashSet<String> = old_paths
+                .iter()
+                .filter_map(|p| Path::new(p).file_name().map(|n| n.to_string_lossy().into_owned()))
+                .collect();
+            let found = find_files_recursive(&roots, &names);
+            (old_paths, found)
+        }));
+    }
+
+    /// Odbiera wynik start_media_search i przekierowuje znalezione media
+    fn poll_media_search(&mut self, ctx: &egui::Context) {
+        let Some(handle) = &self.media_search_thread else { return };
+        if !handle.is_finished() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        let Some(handle) = self.media_search_thread.take() else { return };
+        let Ok((old_paths, found)) = handle.join() else {
+            self.log.push(LogEntry::error("Blad: watek szukania mediow przerwany."));
+            return;
+        };
+        for old_path in old_paths {
+            let name = Path::new(&old_path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            match found.get(&name) {
+                Some(new_path) if new_path.display().to_string() != old_path => {
+                    self.apply_media_relocation(ctx, &old_path, &new_path.display().to_string());
+                }
+                Some(_) => {}
+                None => {
+                    self.remap_not_found.insert(old_path);
+                }
+            }
+        }
+    }
+
+    /// Okno "Remap media": lista wszystkich zrodel z reczna lub automatyczna zmiana sciezki
+    fn show_remap_media_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut relocate: Vec<(String, String)> = Vec::new();
+        let mut search: Vec<String> = Vec::new();
+        let mut add_root: Option<PathBuf> = None;
+        let searching = self.media_search_thread.is_some();
+        let paths = self.source_paths();
+        egui::Window::new(&self.text.remap_media_title)
+            .collapsible(false)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.screen_rect().center())
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if paths.is_empty() {
+                    ui.label(egui::RichText::new(&self.text.remap_media_empty).weak());
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("remap_media_grid").num_columns(3).striped(true).show(ui, |ui| {
+                        for path in &paths {
+                            let exists = Path::new(path).exists();
+                            let color = if exists { ui.visuals().text_color() } else { egui::Color32::from_rgb(230, 120, 100) };
+                            ui.label(egui::RichText::new(path).color(color));
+                            if self.remap_not_found.contains(path) {
+                                ui.label(egui::RichText::new(&self.text.remap_not_found).small().weak());
+                            } else {
+                                ui.label("");
+                            }
+                            ui.horizontal(|ui| {
+                                if ui.button(&self.text.locate_button).clicked() {
+                                    let file_name = Path::new(path).file_name().unwrap_or_default().to_string_lossy().to_string();
+                                    if let Some(new_path) = rfd::FileDialog::new().set_file_name(file_name).pick_file() {
+                                        relocate.push((path.clone(), new_path.display().to_string()));
+                                    }
+                                }
+                                if ui.add_enabled(!searching, egui::Button::new(&self.text.auto_search)).clicked() {
+                                    search.push(path.clone());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.separator();
+                for root in &self.remap_search_roots {
+                    ui.label(egui::RichText::new(root.display().to_string()).small().weak());
+                }
+                ui.horizontal(|ui| {
+                    if ui.button(&self.text.add_search_folder).clicked()
+                        && let Some(dir) = rfd::FileDialog::new().pick_folder()
+                    {
+                        add_root = Some(dir);
+                    }
+                    if ui.add_enabled(!searching, egui::Button::new(&self.text.auto_search_missing)).clicked() {
+                        search.extend(paths.iter().filter(|p| !Path::new(p).exists()).cloned());
+                    }
+                    if searching {
+                        ui.spinner();
+                        ui.label(&self.text.searching_media);
+                    }
+                });
+            });
+
+        if let Some(dir) = add_root
+            && !self.remap_search_roots.contains(&dir)
+        {
+            self.remap_search_roots.push(dir);
+        }
+        self.start_media_search(search);
+        for (old_path, new_path) in relocate {
+            if old_path != new_path {
+                self.apply_media_relocation(ctx, &old_path, &new_path);
+            }
+        }
+        if !open {
+            self.show_remap_media = false;
+            self.remap_not_found.clear();
+        }
+    }
+
+    fn build_playback_filters(&self, start_time: f32) -> (Option<String>, Option<String>) {
+        let mut vf_list = Vec::new();
+        let mut af_list = Vec::new();
+        
+        for clip in &self.clips {
+             // Fade In
+             if clip.fade_in > 0.0 {
+                 let rel_start = clip.start - start_time;
+                 // Jesli playback startuje po poczatku fade'a, nie mozemy zaaplikowac filtra z ujemnym czasem.
+                 // Pomijamy efekt w tym przypadku (bedzie hard cut), co zapobiega crashowi ffmpeg.
+                 if rel_start >= 0.0 {
+                     vf_list.push(format!("fade=t=in:st={:.3}:d={:.3}", rel_start, clip.fade_in));
+                     af_list.push(format!("afade=t=in:st={:.3}:d={:.3}", rel_start, clip.fade_in));
+                 }
+             }
+             
+             // Fade Out
+             if clip.fade_out > 0.0 {
+                 let fade_out_start = clip.end - clip.fade_out;
+                 let rel_out = fade_out_start - start_time;
+                 if rel_out >= 0.0 {
+                     vf_list.push(format!("fade=t=out:st={:.3}:d={:.3}", rel_out, clip.fade_out));
+                     af_list.push(format!("afade=t=out:st={:.3}:d={:.3}", rel_out, clip.fade_out));
+                 }
+             }
+        }
+        
+        let vf = if vf_list.is_empty() { None } else { Some(vf_list.join(",")) };
+        let af = if af_list.is_empty() { None } else { Some(af_list.join(",")) };
+        (vf, af)
+    }
+
+    /// Uruchamia ffprobe glownego wejscia w tle; wynik odbiera poll_probe_result
+    fn prepare_media_assets(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        let path = self.input_path.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = get_video_info_ffprobe(&path).map(|info| (info, get_media_info_ffprobe(&path)));
+            let _ = tx.send(result);
+            ctx.request_repaint();
+        });
+        // Nowe zapytanie zastepuje poprzednie (stary wynik trafia w pustke)
+        self.pending_probe = Some(rx);
+        self.probe_spinner_angle = 0.0;
+    }
+
+    fn poll_probe_result(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.pending_probe else { return };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => {
+                self.probe_spinner_angle = (self.probe_spinner_angle + 0.15) % std::f32::consts::TAU;
+                ctx.request_repaint_after(std::time::Duration::from_millis(16));
+                return;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => Err(anyhow!("watek ffprobe przerwany")),
+        };
+        self.pending_probe = None;
+        match result {
+            Ok(((duration, width, height, fps, bitrate_kbps), media_info)) => {
+                self.duration = duration.max(0.0);
+                self.source_bitrate_kbps = bitrate_kbps;
+                self.media_info = match media_info {
+                    Ok(info) => Some(info),
+                    Err(err) => {
+                        self.log.push(LogEntry::warning(format!("Brak informacji o mediach: {err:#}")));
+                        None
+                    }
+                };
+                self.video_width = width;
+                self.video_height = height;
+                self.video_fps = fps;
+                self.drop_frame = is_drop_frame_rate(self.video_fps);
+                self.playhead = 0.0;
+                self.last_preview_playhead = -1.0;
+                self.stop_playback();
+                self.mark_in = None;
+                self.mark_out = None;
+                if self.clips.is_empty() && self.duration > 0.0 {
+                    self.clips.push(Clip::new(None, 0.0, self.duration));
+                    self.selected_clip = Some(0);
+                } else {
+                    self.selected_clip = None;
+                }
+                self.timeline_zoom = 0.0;
+                self.timeline_offset = 0.0;
+                if let Err(err) = self.ensure_temp_dir() {
+                    self.log.push(LogEntry::error(format!("Blad temp: {err:#}")));
+                    return;
+                }
+                if let Err(err) = self.build_waveform(ctx) {
+                    self.log.push(LogEntry::error(format!("Blad waveform: {err:#}")));
+                }
+                self.reset_thumbnails();
+                self.maybe_update_preview(ctx);
+            }
+            Err(err) => {
+                self.log.push(LogEntry::error(format!("Blad ffprobe: {err:#}")));
+            }
+        }
+    }
+
+    fn media_info_ui(&self, ui: &mut egui::Ui) {
+        let Some(info) = &self.media_info else { return };
+        let or_dash = |s: &str| if s.is_empty() { "-".to_string() } else { s.to_string() };
+        let kbps = |v: u32| if v > 0 { format!("{v} kb/s") } else { "-".to_string() };
+        egui::Grid::new("media_info_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Container");
+            ui.label(or_dash(&info.container_format));
+            ui.end_row();
+            ui.label("Video");
+            ui.label(format!(
+                "{} {}x{} @ {:.3} fps",
+                or_dash(&info.video_codec),
+                self.video_width,
+                self.video_height,
+                self.video_fps
+            ));
+            ui.end_row();
+            ui.label("Video bitrate");
+            ui.label(kbps(info.video_bitrate_kbps));
+            ui.end_row();
+            ui.label("Audio");
+            if info.audio_codec.is_empty() {
+                ui.label("-");
+            } else {
+                ui.label(format!("{} {} Hz, {} ch", info.audio_codec, info.audio_sample_rate, info.audio_channels));
+            }
+            ui.end_row();
+            ui.label("Audio bitrate");
+            ui.label(kbps(info.audio_bitrate_kbps));
+            ui.end_row();
+        });
+    }
+
+    /// Uchwyt pozycji zaznaczonej nakladki (track_index > 0) nad podgladem.
+    /// Przeciagniecie ustawia punkt pozycji (px kadru) w czasie playheada.
+    fn overlay_position_handle(&mut self, ui: &mut egui::Ui, draw_rect: egui::Rect) {
+        let Some(idx) = self.selected_clip else { return };
+        let Some(clip) = self.clips.get(idx) else { return };
+        if clip.track_index == 0 || self.playhead < clip.start || self.playhead >= clip.timeline_end() {
+            return;
+        }
+        let frame_width = if self.video_width > 0 { self.video_width as f32 } else { 1920.0 };
+        let scale = draw_rect.width() / frame_width;
+        let local_t = (self.snap(self.playhead) - clip.start).max(0.0);
+        let (x, y) = clip.position_at(local_t);
+        let handle = egui::Rect::from_min_size(draw_rect.min + egui::vec2(x, y) * scale, egui::vec2(24.0, 24.0));
+        let response = ui.interact(handle, ui.id().with("overlay_position_handle"), egui::Sense::drag());
+        if response.drag_started() {
+            self.push_undo();
+        }
+        if response.dragged() {
+            let moved = handle.min + response.drag_delta() - draw_rect.min;
+            let (nx, ny) = ((moved.x / scale).round(), (moved.y / scale).round());
+            // Punkt w tej samej klatce jest nadpisywany
+            let tolerance = 0.5 / self.video_fps.max(1.0);
+            let keys = &mut self.clips[idx].position_keyframes;
+            match keys.iter_mut().find(|k| (k.0 - local_t).abs() < tolerance) {
+                Some(key) => (key.1, key.2) = (nx, ny),
+                None => {
+                    keys.push((local_t, nx, ny));
+                    keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+                }
+            }
+        }
+        if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Move);
+        }
+        let painter = ui.painter_at(draw_rect);
+        let alpha = if response.hovered() || response.dragged() { 140 } else { 80 };
+        painter.rect_filled(handle, 3.0, egui::Color32::from_rgba_unmultiplied(80, 160, 255, alpha));
+        painter.rect_stroke(handle, 3.0, egui::Stroke::new(1.0, egui::Color32::WHITE));
+        painter.text(handle.center(), egui::Align2::CENTER_CENTER, "✥", egui::FontId::proportional(14.0), egui::Color32::WHITE);
+        // Kolejne punkty pozycji jako sciezka ruchu
+        let path: Vec<egui::Pos2> = self.clips[idx]
+            .position_keyframes
+            .iter()
+            .map(|&(_, kx, ky)| draw_rect.min + egui::vec2(kx, ky) * scale)
+            .collect();
+        if path.len() > 1 {
+            painter.add(egui::Shape::dashed_line(&path, egui::Stroke::new(1.0, egui::Color32::from_white_alpha(120)), 4.0, 3.0));
+        }
+        for point in path {
+            painter.circle_filled(point, 3.0, egui::Color32::from_rgb(80, 160, 255));
+        }
+    }
+
+    /// Statystyki liczone na biezaco z self.clips (bez wywolan zewnetrznych)
+    fn project_stats_ui(&self, ui: &mut egui::Ui) {
+        let stats = project_stats(&self.clips, self.duration);
+        let render_secs = stats.cut_duration * self.render_settings.complexity_factor();
+        let text = &self.text;
+        egui::Grid::new("project_stats_grid").num_columns(2).show(ui, |ui| {
+            ui.label(&text.stats_clips);
+            ui.label(stats.clip_count.to_string());
+            ui.end_row();
+            ui.label(&text.stats_cut_duration);
+            ui.label(format!("{:.2} s", stats.cut_duration));
+            ui.end_row();
+            ui.label(&text.stats_gaps);
+            ui.label(format!("{} ({:.2} s)", stats.gap_count, stats.gap_duration));
+            ui.end_row();
+            ui.label(&text.stats_faded);
+            ui.label(stats.faded_clips.to_string());
+            ui.end_row();
+            ui.label(&text.stats_average);
+            ui.label(format!("{:.2} s", stats.average_clip));
+            ui.end_row();
+            ui.label(&text.stats_render_estimate);
+            ui.label(format!("~{}", format_eta(render_secs as f64)));
+            ui.end_row();
+            ui.label(&text.stats_source_used);
+            ui.label(format!("{:.1}%", stats.source_used_percent));
+            ui.end_row();
+        });
+    }
+
+    fn silence_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.silence_threshold_db, -60.0..=-20.0).text(&self.text.silence_threshold));
+        ui.add(egui::Slider::new(&mut self.silence_min_duration, 0.1..=2.0).text(&self.text.silence_min_duration));
+        let can_detect = self.silence_thread.is_none() && !self.input_path.trim().is_empty();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(can_detect, egui::Button::new(&self.text.detect_silence)).clicked() {
+                let (input, db, dur) = (self.input_path.clone(), self.silence_threshold_db, self.silence_min_duration);
+                self.silence_thread = Some(thread::spawn(move || detect_silence(&input, db, dur)));
+                self.set_status(StatusKind::Working, self.text.status_detecting_silence.clone());
+            }
+            if self.silence_thread.is_some() {
+                ui.spinner();
+            }
+        });
+
+        let fps = self.video_fps;
+        let mut split_at = None;
+        let mut remove = None;
+        egui::ScrollArea::vertical().id_source("silence_list").max_height(160.0).show(ui, |ui| {
+            for (i, &(start, end)) in self.silence_ranges.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} - {}", secs_to_timecode(start, fps, self.drop_frame), secs_to_timecode(end, fps, self.drop_frame)));
+                    if ui.small_button(&self.text.split_here).clicked() {
+                        split_at = Some(start);
+                    }
+                    if ui.small_button(&self.text.remove_range).clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+        });
+
+        if let Some(src) = split_at {
+            match source_to_timeline(&self.clips, src) {
+                Some((idx, t)) => {
+                    self.push_undo();
+                    if let Some(split) = split_clip_at(&mut self.clips, idx, t) {
+                        self.selected_clip = Some(split);
+                        self.playhead = t;
+                    }
+                }
+                None => self.log.push(LogEntry::warning("Brak klipu w tym miejscu.")),
+            }
+        }
+        if let Some(i) = remove {
+            let (start, end) = self.silence_ranges[i];
+            let before = self.clips.clone();
+            let removed = remove_source_range(&mut self.clips, start, end, self.settings.ripple_delete);
+            if removed > 0 {
+                self.push_undo_action(UndoAction::Snapshot(before));
+                self.silence_ranges.remove(i);
+                self.selected_clip = None;
+                self.selected_clips.clear();
+            } else {
+                self.log.push(LogEntry::warning("Brak klipu w tym miejscu."));
+            }
+        }
+    }
+
+    /// Wykrywanie tempa i uderzen dla zaznaczonego klipu oraz ciecie na uderzeniach
+    fn beats_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::DragValue::new(&mut self.bpm).clamp_range(0.0..=300.0).speed(0.5).prefix(format!("{}: ", self.text.bpm_label)));
+        let target = self.selected_clip.or((!self.clips.is_empty()).then_some(0));
+        ui.horizontal(|ui| {
+            let can_detect = self.beat_thread.is_none() && target.is_some();
+            if ui.add_enabled(can_detect, egui::Button::new(&self.text.detect_bpm)).clicked()
+                && let Some(clip) = target.and_then(|idx| self.clips.get(idx))
+            {
+                let input = clip
+                    .asset_id
+                    .and_then(|id| self.media_library.get(id))
+                    .map(|a| a.path.clone())
+                    .unwrap_or_else(|| self.input_path.clone());
+                let clip = clip.clone();
+                let hint = (self.bpm > 0.0).then_some(self.bpm);
+                self.beat_thread = Some(thread::spawn(move || {
+                    let samples = decode_mono_pcm(&input, clip.source_in, clip.end - clip.start, BEAT_SAMPLE_RATE)?;
+                    let bpm = hint.or_else(|| estimate_bpm(&samples, BEAT_SAMPLE_RATE));
+                    let beats = detect_beats(&samples, BEAT_SAMPLE_RATE, bpm);
+                    // Uderzenia sa w czasie zrodla - na os przez predkosc, rampe i odtwarzanie wstecz
+                    let mut beats: Vec<f32> = beats.into_iter().map(|b| clip.start + clip.output_time_at_source(b)).collect();
+                    beats.sort_by(|a, b| a.total_cmp(b));
+                    Ok((bpm, beats))
+                }));
+            }
+            if self.beat_thread.is_some() {
+                ui.spinner();
+            }
+        });
+        ui.label(format!("{}: {}", self.text.beats_label, self.beat_markers.len()));
+
+        ui.horizontal(|ui| {
+            let can_split = self.selected_clip.is_some() && !self.beat_markers.is_empty();
+            if ui.add_enabled(can_split, egui::Button::new(&self.text.split_at_beats)).clicked()
+                && let Some(idx) = self.selected_clip
+            {
+                self.split_clip_at_beats(idx);
+            }
+            if ui.add_enabled(!self.beat_markers.is_empty(), egui::Button::new(&self.text.clear_beats)).clicked() {
+                self.beat_markers.clear();
+            }
+        });
+    }
+
+    /// Tnie klip na kazdym uderzeniu z `beat_markers` wewnatrz jego zakresu (jeden krok undo)
+    fn split_clip_at_beats(&mut self, idx: usize) {
+        let Some(clip) = self.clips.get(idx) else {
+            return;
+        };
+        let (start, end) = (clip.start, clip.timeline_end());
+        let mut cuts: Vec<f32> = self.beat_markers.iter().copied().filter(|&t| t > start && t < end).collect();
+        if cuts.is_empty() {
+            self.log.push(LogEntry::warning("Brak uderzen w zakresie klipu."));
+            return;
+        }
+        self.push_undo();
+        // Od konca - lewa czesc zachowuje indeks `idx`
+        cuts.sort_by(|a, b| b.total_cmp(a));
+        let count = cuts.iter().filter(|&&t| split_clip_at(&mut self.clips, idx, t).is_some()).count();
+        self.selected_clip = Some(idx);
+        self.selected_clips.clear();
+        self.log.push(LogEntry::info(format!("Podzielono klip na uderzeniach: {count} ciec")));
+    }
+
+    /// Przycina cisze z konca i poczatku podanych klipow (prog z panelu wykrywania ciszy)
+    fn start_silence_trim(&mut self, indices: Vec<usize>) {
+        let jobs: Vec<(usize, String, (f32, f32))> = indices
+            .into_iter()
+            .filter_map(|idx| {
+                let clip = self.clips.get(idx).filter(|c| c.audio_enabled)?;
+                let path = match (&clip.audio_override, clip.asset_id.and_then(|id| self.media_library.get(id))) {
+                    (Some(path), _) => path.clone(),
+                    (None, Some(asset)) if asset.kind == MediaType::Image => return None,
+                    (None, Some(asset)) => asset.path.clone(),
+                    (None, None) => self.input_path.clone(),
+                };
+                Some((idx, path, (clip.source_in, clip.source_out())))
+            })
+            .collect();
+        if jobs.is_empty() {
+            self.set_status(StatusKind::Warning, self.text.trim_silence_none.clone());
+            return;
+        }
+        let threshold = self.silence_threshold_db;
+        self.trim_silence_thread = Some(thread::spawn(move || {
+            jobs.into_iter()
+                .map(|(idx, path, range)| (idx, range, auto_trim_silence(&path, range.0, range.1, threshold)))
+                .collect()
+        }));
+        self.set_status(StatusKind::Working, self.text.status_trimming_silence.clone());
+    }
+
+    /// Ustawia nowe punkty In/Out (start klipu zostaje); pomija klipy zmienione w trakcie analizy
+    fn apply_silence_trim(&mut self, results: Vec<SilenceTrim>) {
+        let mut trims = Vec::new();
+        for (idx, before, result) in results {
+            match result {
+                Ok(range) => {
+                    let unchanged = self.clips.get(idx).is_some_and(|c| {
+                        (c.source_in - before.0).abs() < 0.001 && (c.source_out() - before.1).abs() < 0.001
+                    });
+                    if unchanged && range != before {
+                        trims.push((idx, range));
+                    }
+                }
+                Err(err) => self.log.push(LogEntry::warning(format!("Klip #{}: {err:#}", idx + 1))),
+            }
+        }
+        if trims.is_empty() {
+            self.set_status(StatusKind::Info, self.text.trim_silence_none.clone());
+            return;
+        }
+        self.push_undo();
+        for &(idx, (source_in, source_out)) in &trims {
+            let clip = &mut self.clips[idx];
+            clip.source_in = source_in;
+            clip.end = clip.start + (source_out - source_in);
+        }
+        self.set_status(StatusKind::Success, format!("Przycieto cisze w klipach: {}", trims.len()));
+    }
+
+    /// Mierzy korelacje L/R (aphasemeter) pierwszych sekund kazdego klipu z audio
+    fn start_phase_analysis(&mut self) {
+        let jobs: Vec<(usize, String, f32, f32)> = self
+            .clips
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.audio_enabled)
+            .map(|(idx, c)| {
+                let path = c
+                    .asset_id
+                    .and_then(|id| self.media_library.get(id))
+                    .map(|a| a.path.clone())
+                    .unwrap_or_else(|| self.input_path.clone());
+                (idx, path, c.source_in, (c.end - c.start).min(10.0))
+            })
+            .collect();
+        if jobs.is_empty() {
+            self.log.push(LogEntry::info("Brak klipow z audio do analizy."));
+            return;
+        }
+        self.log.push(LogEntry::info(format!("Analiza fazy: {} klipow...", jobs.len())));
+        self.phase_thread = Some(thread::spawn(move || {
+            jobs.into_iter()
+                .filter_map(|(idx, path, start, dur)| measure_phase_correlation(&path, start, dur).ok().map(|c| (idx, c)))
+                .collect()
+        }));
+    }
+
+    fn report_phase_results(&mut self, results: &[(usize, f32)]) {
+        const NEGATIVE: f32 = -0.5;
+        let mut issues = 0;
+        for &(idx, corr) in results {
+            if corr < NEGATIVE {
+                issues += 1;
+                self.log.push(LogEntry::warning(format!(
+                    "Klip #{}: korelacja fazy {corr:.2} - kanaly w przeciwfazie?",
+                    idx + 1
+                )));
+            }
+        }
+        // Sasiednie klipy o przeciwnej polaryzacji (np. ten sam mikrofon raz odwrocony)
+        for pair in results.windows(2) {
+            let ((a, ca), (b, cb)) = (pair[0], pair[1]);
+            if b == a + 1 && ca * cb < 0.0 && ca.min(cb) < NEGATIVE && ca.max(cb) > -NEGATIVE {
+                issues += 1;
+                self.log.push(LogEntry::warning(format!(
+                    "Klipy #{} i #{}: przeciwna polaryzacja ({ca:.2} / {cb:.2})",
+                    a + 1,
+                    b + 1
+                )));
+            }
+        }
+        if issues == 0 {
+            self.log.push(LogEntry::info(format!("Analiza fazy: brak problemow ({} klipow).", results.len())));
+        }
+    }
+
+    /// Wypelnia luki miedzy klipami czarnym obrazem i cisza
+    fn fill_gaps_with_black(&mut self, ctx: &egui::Context) {
+        // Wypelnienia zostaja w projekcie - nie moga lezec w temp sprzatanym przy wyjsciu.
+        // Osobny podkatalog na kazde wypelnianie, zeby nie nadpisac plikow uzywanych przez wczesniejsze klipy.
+        let dir = generated_media_dir(self.project_path.as_deref())
+            .join(chrono::Local::now().format("fill_%Y%m%d_%H%M%S").to_string());
+        let (width, height) = if self.video_width > 0 && self.video_height > 0 {
+            (self.video_width, self.video_height)
+        } else {
+            (1920, 1080)
+        };
+        let before = self.clips.clone();
+        match fill_gaps(&mut self.clips, &mut self.media_library, width, height, self.video_fps, &dir) {
+            Ok(0) => self.log.push(LogEntry::info("Brak luk do wypelnienia.")),
+            Ok(n) => {
+                self.push_undo_action(UndoAction::Snapshot(before));
+                self.selected_clip = None;
+                self.selected_clips.clear();
+                self.rebuild_library_thumbs(ctx);
+                self.log.push(LogEntry::info(format!("Wypelniono luki: {n}")));
+            }
+            Err(err) => self.log.push(LogEntry::error(format!("Blad wypelniania luk: {err:#}"))),
+        }
+    }
+
+    fn ensure_temp_dir(&mut self) -> Result<()> {
+        if self.temp_dir.is_none() {
+            self.temp_dir = Some(create_temp_dir()?);
+        }
+        Ok(())
+    }
+
+    fn maybe_update_preview(&mut self, ctx: &egui::Context) {
+        if (self.input_path.trim().is_empty() && self.clips.is_empty()) || self.duration <= 0.0 {
+            return;
+        }
+        if self.is_playing {
+            return;
+        }
+        let now = Instant::now();
+        if let Some(last) = self.last_preview_time {
+            if now.duration_since(last).as_millis() < 150 {
+                return;
+            }
+        }
+        if (self.playhead - self.last_preview_playhead).abs() < 0.05 {
+            return;
+        }
+        if let Err(err) = self.request_preview(ctx) {
+            self.log.push(LogEntry::error(format!("Blad podgladu: {err:#}")));
+        } else {
+            self.last_preview_time = Some(now);
+            self.last_preview_playhead = self.playhead;
+        }
+    }
+
+    fn maybe_update_preview_drag(&mut self, _ctx: &egui::Context) {
+        if (self.input_path.trim().is_empty() && self.clips.is_empty()) || self.duration <= 0.0 {
+            return;
+        }
+
+        // Jesli watek pracuje, nie robimy nic (drop frame) - to zapewnia plynnosc UI
+        if self.preview_busy.load(Ordering::Relaxed) {
+             return;
+        }
+
+        // Jesli pozycja zmienila sie nieznacznie, tez ignorujemy
+        if (self.playhead - self.last_drag_preview_playhead).abs() < 0.1 {
+            return;
+        }
+        
+        self.last_drag_preview_playhead = self.playhead;
+        
+        let busy = self.preview_busy.clone();
+        let tx = self.preview_tx.clone();
+        // Resolve source!
+        // We need access to clips... but we are moving 'self' fields into closure.
+        // Complex. Thread needs the path.
+        // We can resolve BEFORE spawning.
+        let (input, time) = self.resolve_clip_source(self.playhead);
+        
+        // Ustawiamy flage busy
+        busy.store(true, Ordering::Relaxed);
+        
+        // Spawn watku
+        thread::spawn(move || {
+            // Low-Res Proxy: 320px szerokosci dla szybkosci
+            if let Ok(data) = generate_frame_memory(&input, time, 320, 0) {
+                let _ = tx.send((time, data));
+            }
+            // Zwalniamy flage
+            busy.store(false, Ordering::Relaxed);
+        });
+    }
+
+    /// Odtwarza 0.2 s audio wokol playheada (dekodowanie w tle, nowy fragment zastepuje stary)
+    fn maybe_scrub_audio(&mut self) {
+        if self.scrub_busy.load(Ordering::Relaxed) {
+            return;
+        }
+        if (self.playhead - self.last_scrub_playhead).abs() < 0.05 {
+            return;
+        }
+        self.last_scrub_playhead = self.playhead;
+
+        // Dzwiek klipu pod playheadem, takze gdy obraz jest tu przyciety
+        let (input, time) = match self.audio_clip_at(self.playhead) {
+            Some(clip)
+                if !effective_audio_enabled(clip, &self.track_muted, &self.track_soloed)
+                    || !trim_contains(clip.audio_trim(), self.playhead - clip.start) =>
+            {
+                return;
+            }
+            Some(clip) => match self.clip_source_at(clip, self.playhead) {
+                Some((path, time)) => (self.cached_source(&path), time),
+                None => self.resolve_clip_source(self.playhead),
+            },
+            None => self.resolve_clip_source(self.playhead),
+        };
+        if input.is_empty() {
+            return;
+        }
+        if self.scrub_stream.is_none() {
+            if let Ok(mut q) = self.scrub_buffer.lock() {
+                q.clear();
+            }
+            match build_scrub_stream(&self.scrub_buffer, &self.settings.audio_device_name) {
+                Ok(stream) => self.scrub_stream = Some(stream),
+                Err(e) => {
+                    self.log.push(LogEntry::error(format!("Blad scrub audio: {e}")));
+                    // Bez urzadzenia nie probujemy ponownie w tym przeciaganiu
+                    self.last_scrub_playhead = f32::INFINITY;
+                    return;
+                }
+            }
+        }
+
+        let busy = Arc::clone(&self.scrub_busy);
+        let buffer = Arc::clone(&self.scrub_buffer);
+        busy.store(true, Ordering::Relaxed);
+        thread::spawn(move || {
+            if let Ok(samples) = scrub_audio(&input, time, 0.2)
+                && let Ok(mut q) = buffer.lock()
+            {
+                q.clear();
+                q.extend(samples);
+            }
+            busy.store(false, Ordering::Relaxed);
+        });
+    }
+
+    fn stop_audio_scrub(&mut self) {
+        if self.scrub_stream.take().is_some()
+            && let Ok(mut q) = self.scrub_buffer.lock()
+        {
+            q.clear();
+        }
+        self.last_scrub_playhead = -1.0;
+    }
+
+    fn build_waveform(&mut self, ctx: &egui::Context) -> Result<()> {
+        let wave_path = self.cache_dir()?.join("waveform.png");
+        generate_waveform(&self.input_path, &wave_path, self.settings.waveform_amplitude)?;
+        let texture = load_texture_from_path(ctx, &wave_path, "waveform")?;
+        self.waveform_texture = Some(texture);
+        self.waveform_zoom_level = 1.0;
+        self.pending_waveform = None;
+        self.enforce_cache_limit();
+        Ok(())
+    }
+
+    /// Przy duzym zoomie generuje w tle szerszy waveform (do 8192 px), zeby nie byl rozmyty.
+    /// `zoom_level` = timeline_zoom / min_zoom; ponownie dopiero po zmianie o ponad 50%.
+    fn maybe_regenerate_waveform(&mut self, ctx: &egui::Context, zoom_level: f32) {
+        if self.waveform_texture.is_none() || self.pending_waveform.is_some() || self.input_path.is_empty() {
+            return;
+        }
+        let width_for = |level: f32| {
+            ((level * WAVEFORM_BASE_WIDTH as f32) as u32).clamp(WAVEFORM_BASE_WIDTH, WAVEFORM_MAX_WIDTH)
+        };
+        let change = zoom_level / self.waveform_zoom_level;
+        if (1.0 / 1.5..=1.5).contains(&change) || width_for(zoom_level) == width_for(self.waveform_zoom_level) {
+            return;
+        }
+        let width = width_for(zoom_level);
+        let wave_path = match self.cache_dir() {
+            Ok(cache) => cache.join(format!("waveform_{width}.png")),
+            Err(_) => return,
+        };
+        let input = self.input_path.clone();
+        let amplitude = self.settings.waveform_amplitude;
+        let ctx = ctx.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let result = generate_waveform_sized(&input, &wave_path, width, amplitude).map(|_| wave_path);
+            let _ = tx.send((zoom_level, result));
+            ctx.request_repaint();
+        });
+        self.pending_waveform = Some(rx);
+    }
+
+    fn poll_waveform_result(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.pending_waveform else { return };
+        let (level, result) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.pending_waveform = None;
+                return;
+            }
+        };
+        self.pending_waveform = None;
+        // Poziom zapisujemy tez przy bledzie, zeby nie ponawiac w kazdej klatce
+        self.waveform_zoom_level = level;
+        match result.and_then(|path| load_texture_from_path(ctx, &path, "waveform")) {
+            Ok(texture) => {
+                self.waveform_texture = Some(texture);
+                self.enforce_cache_limit();
+            }
+            Err(err) => self.log.push(LogEntry::warning(format!("Blad waveformu: {err:#}"))),
+        }
+    }
+
+    /// Podkatalog temp na pliki odtwarzalne (waveformy); segmenty uzywane przez klipy zostaja poza nim
+    fn cache_dir(&mut self) -> Result<PathBuf> {
+        self.ensure_temp_dir()?;
+        let temp_dir = self
+            .temp_dir
+            .as_ref()
+            .ok_or_else(|| anyhow!("Brak katalogu temp"))?;
+        let cache = temp_dir.join("cache");
+        fs::create_dir_all(&cache)?;
+        Ok(cache)
+    }
+
+    /// Przycina cache (klatki w temp i lokalne kopie zrodel) do wspolnego limitu z ustawien,
+    /// najstarsze pliki pierwsze
+    fn enforce_cache_limit(&mut self) {
+        let Some(temp_dir) = &self.temp_dir else { return };
+        let max_bytes = self.settings.max_cache_size_mb * 1024 * 1024;
+        let sources = source_cache_dir();
+        match evict_cache_to_limit(&[&temp_dir.join("cache"), &sources], max_bytes) {
+            Ok(0) => {}
+            Ok(freed) => self.log.push(LogEntry::info(format!("Cache: usunieto {:.1} MB", freed as f64 / 1_048_576.0))),
+            Err(err) => self.log.push(LogEntry::warning(format!("Blad czyszczenia cache: {err:#}"))),
+        }
+    }
+
+    /// Filtry wygladu klipu pod danym czasem (podglad odzwierciedla efekty renderu)
+    fn preview_filter_at(&self, time: f32) -> Option<String> {
+        self.top_clip_at(time).and_then(clip_video_effects)
+    }
+
+    /// Widoczny klip pod danym czasem: najwyzsza sciezka wideo, przy remisie pierwszy na liscie
+    /// (klip z obrazem przycietym poza `time` jest pomijany)
+    fn top_clip_at(&self, time: f32) -> Option<&Clip> {
+        self.clips
+            .iter()
+            .filter(|c| {
+                c.video_enabled && time >= c.start && time < c.timeline_end() && trim_contains(c.video_trim(), time - c.start)
+            })
+            .rev()
+            .max_by_key(|c| c.track_index)
+    }
+
+    /// Klip z dzwiekiem pod danym czasem (bez uwzglednienia przyciecia dzwieku)
+    fn audio_clip_at(&self, time: f32) -> Option<&Clip> {
+        self.clips
+            .iter()
+            .filter(|c| c.audio_enabled && time >= c.start && time < c.timeline_end())
+            .rev()
+            .max_by_key(|c| c.track_index)
+    }
+
+    /// Sciezka do uzycia w ffmpeg: lokalna kopia zrodla, gdy wlaczona i gotowa
+    fn cached_source(&self, path: &str) -> String {
+        match self.source_cache.get(path) {
+            // Kopia mogla zostac usunieta przy przycinaniu cache - wtedy oryginal
+            Some(cached) if self.settings.use_cache_copy && cached.is_file() => cached.to_string_lossy().into_owned(),
+            _ => path.to_string(),
+        }
+    }
+
+    /// Kopiuje kolejne zrodla projektu do lokalnego cache (jedno na raz, w tle)
+    fn poll_source_cache(&mut self, ctx: &egui::Context) {
+        if let Some(handle) = &self.source_cache_thread {
+            if !handle.is_finished() {
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+                return;
+            }
+            if let Some(handle) = self.source_cache_thread.take() {
+                match handle.join() {
+                    Ok((path, Ok(cached))) => {
+                        self.source_cache.insert(path, cached);
+                        // Podglad przechodzi na kopie
+                        self.last_preview_playhead = -1.0;
+                        self.enforce_cache_limit();
+                    }
+                    Ok((path, Err(err))) => {
+                        self.log.push(LogEntry::warning(format!("Blad kopii w cache ({path}): {err:#}")));
+                        self.source_cache_failed.insert(path);
+                    }
+                    Err(_) => self.log.push(LogEntry::error("Blad: watek kopiowania do cache przerwany.")),
+                }
+            }
+        }
+        if !self.settings.use_cache_copy {
+            return;
+        }
+        let next = self.source_paths().into_iter().find(|p| {
+            !self.source_cache.contains_key(p) && !self.source_cache_failed.contains(p) && Path::new(p).is_file()
+        });
+        if let Some(path) = next {
+            if let Ok(mut p) = self.source_cache_progress.lock() {
+                *p = (0, 0);
+            }
+            let progress = Arc::clone(&self.source_cache_progress);
+            self.source_cache_thread = Some(thread::spawn(move || {
+                let result = cache_source_file(&path, &source_cache_dir(), Some(&progress));
+                (path, result)
+            }));
+        }
+    }
+
+    fn resolve_clip_source(&self, time: f32) -> (String, f32) {
+        let (path, time) = self.resolve_clip_source_original(time);
+        (self.cached_source(&path), time)
+    }
+
+    fn resolve_clip_source_original(&self, time: f32) -> (String, f32) {
+        if let Some(source) = self.top_clip_at(time).and_then(|clip| self.clip_source_at(clip, time)) {
+            return source;
+        }
+        // If no clip found, return input_path and time? Or empty?
+        // Default behavior: show input_path at time.
+        (self.input_path.clone(), time)
+    }
+
+    /// Zrodlo klipu i czas w zrodle dla czasu `time` na osi
+    fn clip_source_at(&self, clip: &Clip, time: f32) -> Option<(String, f32)> {
+        // Czas na osi -> czas zrodla przez predkosc, rampe i odtwarzanie wstecz
+        let played = clip.played_at_output(time - clip.start);
+        let local_time = if clip.reversed { clip.source_out() - played } else { clip.source_in + played };
+        // Fade in/out logic might be here but for source we just need path
+        // Find asset in library (by index for MVP, assuming valid)
+        if let Some(asset_id) = clip.asset_id
+            && let Some(asset) = self.media_library.get(asset_id)
+            && (asset.kind == MediaType::Video || asset.kind == MediaType::Image)
+        {
+            return Some((asset.path.clone(), local_time));
+        }
+        // Fallback to input_path if no asset_id (legacy clip)
+        if clip.asset_id.is_none() {
+             return Some((self.input_path.clone(), local_time));
+        }
+        None
+    }
+
+    /// Zabija trwajacy proces podgladu i uruchamia nowy; wynik odbiera poll_preview_result
+    fn request_preview(&mut self, ctx: &egui::Context) -> Result<()> {
+        let (path, local_time) = self.resolve_clip_source(self.playhead);
+        if path.is_empty() { return Ok(()); }
+
+        if let Some(mut old) = self.pending_preview.take() {
+            let _ = old.kill();
+            let _ = old.wait();
+        }
+        self.preview_generation += 1;
+        let generation = self.preview_generation;
+
+        let filter = self.preview_filter_at(self.playhead);
+        let width = self.preview_width();
+        let mut child = spawn_frame_process(&path, local_time, width, 0, filter.as_deref())?;
+        let (Some(mut stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            return Err(anyhow!("Brak potokow ffmpeg podgladu"));
+        };
+        self.pending_preview = Some(child);
+
+        let compare = self.comparison_mode;
+        let tx = self.preview_result_tx.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            // Po zabiciu procesu read_to_end konczy sie (EOF) - wynik zostanie odrzucony
+            let mut data = Vec::new();
+            let mut err_text = String::new();
+            let _ = stdout.read_to_end(&mut data);
+            let _ = stderr.read_to_string(&mut err_text);
+            let frame = if data.is_empty() {
+                Err(anyhow!("ffmpeg frame error: {}", err_text.trim()))
+            } else {
+                Ok(data)
+            };
+            // A = surowa klatka, B = z filtrami klipu
+            let raw = if compare && frame.is_ok() {
+                generate_frame_memory(&path, local_time, width, 0).ok()
+            } else {
+                None
+            };
+            let _ = tx.send(PreviewResult { generation, frame, raw });
+            ctx.request_repaint();
+        });
+        Ok(())
+    }
+
+    /// Odbiera gotowy podglad z watku i laduje teksture (tylko najnowsze zadanie)
+    fn poll_preview_result(&mut self, ctx: &egui::Context) {
+        while let Ok(result) = self.preview_result_rx.try_recv() {
+            if result.generation != self.preview_generation {
+                continue;
+            }
+            if let Some(mut child) = self.pending_preview.take() {
+                let _ = child.wait();
+            }
+            match result.frame.and_then(|data| decode_color_image(&data)) {
+                Ok(image) => {
+                    self.update_histogram(&image);
+                    let texture = ctx.load_texture("preview", image, egui::TextureOptions::LINEAR);
+                    if let Some(raw) = result.raw
+                        && let Ok(tex_a) = load_texture_from_memory(ctx, &raw, "preview_a")
+                    {
+                        self.comparison_a_texture = Some(tex_a);
+                        self.comparison_b_texture = Some(texture.clone());
+                    }
+                    self.preview_texture = Some(texture);
+                }
+                Err(err) => self.log.push(LogEntry::error(format!("Blad podgladu: {err:#}"))),
+            }
+        }
+    }
+
+
+
+    /// Lista dodatkowych wyjsc renderu (MultiOutput) ze statusem ostatniego przebiegu
+    fn extra_outputs_ui(&mut self, ui: &mut egui::Ui) {
+        let statuses = self.render_progress.lock().map(|p| p.target_status.clone()).unwrap_or_default();
+        let multi = &mut self.render_settings.multi_output;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut multi.enabled, &self.text.extra_outputs);
+            if ui.button("+").on_hover_text("Add output target (.mp4 / .webm / .mov)").clicked() {
+                multi.enabled = true;
+                multi.formats.push(OutputTarget::default());
+            }
+        });
+        if !multi.enabled {
+            return;
+        }
+        let mut remove = None;
+        for (i, target) in multi.formats.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut target.path).desired_width(140.0).hint_text("out.webm"));
+                if ui.button("...").clicked()
+                    && let Some(path) = rfd::FileDialog::new().save_file()
+                {
+                    target.path = path.display().to_string();
+                }
+                egui::ComboBox::from_id_source(("target_resolution", i))
+                    .selected_text(target.settings.output_resolution.to_string())
+                    .show_ui(ui, |ui| {
+                        for res in OutputResolution::ALL {
+                            ui.selectable_value(&mut target.settings.output_resolution, res, res.to_string());
+                        }
+                    });
+                egui::ComboBox::from_id_source(("target_codec", i))
+                    .selected_text(target.settings.output_codec.to_string())
+                    .show_ui(ui, |ui| {
+                        for codec in OutputCodec::ALL {
+                            ui.selectable_value(&mut target.settings.output_codec, codec, codec.to_string());
+                        }
+                    });
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+            if let Some(status) = statuses.get(i) {
+                ui.label(egui::RichText::new(status).small().color(egui::Color32::GRAY));
+            }
+        }
+        if let Some(i) = remove {
+            multi.formats.remove(i);
+        }
+    }
+
+    /// Wykonuje akcje z menu kontekstowego klipu (z wpisem do historii undo)
+    fn apply_clip_menu_action(&mut self, action: ClipMenuAction) {
+        let idx = match action {
+            ClipMenuAction::Split(idx)
+            | ClipMenuAction::Duplicate(idx)
+            | ClipMenuAction::Reverse(idx)
+            | ClipMenuAction::Properties(idx)
+            | ClipMenuAction::Color(idx, _)
+            | ClipMenuAction::TrimLeft(idx)
+            | ClipMenuAction::TrimRight(idx)
+            | ClipMenuAction::FillToNext(idx, _)
+            | ClipMenuAction::MoveToTrack(idx, _) => idx,
+        };
+        if idx >= self.clips.len() {
+            return;
+        }
+        self.selected_clips.clear();
+        self.selected_clip = Some(idx);
+        if let ClipMenuAction::Properties(_) = action {
+            self.show_clip_properties = true;
+            return;
+        }
+        let playhead = self.snap(self.playhead);
+        self.push_undo();
+        match action {
+            ClipMenuAction::Split(idx) => {
+                if let Some(split) = split_clip_at(&mut self.clips, idx, playhead) {
+                    self.selected_clip = Some(split);
+                }
+            }
+            ClipMenuAction::Duplicate(idx) => self.selected_clip = duplicate_clip(&mut self.clips, idx),
+            ClipMenuAction::Reverse(idx) => self.clips[idx].reversed = !self.clips[idx].reversed,
+            ClipMenuAction::Color(idx, color) => self.clips[idx].color_label = color,
+            ClipMenuAction::TrimLeft(idx) => {
+                trim_clip_start(&mut self.clips, idx, playhead);
+            }
+            ClipMenuAction::TrimRight(idx) => {
+                trim_clip_end(&mut self.clips, idx, playhead);
+            }
+            ClipMenuAction::FillToNext(idx, next_start) => {
+                let clip = &mut self.clips[idx];
+                clip.end += (next_start - clip.timeline_end()) * clip.speed.max(0.01);
+            }
+            ClipMenuAction::MoveToTrack(idx, track) => self.clips[idx].track_index = track,
+            ClipMenuAction::Properties(_) => {}
+        }
+        self.duration = self.duration.max(self.clips.iter().map(|c| c.timeline_end()).fold(0.0, f32::max));
+    }
+
+    /// Znak wodny renderu: wybor obrazu, pozycja, krycie i skala
+    fn watermark_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(&self.text.watermark);
+            if ui.button(&self.text.watermark_choose).clicked()
+                && let Some(path) = rfd::FileDialog::new().add_filter("Image", &["png", "jpg", "jpeg", "webp"]).pick_file()
+            {
+                match &mut self.render_settings.watermark {
+                    Some(watermark) => watermark.path = path,
+                    None => self.render_settings.watermark = Some(WatermarkSettings::new(path)),
+                }
+            }
+            if let Some(watermark) = &self.render_settings.watermark {
+                let name = watermark.path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                ui.label(egui::RichText::new(name).small().color(egui::Color32::GRAY));
+                if ui.small_button("✖").clicked() {
+                    self.render_settings.watermark = None;
+                }
+            }
+        });
+        let (width, height) = (self.video_width.max(1), self.video_height.max(1));
+        let Some(watermark) = &mut self.render_settings.watermark else { return };
+        ui.horizontal(|ui| {
+            ui.label(&self.text.watermark_position);
+            egui::ComboBox::from_id_source("watermark_position")
+                .selected_text(watermark.position.to_string())
+                .show_ui(ui, |ui| {
+                    for position in WatermarkPosition::CORNERS {
+                        ui.selectable_value(&mut watermark.position, position, position.to_string());
+                    }
+                    let custom = matches!(watermark.position, WatermarkPosition::Custom(..));
+                    if ui.selectable_label(custom, "Custom").clicked() && !custom {
+                        watermark.position = WatermarkPosition::Custom(0, 0);
+                    }
+                });
+            if let WatermarkPosition::Custom(x, y) = &mut watermark.position {
+                ui.add(egui::DragValue::new(x).prefix("x ").clamp_range(0..=width));
+                ui.add(egui::DragValue::new(y).prefix("y ").clamp_range(0..=height));
+            }
+        });
+        ui.add(egui::Slider::new(&mut watermark.opacity, 0.0..=1.0).fixed_decimals(2).text(&self.text.watermark_opacity));
+        ui.add(egui::Slider::new(&mut watermark.scale, 0.05..=2.0).fixed_decimals(2).text(&self.text.watermark_scale));
+    }
+
+    /// Dzieli wszystkie klipy pod playheadem (od konca, zeby indeksy sie nie przesuwaly)
+    fn split_at_playhead(&mut self) {
+        let t = self.playhead;
+        let hits: Vec<usize> = self
+            .clips
+            .iter()
+            .enumerate()
+            .filter(|(_, clip)| t > clip.start && t < clip.timeline_end())
+            .map(|(idx, _)| idx)
+            .collect();
+        if hits.is_empty() {
+            self.log.push(LogEntry::warning("No clip at playhead"));
+            return;
+        }
+        self.push_undo();
+        for &idx in hits.iter().rev() {
+            if let Some(split) = split_clip_at(&mut self.clips, idx, t) {
+                self.selected_clip = Some(split);
+            }
+        }
+        self.selected_clips.clear();
+    }
+
+    /// Szerokosc klatek podgladu wg ustawionej jakosci
+    fn preview_width(&self) -> u32 {
+        self.settings.preview_quality.width(self.video_width)
+    }
+
+    /// Przyciaganie czasu do siatki klatek (gdy wlaczone)
+    fn snap(&self, time: f32) -> f32 {
+        if self.settings.timeline_snap_to_frames && self.video_fps > 0.0 {
+            snap_to_frame(time, self.video_fps)
+        } else {
+            time
+        }
+    }
+
+    /// Ustawia visuals wg `settings.theme` i koloru akcentu (tylko po zmianie)
+    fn apply_theme(&mut self, ctx: &egui::Context, system_theme: Option<eframe::Theme>) {
+        let system_dark = system_theme.map(|t| t == eframe::Theme::Dark);
+        let key = (self.settings.theme, self.settings.accent_color, system_dark);
+        if self.applied_theme == Some(key) {
+            return;
+        }
+        let mut visuals = self.settings.theme.visuals(system_dark);
+        visuals.selection.bg_fill = self.settings.accent_color;
+        ctx.set_visuals(visuals);
+        self.applied_theme = Some(key);
     }
-    let right = Clip {
-        asset_id: clip.asset_id,
-        start: t,
-        end: clip.end,
-        fade_in: 0.0,
-        fade_out: clip.fade_out,
-        linked: clip.linked,
-        video_enabled: clip.video_enabled,
-        audio_enabled: clip.audio_enabled,
-    };
-    clips[idx].end = t;
-    clips[idx].fade_out = 0.0;
-    clips.insert(idx + 1, right);
-    Some(idx + 1)
-}
 
+    /// Histogram liczony z pikseli przed wyslaniem na GPU (tylko gdy nakladka wlaczona)
+    fn update_histogram(&mut self, image: &egui::ColorImage) {
+        if self.show_histogram {
+            self.preview_histogram = Some(compute_histogram(image));
+        }
+    }
 
+    /// Zaznaczone klipy: Ctrl+klik lub pojedynczy selected_clip
+    fn selected_indices(&self) -> Vec<usize> {
+        let indices = if self.selected_clips.is_empty() {
+            self.selected_clip.into_iter().collect()
+        } else {
+            self.selected_clips.clone()
+        };
+        indices.into_iter().filter(|&idx| idx < self.clips.len()).collect()
+    }
 
+    /// Zapamietuje stan klipow przed zmiana (Ctrl+Z)
+    fn push_undo(&mut self) {
+        self.push_undo_action(UndoAction::Snapshot(self.clips.clone()));
+    }
 
-impl VideoEditorApp {
-    fn save_project_as(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Rust Video Editor Project", &["rev", "json"])
-            .save_file() 
-        {
-            let data = ProjectData {
-                input_path: self.input_path.clone(),
-                output_path: self.output_path.clone(),
-                playhead: self.playhead,
-                clips: self.clips.clone(),
-                media_library: self.media_library.clone(),
-                duration: self.duration,
-                video_width: self.video_width,
-                video_height: self.video_height,
-                video_fps: self.video_fps,
-            };
-            match serde_json::to_string_pretty(&data) {
-                Ok(json) => {
-                    if let Err(e) = fs::write(path, json) {
-                        self.status = format!("Blad zapisu projektu: {e}");
-                    } else {
-                        self.status = "Projekt zapisany.".to_string();
-                    }
-                }
-                Err(e) => {
-                    self.status = format!("Blad serializacji: {e}");
-                }
-            }
+    fn push_undo_action(&mut self, action: UndoAction) {
+        self.undo_stack.push(action);
+        if self.undo_stack.len() > 100 {
+            self.undo_stack.remove(0);
         }
+        self.redo_stack.clear();
     }
 
-    fn load_project_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Rust Video Editor Project", &["rev", "json"])
-            .pick_file() 
-        {
-            if let Ok(content) = fs::read_to_string(&path) {
-                match serde_json::from_str::<ProjectData>(&content) {
-                    Ok(data) => {
-                        self.input_path = data.input_path;
-                        self.output_path = data.output_path;
-                        self.clips = data.clips;
-                        self.media_library = data.media_library;
-                        self.duration = data.duration;
-                        self.video_width = data.video_width;
-                        self.video_height = data.video_height;
-                        self.video_fps = data.video_fps;
-                        self.playhead = data.playhead;
-                        
-                        // Reset stanu UI
-                        self.selected_clip = None;
-                        self.stop_playback();
-                        
-                        // Przywrocenie zasobow (podglady, waveform)
-                        if !self.input_path.is_empty() {
-                            self.prepare_media_assets(ctx);
-                        }
-                        
-                        // Regeneracja miniatur biblioteki
-                        self.media_thumbs.clear();
-                        for (idx, asset) in self.media_library.iter().enumerate() {
-                             let mut thumb = None;
-                             let path = Path::new(&asset.path);
-                             if asset.kind == MediaType::Image {
-                                 if let Ok(t) = load_texture_from_path(ctx, path, &format!("thumb_{}", idx)) {
-                                     thumb = Some(t);
-                                 }
-                             } else {
-                                 // Video thumb
-                                 if let Ok(data) = generate_frame_memory(&asset.path, asset.duration * 0.1, 128, 0) { 
-                                     if let Ok(t) = load_texture_from_memory(ctx, &data, &format!("thumb_{}", idx)) {
-                                         thumb = Some(t);
-                                     }
-                                 }
-                             }
-                             if let Some(t) = thumb {
-                                 self.media_thumbs.insert(idx, t); 
-                             }
-                        }
-                        self.status = "Projekt wczytany.".to_string();
-                    }
-                    Err(e) => {
-                        self.status = format!("Blad parsowania projektu: {e}");
+    /// Cofa (undo = true) lub ponawia akcje; zwraca akcje do przeciwnego stosu
+    fn apply_undo_action(&mut self, action: UndoAction, undo: bool) -> UndoAction {
+        match action {
+            UndoAction::Snapshot(clips) => UndoAction::Snapshot(std::mem::replace(&mut self.clips, clips)),
+            UndoAction::BatchColorChange { indices, old_colors, new_color } => {
+                for (i, &idx) in indices.iter().enumerate() {
+                    if let Some(clip) = self.clips.get_mut(idx) {
+                        clip.color_label = if undo { old_colors[i] } else { new_color };
                     }
                 }
-            } else {
-                self.status = "Blad odczytu pliku projektu.".to_string();
+                UndoAction::BatchColorChange { indices, old_colors, new_color }
             }
         }
     }
 
-    fn build_playback_filters(&self, start_time: f32) -> (Option<String>, Option<String>) {
-        let mut vf_list = Vec::new();
-        let mut af_list = Vec::new();
-        
-        for clip in &self.clips {
-             // Fade In
-             if clip.fade_in > 0.0 {
-                 let rel_start = clip.start - start_time;
-                 // Jesli playback startuje po poczatku fade'a, nie mozemy zaaplikowac filtra z ujemnym czasem.
-                 // Pomijamy efekt w tym przypadku (bedzie hard cut), co zapobiega crashowi ffmpeg.
-                 if rel_start >= 0.0 {
-                     vf_list.push(format!("fade=t=in:st={:.3}:d={:.3}", rel_start, clip.fade_in));
-                     af_list.push(format!("afade=t=in:st={:.3}:d={:.3}", rel_start, clip.fade_in));
-                 }
-             }
-             
-             // Fade Out
-             if clip.fade_out > 0.0 {
-                 let fade_out_start = clip.end - clip.fade_out;
-                 let rel_out = fade_out_start - start_time;
-                 if rel_out >= 0.0 {
-                     vf_list.push(format!("fade=t=out:st={:.3}:d={:.3}", rel_out, clip.fade_out));
-                     af_list.push(format!("afade=t=out:st={:.3}:d={:.3}", rel_out, clip.fade_out));
-                 }
-             }
+    fn undo(&mut self) {
+        if let Some(action) = self.undo_stack.pop() {
+            let redo = self.apply_undo_action(action, true);
+            self.redo_stack.push(redo);
+            self.selected_clips.retain(|&idx| idx < self.clips.len());
+            self.selected_clip = self.selected_clip.filter(|&idx| idx < self.clips.len());
         }
-        
-        let vf = if vf_list.is_empty() { None } else { Some(vf_list.join(",")) };
-        let af = if af_list.is_empty() { None } else { Some(af_list.join(",")) };
-        (vf, af)
     }
 
-    fn prepare_media_assets(&mut self, ctx: &egui::Context) {
-        match get_video_info_ffprobe(&self.input_path) {
-            Ok((duration, width, height, fps)) => {
-                self.duration = duration.max(0.0);
-                self.video_width = width;
-                self.video_height = height;
-                self.video_fps = fps;
-                self.playhead = 0.0;
-                self.last_preview_playhead = -1.0;
-                self.stop_playback();
-                self.mark_in = None;
-                self.mark_out = None;
-                if self.clips.is_empty() && self.duration > 0.0 {
-                    self.clips.push(Clip {
-                        asset_id: None,
-                        start: 0.0,
-                        end: self.duration,
-                        fade_in: 0.0,
-                        fade_out: 0.0,
-                        linked: true,
-                        video_enabled: true,
-                        audio_enabled: true,
-                    });
-                    self.selected_clip = Some(0);
-                } else {
-                    self.selected_clip = None;
-                }
-                self.timeline_zoom = 0.0;
-                self.timeline_offset = 0.0;
-                self.status.clear();
-                if let Err(err) = self.ensure_temp_dir() {
-                    self.status = format!("Blad temp: {err:#}");
-                    return;
-                }
-                if let Err(err) = self.build_waveform(ctx) {
-                    self.status = format!("Blad waveform: {err:#}");
-                }
-                if let Err(err) = self.build_thumbnails(ctx, 8) {
-                    self.status = format!("Blad miniatur: {err:#}");
-                }
-                self.maybe_update_preview(ctx);
-            }
-            Err(err) => {
-                self.status = format!("Blad ffprobe: {err:#}");
-            }
+    fn redo(&mut self) {
+        if let Some(action) = self.redo_stack.pop() {
+            let undo = self.apply_undo_action(action, false);
+            self.undo_stack.push(undo);
+            self.selected_clips.retain(|&idx| idx < self.clips.len());
+            self.selected_clip = self.selected_clip.filter(|&idx| idx < self.clips.len());
         }
     }
 
-    fn ensure_temp_dir(&mut self) -> Result<()> {
-        if self.temp_dir.is_none() {
-            self.temp_dir = Some(create_temp_dir()?);
+    /// Ten sam kolor dla wszystkich zaznaczonych klipow - jeden krok cofania
+    fn apply_color_to_selected(&mut self, new_color: ClipColor) {
+        let indices = self.selected_indices();
+        if indices.is_empty() {
+            return;
         }
-        Ok(())
+        let old_colors = indices.iter().map(|&idx| self.clips[idx].color_label).collect();
+        for &idx in &indices {
+            self.clips[idx].color_label = new_color;
+        }
+        self.push_undo_action(UndoAction::BatchColorChange { indices, old_colors, new_color });
     }
 
-    fn maybe_update_preview(&mut self, ctx: &egui::Context) {
-        if (self.input_path.trim().is_empty() && self.clips.is_empty()) || self.duration <= 0.0 {
+    /// Przelacza audio (U) lub wideo (V) we wszystkich zaznaczonych klipach
+    fn toggle_selected_tracks(&mut self, track: TrackType) {
+        let indices = self.selected_indices();
+        if indices.is_empty() {
             return;
         }
-        if self.is_playing {
+        self.push_undo();
+        for idx in indices {
+            let clip = &mut self.clips[idx];
+            match track {
+                TrackType::Audio => clip.audio_enabled = !clip.audio_enabled,
+                TrackType::Video => clip.video_enabled = !clip.video_enabled,
+                TrackType::Both => {}
+            }
+        }
+        self.last_preview_playhead = -1.0;
+    }
+
+    /// Ustawia sloty miniatur bez dekodowania - tekstury generuje poll_thumbnail_requests
+    fn reset_thumbnails(&mut self) {
+        self.thumb_textures.clear();
+        self.thumb_times.clear();
+        self.thumb_requests.clear();
+        let count = self.settings.thumbnails.count;
+        if self.duration <= 0.0 || count == 0 || self.input_path.is_empty() {
             return;
         }
-        let now = Instant::now();
-        if let Some(last) = self.last_preview_time {
-            if now.duration_since(last).as_millis() < 150 {
+        for i in 0..count {
+            self.thumb_times.push((i as f32 + 0.5) * (self.duration / count as f32));
+            self.thumb_textures.push(None);
+        }
+    }
+
+    /// Waveform jednego pliku zastepczego audio na klatke (jak miniatury); blad zostawia pusty pasek
+    fn poll_override_waveforms(&mut self, ctx: &egui::Context) {
+        if let Some(handle) = &self.override_waveform_thread {
+            if !handle.is_finished() {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
                 return;
             }
+            if let Some(handle) = self.override_waveform_thread.take() {
+                match handle.join() {
+                    Ok((path, wave_path, result)) => {
+                        let result = result.and_then(|duration| {
+                            let texture = load_texture_from_path(ctx, &wave_path, &format!("wave_override_{path}"))?;
+                            Ok((texture, duration.max(0.001)))
+                        });
+                        // Blad tez zapamietujemy, zeby nie ponawiac generowania co klatke
+                        if let Err(err) = &result {
+                            self.log.push(LogEntry::warning(format!("Blad waveformu audio {path}: {err:#}")));
+                        }
+                        self.override_waveforms.insert(path, result.ok());
+                    }
+                    Err(_) => self.log.push(LogEntry::error("Blad: watek waveformu audio przerwany.")),
+                }
+            }
         }
-        if (self.playhead - self.last_preview_playhead).abs() < 0.05 {
+        let Some(idx) = self.override_waveform_requests.iter().position(|p| !self.override_waveforms.contains_key(p)) else {
+            self.override_waveform_requests.clear();
             return;
+        };
+        let path = self.override_waveform_requests.remove(idx);
+        let cache = match self.cache_dir() {
+            Ok(cache) => cache,
+            Err(err) => {
+                self.log.push(LogEntry::warning(format!("Blad waveformu audio {path}: {err:#}")));
+                self.override_waveforms.insert(path, None);
+                return;
+            }
+        };
+        let amplitude = self.settings.waveform_amplitude;
+        // Nazwa zalezy od amplitudy - porzucony watek ze stara amplituda nie nadpisze nowego pliku
+        use std::hash::{Hash, Hasher};
+        let amplitude_key = match amplitude {
+            WaveformAmplitude::Auto => (0u8, 0u32),
+            WaveformAmplitude::Normalized => (1, 0),
+            WaveformAmplitude::Dbfs(db) => (2, db.to_bits()),
+        };
+        let mut hasher = std::hash::DefaultHasher::new();
+        (&path, amplitude_key).hash(&mut hasher);
+        let wave_path = cache.join(format!("wave_override_{:016x}.png", hasher.finish()));
+        self.override_waveform_thread = Some(thread::spawn(move || {
+            let result = generate_waveform(&path, &wave_path, amplitude)
+                .and_then(|_| get_video_info_ffprobe(&path))
+                .map(|(duration, ..)| duration);
+            (path, wave_path, result)
+        }));
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    /// Miniatura z dyskowego cache; przy braku (lub zmienionym zrodle) generuje ja ffmpeg i zapisuje do cache
+    fn cached_thumbnail(
+        &self,
+        ctx: &egui::Context,
+        path: &str,
+        time: f32,
+        width: u32,
+        height: i32,
+        name: &str,
+    ) -> Result<egui::TextureHandle> {
+        let hash = ThumbnailCache::source_hash(path, width, height).ok();
+        // Klucz w milisekundach - klatki filmstripu blizej niz 1 s nie moga sie zlac
+        let time_ms = (time.max(0.0) * 1000.0).round() as u32;
+        if let Some(hash) = hash
+            && let Some(cached) = self.thumb_cache.get(hash, time_ms)
+            && let Ok(texture) = load_texture_from_path(ctx, &cached, name)
+        {
+            return Ok(texture);
         }
-        if let Err(err) = self.build_preview(ctx) {
-            self.status = format!("Blad podgladu: {err:#}");
-        } else {
-            self.last_preview_time = Some(now);
-            self.last_preview_playhead = self.playhead;
+        let data = generate_frame_memory(path, time, width, height)?;
+        if let Some(hash) = hash {
+            // Cache jest tylko przyspieszeniem - blad zapisu nie blokuje miniatury
+            let _ = self.thumb_cache.put(hash, time_ms, &data);
         }
+        load_texture_from_memory(ctx, &data, name)
     }
 
-    fn maybe_update_preview_drag(&mut self, _ctx: &egui::Context) {
-        if (self.input_path.trim().is_empty() && self.clips.is_empty()) || self.duration <= 0.0 {
+    /// Generuje co najwyzej jedna miniature z kolejki na klatke (zeby nie zacinac UI)
+    fn poll_thumbnail_requests(&mut self, ctx: &egui::Context) {
+        if self.thumb_requests.is_empty() {
             return;
         }
-
-        // Jesli watek pracuje, nie robimy nic (drop frame) - to zapewnia plynnosc UI
-        if self.preview_busy.load(Ordering::Relaxed) {
-             return;
-        }
-
-        // Jesli pozycja zmienila sie nieznacznie, tez ignorujemy
-        if (self.playhead - self.last_drag_preview_playhead).abs() < 0.1 {
+        let t = self.thumb_requests.remove(0);
+        let Some(idx) = self.thumb_times.iter().position(|&time| time == t) else {
+            return;
+        };
+        if self.thumb_textures[idx].is_some() {
             return;
         }
-        
-        self.last_drag_preview_playhead = self.playhead;
-        
-        let busy = self.preview_busy.clone();
-        let tx = self.preview_tx.clone();
-        // Resolve source!
-        // We need access to clips... but we are moving 'self' fields into closure.
-        // Complex. Thread needs the path.
-        // We can resolve BEFORE spawning.
-        let (input, time) = self.resolve_clip_source(self.playhead);
-        
-        // Ustawiamy flage busy
-        busy.store(true, Ordering::Relaxed);
-        
-        // Spawn watku
-        thread::spawn(move || {
-            // Low-Res Proxy: 320px szerokosci dla szybkosci
-            if let Ok(data) = generate_frame_memory(&input, time, 320, 0) {
-                let _ = tx.send((time, data));
+        // Najpierw dyskowy cache miniatur (przezywa restart), dopiero potem ffmpeg
+        let ThumbnailSettings { width, height, .. } = self.settings.thumbnails;
+        // scale=width:height (0 = zachowaj proporcje)
+        let result = self.cached_thumbnail(ctx, &self.input_path, t, width, height as i32, &format!("thumb_{idx}"));
+        match result {
+            Ok(texture) => self.thumb_textures[idx] = Some(texture),
+            Err(err) => {
+                // Nie ponawiamy w nieskonczonosc - slot zostaje pusty do regeneracji
+                self.thumb_times[idx] = f32::NAN;
+                self.log.push(LogEntry::error(format!("Blad miniatur: {err:#}")));
             }
-            // Zwalniamy flage
-            busy.store(false, Ordering::Relaxed);
-        });
+        }
+        if !self.thumb_requests.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+    /// "Klatka: n / N"; klikniecie zamienia licznik na pole numeru klatki (seek do frame / fps)
+    fn frame_counter_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.video_fps <= 0.0 {
+            return false;
+        }
+        let fps = self.video_fps;
+        let total = (self.duration * fps).round() as u64;
+        let mut frame = (self.playhead * fps).round() as u64;
+        if !self.editing_frame {
+            let label = egui::Label::new(egui::RichText::new(format!("{}: {frame} / {total}", self.text.frame_label)).monospace())
+                .sense(egui::Sense::click());
+            if ui.add(label).on_hover_text(&self.text.frame_edit_hint).clicked() {
+                self.editing_frame = true;
+            }
+            return false;
+        }
+        let response = ui.add(
+            egui::DragValue::new(&mut frame)
+                .clamp_range(0..=total)
+                .prefix(format!("{}: ", self.text.frame_label))
+                .suffix(format!(" / {total}")),
+        );
+        if response.lost_focus() || response.clicked_elsewhere() {
+            self.editing_frame = false;
+        }
+        if response.changed() {
+            self.playhead = (frame as f32 / fps).min(self.duration);
+            return true;
+        }
+        false
     }
 
-    fn build_waveform(&mut self, ctx: &egui::Context) -> Result<()> {
-        self.ensure_temp_dir()?;
-        let temp_dir = self
-            .temp_dir
-            .as_ref()
-            .ok_or_else(|| anyhow!("Brak katalogu temp"))?;
-        let wave_path = temp_dir.join("waveform.png");
-        generate_waveform(&self.input_path, &wave_path)?;
-        let texture = load_texture_from_path(ctx, &wave_path, "waveform")?;
-        self.waveform_texture = Some(texture);
-        Ok(())
+    /// Komunikat na pasku stanu; trafia tez do logu sesji
+    fn set_status(&mut self, kind: StatusKind, text: impl Into<String>) {
+        let status = StatusMessage::new(kind, text);
+        self.log.push(LogEntry::new(kind.log_level(), status.text.clone()));
+        self.status = Some(status);
     }
 
-    fn resolve_clip_source(&self, time: f32) -> (String, f32) {
-        for (_idx, clip) in self.clips.iter().enumerate() {
-            if clip.video_enabled && time >= clip.start && time < clip.end {
-                let local_time = time - clip.start;
-                // Fade in/out logic might be here but for source we just need path
-                if let Some(asset_id) = clip.asset_id {
-                    // Find asset in library (by index for MVP, assuming valid)
-                     if let Some(asset) = self.media_library.get(asset_id) {
-                         if asset.kind == MediaType::Video || asset.kind == MediaType::Image {
-                             return (asset.path.clone(), local_time);
-                         }
-                     }
-                }
-                // Fallback to input_path if no asset_id (legacy clip)
-                if clip.asset_id.is_none() {
-                     return (self.input_path.clone(), time); // Main video uses global time? No, main video clip usually 0..duration.
-                }
+    /// Tresc paska stanu: spinner dla Working, kolor wg rodzaju, zamykanie (Error tylko recznie)
+    fn status_message_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(status) = &self.status else { return };
+        let color = status.kind.color(ui.visuals());
+        match status.kind {
+            StatusKind::Working => {
+                self.status_spinner_angle = (self.status_spinner_angle + 0.15) % std::f32::consts::TAU;
+                draw_arc_spinner(ui, self.status_spinner_angle, color);
+                ui.ctx().request_repaint();
+            }
+            StatusKind::Success => {
+                // Odswiezenie w chwili wygasniecia komunikatu
+                ui.ctx().request_repaint_after(StatusMessage::SUCCESS_TIMEOUT.saturating_sub(status.timestamp.elapsed()));
             }
+            _ => {}
+        }
+        ui.label(egui::RichText::new(&status.text).color(color));
+        if ui.small_button("✖").clicked() {
+            self.status = None;
         }
-        // If no clip found, return input_path and time? Or empty?
-        // Default behavior: show input_path at time.
-        (self.input_path.clone(), time)
     }
 
-    fn build_preview(&mut self, ctx: &egui::Context) -> Result<()> {
-        let (path, local_time) = self.resolve_clip_source(self.playhead);
-        if path.is_empty() { return Ok(()); }
-        
-        let data = generate_frame_memory(&path, local_time, 640, 0)?;
-        let texture = load_texture_from_memory(ctx, &data, "preview")?;
-        self.preview_texture = Some(texture);
-        Ok(())
+    /// Biezace rozmiary okna i paneli do ustawien (zapis przy wyjsciu w on_exit)
+    fn remember_window_layout(&mut self, ctx: &egui::Context, timeline_height: f32, tools_width: f32) {
+        let layout = &mut self.settings.window_layout;
+        layout.timeline_height = timeline_height;
+        layout.tools_width = tools_width;
+        ctx.input(|i| {
+            let viewport = i.viewport();
+            // Zminimalizowane okno ma zerowy rozmiar - zostawiamy ostatni prawidlowy
+            if viewport.minimized == Some(true) {
+                return;
+            }
+            if let Some(rect) = viewport.inner_rect {
+                layout.window_size = rect.size().into();
+            }
+            if let Some(rect) = viewport.outer_rect {
+                layout.window_pos = Some(rect.min.into());
+            }
+        });
     }
 
-
-
-    fn build_thumbnails(&mut self, ctx: &egui::Context, count: usize) -> Result<()> {
-        // Miniatury tez robimy w pamieci, bez zasmiecania dysku
-        self.thumb_textures.clear();
-        self.thumb_times.clear();
-        if self.duration <= 0.0 || count == 0 {
-            return Ok(());
-        }
-        for i in 0..count {
-            let t = (i as f32 + 0.5) * (self.duration / count as f32);
-            // scale=200:-1
-            let data = generate_frame_memory(&self.input_path, t, 200, 0)?;
-            let texture = load_texture_from_memory(ctx, &data, &format!("thumb_{i}"))?;
-            self.thumb_textures.push(texture);
-            self.thumb_times.push(t);
+    /// Start / stop odtwarzania (przycisk ▶ i skrot play_pause)
+    fn toggle_playback(&mut self) {
+        if self.is_playing {
+            self.stop_playback();
+        } else if self.duration > 0.0 {
+            self.is_playing = true;
+            self.last_tick = Some(Instant::now());
+            if let Err(err) = self.start_playback() {
+                self.log.push(LogEntry::error(format!("Blad odtwarzania: {err:#}")));
+                self.is_playing = false;
+            }
         }
-        Ok(())
     }
+
     fn start_playback(&mut self) -> Result<()> {
         let was_playing = self.is_playing;
         self.stop_playback();
+        self.stop_audio_scrub();
         if was_playing {
             self.is_playing = true;
             self.last_tick = Some(Instant::now());
@@ -2342,7 +8364,7 @@ impl VideoEditorApp {
         }
         
         // Wstępne załadowanie pierwszej ramki (instant preview)
-        let (width, height) = scaled_preview_size(self.video_width, self.video_height, 640);
+        let (width, height) = scaled_preview_size(self.video_width, self.video_height, self.preview_width());
         let (start_input, start_time) = self.resolve_clip_source(self.playhead);
         if let Ok(frame_data) = generate_frame_memory(&start_input, start_time, width, height as i32) {
             if let Ok(image) = image::load_from_memory(&frame_data) {
@@ -2358,6 +8380,9 @@ impl VideoEditorApp {
         
         // Initialize start position for audio sync
         self.playback_start_playhead = self.playhead;
+        if let Ok(mut start) = self.audio_clock_start.lock() {
+            *start = None;
+        }
 
         // VIDEO SYNC: Start video thread, but wait for signal before starting audio and time
         self.waiting_for_video_ready = true;
@@ -2373,16 +8398,16 @@ impl VideoEditorApp {
         if self.input_path.is_empty() && self.media_library.is_empty() && self.clips.is_empty() {
             return Ok(());
         }
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or_else(|| anyhow!("Brak urzadzenia audio"))?;
+        let device = output_device(&self.settings.audio_device_name)?;
         let config = device.default_output_config()?;
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
         self.audio_sample_rate = sample_rate;
         self.audio_channels = channels;
         self.audio_samples_played.store(0, Ordering::Relaxed);
+        if let Ok(mut start) = self.audio_clock_start.lock() {
+            *start = None;
+        }
         if let Ok(mut q) = self.audio_buffer.lock() {
             q.clear();
         }
@@ -2392,7 +8417,10 @@ impl VideoEditorApp {
         
         // Resolve input path - matches video logic
         let (resolved_path, clip_offset) = self.resolve_clip_source(self.playhead);
-        let input = if !resolved_path.is_empty() {
+        let audio_override = self.audio_clip_at(self.playhead).and_then(|clip| clip.audio_override.clone());
+        let input = if let Some(path) = audio_override {
+             path
+        } else if !resolved_path.is_empty() {
              resolved_path
         } else {
              if let Some(first) = self.clips.first().and_then(|c| c.asset_id).and_then(|id| self.media_library.get(id)) {
@@ -2402,21 +8430,22 @@ impl VideoEditorApp {
              }
         };
         
-        let start_time = if input != self.input_path {
-            clip_offset
-        } else {
-            self.playhead.max(0.0)
-        };
+        let start_time = clip_offset.max(0.0);
         
         // Generujemy filtry audio dla playbacku
         let (_, af_opt) = self.build_playback_filters(start_time);
 
         // Collect valid audio intervals for masking
-        // (start, end)
+        // (start, end, volume)
         let mut audio_intervals = Vec::new();
         for clip in &self.clips {
-             if clip.audio_enabled {
-                 audio_intervals.push((clip.start, clip.end));
+             if effective_audio_enabled(clip, &self.track_muted, &self.track_soloed) {
+                 // Niezalezne przyciecie dzwieku zaweza okno klipu
+                 let (start, end) = match clip.audio_trim() {
+                     Some((a, b)) => (clip.start + a, clip.start + b),
+                     None => (clip.start, clip.timeline_end()),
+                 };
+                 audio_intervals.push((start, end, clip.volume));
              }
         }
         let audio_intervals = Arc::new(audio_intervals);
@@ -2425,7 +8454,7 @@ impl VideoEditorApp {
         let stop_thread = Arc::clone(&stop);
         let buffer_thread = Arc::clone(&buffer);
         let audio_thread = thread::spawn(move || {
-            let mut cmd = Command::new("ffmpeg");
+            let mut cmd = ffmpeg_command();
             cmd.args([
                 "-hide_banner",
                 "-loglevel",
@@ -2536,16 +8565,8 @@ impl VideoEditorApp {
                         let current_played = samples_played.load(Ordering::Relaxed);
                         for (i, sample) in data.iter_mut().enumerate() {
                             let time = start_ph + (current_played + i as u64) as f32 / (sample_rate as f32 * channels as f32);
-                            let mut valid = false;
-                            for (s, e) in intervals.iter() {
-                                if time >= *s && time < *e {
-                                    valid = true;
-                                    break;
-                                }
-                            }
-                            if !valid {
-                                *sample = 0;
-                            }
+                            let gain = interval_gain(&intervals, time);
+                            *sample = (*sample as f32 * gain).clamp(-32768.0, 32767.0) as i16;
                         }
 
                         samples_played.fetch_add(filled as u64, Ordering::Relaxed);
@@ -2582,16 +8603,7 @@ impl VideoEditorApp {
                         let current_played = samples_played.load(Ordering::Relaxed);
                         for (i, sample) in data.iter_mut().enumerate() {
                             let time = start_ph + (current_played + i as u64) as f32 / (sample_rate as f32 * channels as f32);
-                            let mut valid = false;
-                            for (s, e) in intervals.iter() {
-                                if time >= *s && time < *e {
-                                    valid = true;
-                                    break;
-                                }
-                            }
-                            if !valid {
-                                *sample = 0.0;
-                            }
+                            *sample *= interval_gain(&intervals, time);
                         }
                         
                         samples_played.fetch_add(filled as u64, Ordering::Relaxed);
@@ -2628,16 +8640,8 @@ impl VideoEditorApp {
                         let current_played = samples_played.load(Ordering::Relaxed);
                         for (i, sample) in data.iter_mut().enumerate() {
                             let time = start_ph + (current_played + i as u64) as f32 / (sample_rate as f32 * channels as f32);
-                            let mut valid = false;
-                            for (s, e) in intervals.iter() {
-                                if time >= *s && time < *e {
-                                    valid = true;
-                                    break;
-                                }
-                            }
-                            if !valid {
-                                *sample = 32768;
-                            }
+                            let gain = interval_gain(&intervals, time);
+                            *sample = ((*sample as f32 - 32768.0) * gain + 32768.0).clamp(0.0, 65535.0) as u16;
                         }
 
                         samples_played.fetch_add(filled as u64, Ordering::Relaxed);
@@ -2651,6 +8655,14 @@ impl VideoEditorApp {
             }
         };
         stream.play()?;
+        if let Ok(mut start) = self.audio_clock_start.lock() {
+            *start = Some(AudioClockStart {
+                wall_clock_start: Instant::now(),
+                audio_pts_start: self.playback_start_playhead as f64,
+                sample_rate,
+                channels,
+            });
+        }
 
         self.audio_stream = Some(stream);
         self.audio_stop = Some(stop);
@@ -2658,12 +8670,21 @@ impl VideoEditorApp {
         Ok(())
     }
 
+    /// Zegar A/V biezacego odtwarzania (wspoldzielony z watkiem wideo)
+    fn av_clock(&self) -> AvClock {
+        AvClock {
+            start: Arc::clone(&self.audio_clock_start),
+            samples_played: Arc::clone(&self.audio_samples_played),
+            correction: self.settings.audio_clock_correction,
+        }
+    }
+
     fn start_video_playback(&mut self) -> Result<()> {
         // Early exit if no valid input
         if self.input_path.is_empty() && self.media_library.is_empty() && self.clips.is_empty() {
             return Ok(());
         }
-        let (width, height) = scaled_preview_size(self.video_width, self.video_height, 640);
+        let (width, height) = scaled_preview_size(self.video_width, self.video_height, self.preview_width());
         let stop = Arc::new(AtomicBool::new(false));
         let stop_thread = Arc::clone(&stop);
         let frames = Arc::clone(&self.playback_frames);
@@ -2681,16 +8702,13 @@ impl VideoEditorApp {
              }
         };
         
-        let start_time = if input != self.input_path {
-            clip_offset // For library clips, use local time (offset from start of clip)
-        } else {
-            self.playhead.max(0.0) // For simple mode, use global time
-        };
+        // Pozycja w zrodle (source_in klipu + offset od poczatku klipu)
+        let start_time = clip_offset.max(0.0);
 
-        let fps = self.video_fps.max(1.0);
-        let audio_clock = Arc::clone(&self.audio_samples_played);
-        let sample_rate = self.audio_sample_rate.max(1);
-        let channels = self.audio_channels.max(1);
+        let fps_limit = self.settings.playback_fps_limit as f32;
+        let limited = fps_limit > 0.0 && fps_limit < self.video_fps;
+        let fps = if limited { fps_limit } else { self.video_fps.max(1.0) };
+        let audio_clock = self.av_clock();
         let ready_signal = Arc::clone(&self.video_ready_signal); // VIDEO SYNC
         let hw_accel = self.hw_accel_mode; // Capture for thread
         
@@ -2699,7 +8717,12 @@ impl VideoEditorApp {
 
         let handle = thread::spawn(move || {
             // Laczymy scale z filtrami fade
-            let scale_str = format!("scale={width}:{height}");
+            // Limit klatek: ffmpeg odrzuca nadmiarowe klatki zamiast je dekodowac do RGBA
+            let scale_str = if limited {
+                format!("fps={fps},scale={width}:{height}")
+            } else {
+                format!("scale={width}:{height}")
+            };
             let vf_string = if let Some(fade) = &vf_opt {
                 format!("{},{}", scale_str, fade)
             } else {
@@ -2708,7 +8731,7 @@ impl VideoEditorApp {
 
             let start_time_str = format!("{:.3}", start_time);
             
-            let mut cmd = Command::new("ffmpeg");
+            let mut cmd = ffmpeg_command();
             cmd.args(["-hide_banner", "-loglevel", "error"]);
             
             match hw_accel {
@@ -2752,8 +8775,7 @@ impl VideoEditorApp {
                 // --- Frame Dropping Logic ---
                 let video_timestamp = frame_idx as f32 / fps;
                 let target_video_rel = video_timestamp - start_time; // Time relative to playback start
-                let played_samples = audio_clock.load(Ordering::Relaxed);
-                let current_audio_time = played_samples as f32 / (sample_rate as f32 * channels as f32);
+                let current_audio_time = audio_clock.elapsed().unwrap_or(0.0) as f32;
                 let early_diff = target_video_rel - current_audio_time;
                 
                 // Jesli jestesmy spoznieni więcej niż 50ms I wideo juz ruszylo (signal=true)
@@ -2768,9 +8790,8 @@ impl VideoEditorApp {
                         break;
                     }
 
-                    let played_samples = audio_clock.load(Ordering::Relaxed);
-                    let current_audio_time = played_samples as f32 / (sample_rate as f32 * channels as f32);
-                    
+                    let current_audio_time = audio_clock.elapsed().unwrap_or(0.0) as f32;
+
                     let diff = target_video_rel - current_audio_time;
                     
                     if diff <= 0.005 {
@@ -2843,23 +8864,101 @@ impl VideoEditorApp {
 impl Default for VideoEditorApp {
     fn default() -> Self {
         let (tx, rx) = mpsc::channel();
+        let (preview_result_tx, preview_result_rx) = mpsc::channel();
+        let settings = load_settings();
+        set_tool_paths(&settings.ffmpeg_path, &settings.ffprobe_path);
         Self {
             input_path: String::new(),
             output_path: String::new(),
+            output_template: OutputTemplate::default(),
+            use_output_template: false,
+            output_path_preview: None,
+            project_path: None,
+            project_lock: None,
+            project_read_only: false,
+            pending_locked_project: None,
+            render_settings: RenderSettings::default(),
+            rubberband_available: ffmpeg_has_filter("rubberband"),
+            subtitles: Vec::new(),
+            settings,
+            render_thread: None,
+            render_queue: Arc::new(Mutex::new(Vec::new())),
+            queue_thread: None,
+            show_render_queue: false,
+            silence_thread: None,
+            phase_thread: None,
+            trim_silence_thread: None,
+            chop_parts: 2,
+            archive_thread: None,
+            script_thread: None,
+            calibration_thread: None,
+            archive_progress: Arc::new(Mutex::new((0, 0))),
+            source_cache: HashMap::new(),
+            source_cache_failed: HashSet::new(),
+            source_cache_thread: None,
+            source_cache_progress: Arc::new(Mutex::new((0, 0))),
+            pending_render_confirmation: None,
+            pending_auto_arrange: None,
+            timeline_zoom_request: None,
+            silence_ranges: Vec::new(),
+            silence_threshold_db: -40.0,
+            silence_min_duration: 0.5,
+            bpm: 0.0,
+            beat_markers: Vec::new(),
+            beat_thread: None,
+            render_progress: Arc::new(Mutex::new(RenderProgress::default())),
+            show_clip_properties: false,
+            missing_media: Vec::new(),
+            show_remap_media: false,
+            remap_not_found: HashSet::new(),
+            remap_search_roots: Vec::new(),
+            media_search_thread: None,
+            import_folder_thread: None,
+            show_goto: false,
+            goto_input: String::new(),
             clips: Vec::new(),
             duration: 0.0,
             video_width: 0,
             video_height: 0,
             video_fps: 30.0,
+            drop_frame: false,
+            editing_frame: false,
+            applied_theme: None,
+            preview_pan: egui::Vec2::ZERO,
+            recording_shortcut: None,
+            source_bitrate_kbps: 0.0,
+            media_info: None,
+            pending_probe: None,
+            drop_probes: HashMap::new(),
+            drop_probe_threads: Vec::new(),
+            waveform_zoom_level: 1.0,
+            pending_waveform: None,
+            audio_device_names: Vec::new(),
+            last_auto_save: Instant::now(),
+            probe_spinner_angle: 0.0,
             playhead: 0.0,
             mark_in: None,
             mark_out: None,
             selected_clip: None,
+            selected_clips: Vec::new(),
+            groups: HashMap::new(),
+            markers: Vec::new(),
+            renaming_group: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
             selected_track: TrackType::Both,
             preview_texture: None,
+            comparison_mode: false,
+            comparison_a_texture: None,
+            comparison_b_texture: None,
+            comparison_split: 0.5,
+            show_histogram: false,
+            preview_histogram: None,
             waveform_texture: None,
             thumb_textures: Vec::new(),
             thumb_times: Vec::new(),
+            thumb_requests: Vec::new(),
+            thumb_cache: ThumbnailCache::default(),
             temp_dir: None,
             last_preview_time: None,
             last_preview_playhead: -1.0,
@@ -2871,37 +8970,66 @@ impl Default for VideoEditorApp {
             audio_thread: None,
             audio_stop: None,
             audio_stream: None,
+            scrub_stream: None,
+            scrub_buffer: Arc::new(Mutex::new(VecDeque::new())),
+            scrub_busy: Arc::new(AtomicBool::new(false)),
+            last_scrub_playhead: -1.0,
             audio_buffer: Arc::new(Mutex::new(VecDeque::new())),
             audio_samples_played: Arc::new(AtomicU64::new(0)),
+            audio_clock_start: Arc::new(Mutex::new(None)),
             audio_sample_rate: 48000,
             audio_channels: 2,
             dragging_playhead: false,
             was_dragging_playhead: false,
             timeline_zoom: 0.0,
             timeline_offset: 0.0,
+            minimap_drag_start: None,
             last_drag_preview_playhead: -1.0,
-            live_drag_preview: true,
             tool: Tool::Hand,
             dragging_timeline: false,
             dragging_fade: None,
             dragging_clip: None,
             drag_clip_offset: 0.0,
-
-            ripple_delete: false,
+            track_height_video: 60.0,
+            track_height_audio: 50.0,
+            track_video_name: "Video".to_string(),
+            track_audio_name: "Audio".to_string(),
+            track_video_locked: false,
+            track_audio_locked: false,
+            track_muted: Vec::new(),
+            track_soloed: Vec::new(),
+            hover_start: None,
+
+            show_storyboard: false,
+            show_secondary_preview: false,
+            storyboard_columns: 4,
+            show_clip_list: false,
             show_settings: false,
+            show_about: false,
+            about_tool_versions: None,
             language: Language::En,
             text: TextResources::new(Language::En),
             
             media_library: Vec::new(),
             media_thumbs: HashMap::new(),
             media_waveforms: HashMap::new(),
+            override_waveforms: HashMap::new(),
+            override_waveform_requests: Vec::new(),
+            override_waveform_thread: None,
             media_filmstrips: HashMap::new(),
             dragging_library_asset: None,
             language_switch_start: None,
-            status: String::new(),
+            log: Vec::new(),
+            status: None,
+            status_spinner_angle: 0.0,
+            session_start: Instant::now(),
             preview_rx: rx,
             preview_tx: tx,
             preview_busy: Arc::new(AtomicBool::new(false)),
+            pending_preview: None,
+            preview_generation: 0,
+            preview_result_tx,
+            preview_result_rx,
             frame_cache: HashMap::new(),
             frame_cache_max_size: 100,  // Max 100 frames in cache (~50MB for 720p)
             
@@ -0,0 +1,77 @@
+// migration.rs - Wersjonowanie formatu projektu i migracje starszych plikow
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::types::ProjectData;
+
+/// Aktualna wersja formatu pliku projektu
+pub const CURRENT_PROJECT_VERSION: u32 = 3;
+
+/// Wersja zapisana w pliku; projekty bez pola `version` to wersja 1
+pub fn project_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v as u32)
+}
+
+/// Wczytuje projekt w dowolnej obslugiwanej wersji, migrujac go krok po kroku
+pub fn migrate_to_current(mut value: Value) -> Result<ProjectData> {
+    let version = project_version(&value);
+    if version == 0 || version > CURRENT_PROJECT_VERSION {
+        return Err(anyhow!("Nieobslugiwana wersja projektu: {version}"));
+    }
+    if version < 2 {
+        migrate_v1_to_v2(&mut value);
+    }
+    if version < 3 {
+        migrate_v2_to_v3(&mut value);
+    }
+    serde_json::from_value(value).map_err(|e| anyhow!("Blad parsowania projektu (v{version}): {e}"))
+}
+
+fn set_version(value: &mut Value, version: u32) {
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("version".to_string(), Value::from(version));
+    }
+}
+
+/// v1 -> v2: klipy nie mialy `source_in` - klipy z biblioteki startuja od 0,
+/// a klipy legacy (input_path) od swojej pozycji na osi czasu
+pub fn migrate_v1_to_v2(value: &mut Value) {
+    if let Some(clips) = value.get_mut("clips").and_then(Value::as_array_mut) {
+        for clip in clips.iter_mut().filter_map(Value::as_object_mut) {
+            if clip.contains_key("source_in") {
+                continue;
+            }
+            let from_library = clip.get("asset_id").is_some_and(|id| !id.is_null());
+            let source_in = if from_library {
+                Value::from(0.0)
+            } else {
+                clip.get("start").cloned().unwrap_or(Value::from(0.0))
+            };
+            clip.insert("source_in".to_string(), source_in);
+        }
+    }
+    set_version(value, 2);
+}
+
+/// v2 -> v3: pola `sharpness` i `denoise` klipu przechodza na liste `effects`
+/// (w dotychczasowej kolejnosci filtrow: najpierw wyostrzenie, potem odszumianie)
+pub fn migrate_v2_to_v3(value: &mut Value) {
+    if let Some(clips) = value.get_mut("clips").and_then(Value::as_array_mut) {
+        for clip in clips.iter_mut().filter_map(Value::as_object_mut) {
+            let mut effects = Vec::new();
+            if let Some(sharpness) = clip.remove("sharpness").and_then(|v| v.as_f64())
+                && sharpness != 0.0
+            {
+                effects.push(serde_json::json!({ "Sharpness": sharpness }));
+            }
+            if let Some(denoise) = clip.remove("denoise").filter(|v| !v.is_null()) {
+                effects.push(serde_json::json!({ "Denoise": denoise }));
+            }
+            clip.entry("effects").or_insert(Value::Array(effects));
+        }
+    }
+    set_version(value, 3);
+}
@@ -0,0 +1,116 @@
+// scripting.rs - Skrypty uzytkownika (Lua / JavaScript) jako zewnetrzny proces z protokolem JSON
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::types::Clip;
+
+/// Limit czasu skryptu; po nim proces jest zabijany
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Odpowiedz skryptu na stdout: {"clips": [...], "status": "..."}
+#[derive(Deserialize)]
+pub struct ScriptResponse {
+    pub clips: Vec<Clip>,
+    #[serde(default)]
+    pub status: String,
+}
+
+/// Interpreter dobierany po rozszerzeniu pliku
+fn interpreter_command(script_path: &Path) -> Result<Command> {
+    let ext = script_path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    let mut command = match ext.as_str() {
+        "js" => {
+            let mut c = Command::new("deno");
+            c.args(["run", "--quiet"]);
+            c
+        }
+        "lua" => Command::new("lua"),
+        _ => return Err(anyhow!("Nieobslugiwany typ skryptu: .{ext} (dozwolone .js, .lua)")),
+    };
+    command.arg(script_path);
+    Ok(command)
+}
+
+/// Uruchamia skrypt: JSON projektu na stdin, zwraca to co skrypt wypisal na stdout.
+/// stdin, stdout i stderr obslugiwane w osobnych watkach (pelny potok nie blokuje skryptu),
+/// po SCRIPT_TIMEOUT proces jest zabijany.
+pub fn run_script(script_path: &Path, project_json: &str) -> Result<String> {
+    let mut child = interpreter_command(script_path)?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Nie mozna uruchomic interpretera skryptu (deno / lua w PATH?)")?;
+    let writer = child.stdin.take().map(|mut stdin| {
+        let json = project_json.to_owned();
+        // Skrypt moze nie czytac stdin - blad zapisu (zamkniety potok) nie jest bledem skryptu
+        thread::spawn(move || {
+            let _ = stdin.write_all(json.as_bytes());
+        })
+    });
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut data = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut data);
+            }
+            data
+        })
+    };
+    let stdout = read_pipe(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = read_pipe(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("Blad oczekiwania na skrypt")? {
+            break status;
+        }
+        if started.elapsed() > SCRIPT_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("Skrypt przekroczyl limit czasu ({} s)", SCRIPT_TIMEOUT.as_secs()));
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() {
+        return Err(anyhow!("Skrypt zakonczyl sie bledem: {}", String::from_utf8_lossy(&stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// Odrzuca odpowiedz z klipami, ktorych nie da sie wstawic do projektu
+/// (nieznany asset, pusty lub odwrocony zakres, czasy spoza osi)
+pub fn validate_script_clips(clips: &[Clip], library_len: usize) -> Result<()> {
+    for (i, clip) in clips.iter().enumerate() {
+        let n = i + 1;
+        if let Some(id) = clip.asset_id
+            && id >= library_len
+        {
+            return Err(anyhow!("Klip {n}: nieznany asset_id {id}"));
+        }
+        if ![clip.start, clip.end, clip.source_in].iter().all(|t| t.is_finite()) {
+            return Err(anyhow!("Klip {n}: nieprawidlowy czas"));
+        }
+        if clip.end <= clip.start {
+            return Err(anyhow!("Klip {n}: koniec ({:.3}) nie jest po poczatku ({:.3})", clip.end, clip.start));
+        }
+        if clip.start < 0.0 || clip.source_in < 0.0 {
+            return Err(anyhow!("Klip {n}: ujemny czas"));
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_script_response(output: &str) -> Result<ScriptResponse> {
+    serde_json::from_str(output.trim()).context("Nieprawidlowa odpowiedz JSON skryptu")
+}
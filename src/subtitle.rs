@@ -0,0 +1,108 @@
+// subtitle.rs - Napisy SRT: wczytywanie, zapis i przeliczanie czasow po montazu
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::ffmpeg::timeline_to_output;
+use crate::types::Clip;
+
+/// Pojedynczy napis (czasy w sekundach)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleEntry {
+    pub index: usize,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// "HH:MM:SS,mmm" -> sekundy
+fn parse_srt_time(s: &str) -> Option<f32> {
+    let (hms, millis) = s.trim().split_once([',', '.'])?;
+    let mut parts = hms.split(':');
+    let h: f32 = parts.next()?.parse().ok()?;
+    let m: f32 = parts.next()?.parse().ok()?;
+    let sec: f32 = parts.next()?.parse().ok()?;
+    let ms: f32 = millis.parse().ok()?;
+    Some(h * 3600.0 + m * 60.0 + sec + ms / 1000.0)
+}
+
+/// Sekundy -> "HH:MM:SS,mmm"
+fn format_srt_time(secs: f32) -> String {
+    let total_ms = (secs.max(0.0) * 1000.0).round() as u64;
+    let (h, rest) = (total_ms / 3_600_000, total_ms % 3_600_000);
+    let (m, rest) = (rest / 60_000, rest % 60_000);
+    format!("{:02}:{:02}:{:02},{:03}", h, m, rest / 1000, rest % 1000)
+}
+
+/// Parsuje zawartosc pliku SRT; bloki bez poprawnego czasu sa pomijane
+pub fn parse_srt(content: &str) -> Vec<SubtitleEntry> {
+    let normalized = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut entries = Vec::new();
+    for block in normalized.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| l.trim().is_empty());
+        let Some(first) = lines.next() else { continue };
+        // Numer bloku jest opcjonalny w praktyce - czasem pierwsza linia to od razu czas
+        let timing = if first.contains("-->") { first } else { lines.next().unwrap_or_default() };
+        let Some((start, end)) = timing.split_once("-->") else { continue };
+        let (Some(start), Some(end)) = (parse_srt_time(start), parse_srt_time(end)) else { continue };
+        let text = lines.collect::<Vec<_>>().join("\n");
+        entries.push(SubtitleEntry { index: entries.len() + 1, start, end, text });
+    }
+    entries
+}
+
+pub fn load_srt(path: &Path) -> Result<Vec<SubtitleEntry>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Blad odczytu SRT {}: {e}", path.display()))?;
+    Ok(parse_srt(&content))
+}
+
+pub fn format_srt(subtitles: &[SubtitleEntry]) -> String {
+    subtitles
+        .iter()
+        .map(|s| format!("{}\n{} --> {}\n{}\n", s.index, format_srt_time(s.start), format_srt_time(s.end), s.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn write_srt(path: &Path, subtitles: &[SubtitleEntry]) -> Result<()> {
+    std::fs::write(path, format_srt(subtitles))
+        .map_err(|e| anyhow!("Blad zapisu SRT {}: {e}", path.display()))
+}
+
+/// Przelicza czasy napisow (czas zrodla) na pozycje w wyrenderowanym filmie.
+/// `clips` to klipy V1 jak w render_video (z przejsciami xfade); napisy z wycietych
+/// fragmentow sa odrzucane, a napisy przechodzace przez ciecie dzielone na czesci.
+/// Napisy dotycza glownego wejscia - klipy z biblioteki (i wypelnienia luk) sa pomijane.
+pub fn retime_subtitles(subtitles: &[SubtitleEntry], clips: &[Clip]) -> Vec<SubtitleEntry> {
+    let mut out: Vec<SubtitleEntry> = Vec::new();
+    for clip in clips.iter().filter(|c| (c.video_enabled || c.audio_enabled) && c.asset_id.is_none()) {
+        let output_offset = timeline_to_output(clips, clip.start);
+        let (src_in, src_out) = (clip.source_in.max(0.0), clip.source_out());
+        for sub in subtitles {
+            let start = sub.start.max(src_in);
+            let end = sub.end.min(src_out);
+            if end - start <= 0.001 {
+                continue;
+            }
+            // Rampa i odtwarzanie wstecz: liczymy oba konce, wstecz zamieniaja sie miejscami
+            let a = clip.output_time_at_source(start - src_in);
+            let b = clip.output_time_at_source(end - src_in);
+            let (out_start, out_end) = (output_offset + a.min(b), output_offset + a.max(b));
+            // Kawalki tego samego napisu stykajace sie w wyjsciu sklejamy z powrotem
+            if let Some(prev) = out.last_mut()
+                && prev.text == sub.text
+                && (prev.end - out_start).abs() < 0.01
+            {
+                prev.end = out_end;
+                continue;
+            }
+            out.push(SubtitleEntry { index: 0, start: out_start, end: out_end, text: sub.text.clone() });
+        }
+    }
+    out.sort_by(|a, b| a.start.total_cmp(&b.start));
+    for (i, entry) in out.iter_mut().enumerate() {
+        entry.index = i + 1;
+    }
+    out
+}
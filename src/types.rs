@@ -1,5 +1,10 @@
 // types.rs - Struktury danych i enumy
+use eframe::egui;
 use serde::{Deserialize, Serialize};
+use crate::subtitle::SubtitleEntry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Language {
@@ -19,6 +24,66 @@ fn default_true() -> bool {
     true
 }
 
+fn default_one() -> f32 {
+    1.0
+}
+
+/// Kolorowa etykieta klipu
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ClipColor {
+    #[default]
+    None,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Pink,
+}
+
+impl ClipColor {
+    pub const ALL: [ClipColor; 8] = [
+        ClipColor::None,
+        ClipColor::Red,
+        ClipColor::Orange,
+        ClipColor::Yellow,
+        ClipColor::Green,
+        ClipColor::Blue,
+        ClipColor::Purple,
+        ClipColor::Pink,
+    ];
+
+    /// Kolor na timeline (None = domyślny kolor klipu)
+    pub fn color32(&self) -> Option<egui::Color32> {
+        match self {
+            ClipColor::None => None,
+            ClipColor::Red => Some(egui::Color32::from_rgb(200, 70, 70)),
+            ClipColor::Orange => Some(egui::Color32::from_rgb(220, 130, 50)),
+            ClipColor::Yellow => Some(egui::Color32::from_rgb(210, 190, 60)),
+            ClipColor::Green => Some(egui::Color32::from_rgb(70, 170, 80)),
+            ClipColor::Blue => Some(egui::Color32::from_rgb(70, 110, 210)),
+            ClipColor::Purple => Some(egui::Color32::from_rgb(140, 80, 190)),
+            ClipColor::Pink => Some(egui::Color32::from_rgb(220, 110, 170)),
+        }
+    }
+}
+
+impl std::fmt::Display for ClipColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipColor::None => write!(f, "None"),
+            ClipColor::Red => write!(f, "Red"),
+            ClipColor::Orange => write!(f, "Orange"),
+            ClipColor::Yellow => write!(f, "Yellow"),
+            ClipColor::Green => write!(f, "Green"),
+            ClipColor::Blue => write!(f, "Blue"),
+            ClipColor::Purple => write!(f, "Purple"),
+            ClipColor::Pink => write!(f, "Pink"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MediaType {
     Video,
@@ -36,6 +101,79 @@ pub struct MediaAsset {
     // No texture here to keep it serializable easily, handle thumbs in App
 }
 
+/// Szczegóły strumieni pliku źródłowego z ffprobe (panel Media Info)
+#[derive(Clone, Default)]
+pub struct MediaInfo {
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub audio_sample_rate: u32,
+    pub audio_channels: u16,
+    pub video_bitrate_kbps: u32,
+    pub audio_bitrate_kbps: u32,
+    pub container_format: String,
+}
+
+/// Preset odszumiania (filtr hqdn3d)
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DenoisePreset {
+    Mild,
+    Medium,
+    Strong,
+    Custom(f32), // luma_spatial 0.0-16.0, reszta proporcjonalnie
+}
+
+impl DenoisePreset {
+    /// Parametry hqdn3d: luma_spatial, chroma_spatial, luma_tmp, chroma_tmp
+    pub fn hqdn3d_params(&self) -> (f32, f32, f32, f32) {
+        match self {
+            DenoisePreset::Mild => (2.0, 1.5, 2.0, 1.5),
+            DenoisePreset::Medium => (4.0, 3.0, 4.0, 3.0),
+            DenoisePreset::Strong => (8.0, 6.0, 8.0, 6.0),
+            DenoisePreset::Custom(luma) => (*luma, luma * 0.75, *luma, luma * 0.75),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DenoisePreset::Mild => "Mild",
+            DenoisePreset::Medium => "Medium",
+            DenoisePreset::Strong => "Strong",
+            DenoisePreset::Custom(_) => "Custom",
+        }
+    }
+}
+
+/// Efekt obrazu klipu; kolejność na liście `Clip::effects` to kolejność filtrów w łańcuchu
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ClipEffect {
+    /// Wyostrzenie (> 0, unsharp) lub rozmycie (< 0, boxblur), zakres -5.0..5.0
+    Sharpness(f32),
+    Denoise(DenoisePreset),
+    /// Korekcja kolorów (filtr eq); wartości neutralne: 0.0 / 1.0 / 1.0
+    ColorCorrection { brightness: f32, contrast: f32, saturation: f32 },
+    /// Winieta - kąt w radianach (0.0-PI/2), większy = mocniejsze przyciemnienie rogów
+    Vignette(f32),
+}
+
+impl ClipEffect {
+    /// Efekty dostępne w menu "Add effect" z wartościami startowymi
+    pub const ALL: [ClipEffect; 4] = [
+        ClipEffect::ColorCorrection { brightness: 0.0, contrast: 1.0, saturation: 1.0 },
+        ClipEffect::Sharpness(1.0),
+        ClipEffect::Denoise(DenoisePreset::Medium),
+        ClipEffect::Vignette(std::f32::consts::PI / 5.0), // domyślna wartość filtra vignette
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipEffect::Sharpness(_) => "Sharpness",
+            ClipEffect::Denoise(_) => "Denoise",
+            ClipEffect::ColorCorrection { .. } => "Color Correction",
+            ClipEffect::Vignette(_) => "Vignette",
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Clip {
     pub start: f32,
@@ -52,10 +190,296 @@ pub struct Clip {
     pub video_enabled: bool,
     #[serde(default = "default_true")]
     pub audio_enabled: bool,
+    #[serde(default)]
+    pub source_in: f32, // Punkt wejścia w materiale źródłowym (sekundy)
+    #[serde(default = "default_one")]
+    pub volume: f32,
+    #[serde(default = "default_one")]
+    pub speed: f32,
+    #[serde(default)]
+    pub color_label: ClipColor,
+    #[serde(default)]
+    pub notes: String,
+    /// Stos efektów obrazu, nakładanych w kolejności listy
+    #[serde(default)]
+    pub effects: Vec<ClipEffect>,
+    /// Odwrocenie polaryzacji audio (mikrofony w przeciwfazie)
+    #[serde(default)]
+    pub audio_phase_inverted: bool,
+    /// Sciezka wideo (0 = V1, baza montazu; wyzsze nakladane overlayem)
+    #[serde(default)]
+    pub track_index: u32,
+    /// Przenikanie audio do nastepnego klipu (s): dzwiek ciagnie sie za `end`
+    /// i miesza z poczatkiem kolejnego klipu (gdy ma fade_in), obraz tnie sie ostro
+    #[serde(default)]
+    pub audio_crossfade_out: f32,
+    /// Pozycja nakładki w px kadru (czas od początku klipu, x, y); tylko dla track_index > 0
+    #[serde(default)]
+    pub position_keyframes: Vec<(f32, f32, f32)>,
+    /// Obwiednia głośności: (czas od początku klipu, wzmocnienie), posortowane po czasie
+    #[serde(default)]
+    pub volume_keyframes: Vec<(f32, f32)>,
+    /// Panorama audio: -1.0 = lewo, 0.0 = środek, 1.0 = prawo
+    #[serde(default)]
+    pub audio_pan: f32,
+    /// Własne filtry ffmpeg doklejane na końcu łańcucha (-vf / -af)
+    #[serde(default)]
+    pub custom_vf: Option<String>,
+    #[serde(default)]
+    pub custom_af: Option<String>,
+    /// Liniowa zmiana prędkości (początkowa, końcowa); None = stała `speed`
+    #[serde(default)]
+    pub speed_ramp: Option<(f32, f32)>,
+    /// Grupa klipów przesuwanych i usuwanych razem (nazwy w `ProjectData::groups`)
+    #[serde(default)]
+    pub group_id: Option<u32>,
+    /// Przejście obrazu do następnego klipu V1 (xfade w renderze)
+    #[serde(default)]
+    pub transition_out: Transition,
+    /// Krycie nakładki (0.0-1.0); tylko dla track_index > 0
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    /// Plik audio zastępujący dźwięk klipu (ten sam punkt wejścia `source_in` i długość co obraz)
+    #[serde(default)]
+    pub audio_override: Option<String>,
+    /// Odtwarzanie wstecz (obraz i dźwięk)
+    #[serde(default)]
+    pub reversed: bool,
+    /// Korektor audio; pusta lista = bez filtra equalizer
+    #[serde(default)]
+    pub eq_bands: Vec<EqBand>,
+    /// Niezależne przycięcie obrazu i dźwięku, gdy klip jest rozłączony (`linked == false`):
+    /// przesunięcie od `start` w czasie osi (s); None = krawędź klipu
+    #[serde(default)]
+    pub video_start: Option<f32>,
+    #[serde(default)]
+    pub video_end: Option<f32>,
+    #[serde(default)]
+    pub audio_start: Option<f32>,
+    #[serde(default)]
+    pub audio_end: Option<f32>,
+}
+
+/// Pasmo korektora: częstotliwość środkowa, wzmocnienie i szerokość w oktawach
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub frequency_hz: f32,
+    pub gain_db: f32,
+    pub bandwidth: f32,
+}
+
+impl EqBand {
+    /// Pasma graficznego korektora w panelu właściwości klipu
+    pub const DEFAULT_FREQUENCIES: [f32; 5] = [80.0, 400.0, 2000.0, 8000.0, 16000.0];
+    pub const MAX_GAIN_DB: f32 = 12.0;
+
+    pub fn flat(frequency_hz: f32) -> Self {
+        Self { frequency_hz, gain_db: 0.0, bandwidth: 1.0 }
+    }
+
+    /// Przybliżona odpowiedź pasma (dB) dla częstotliwości `hz` - do rysowania krzywej
+    pub fn response_db(&self, hz: f32) -> f32 {
+        let octaves = (hz / self.frequency_hz).log2() / self.bandwidth.max(0.1);
+        self.gain_db * (-2.0 * octaves * octaves).exp()
+    }
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+impl Clip {
+    /// Nowy klip z domyślnymi ustawieniami; klipy legacy (input_path) startują w źródle od `start`
+    pub fn new(asset_id: Option<usize>, start: f32, end: f32) -> Self {
+        Self {
+            start,
+            end,
+            asset_id,
+            fade_in: 0.0,
+            fade_out: 0.0,
+            linked: true,
+            video_enabled: true,
+            audio_enabled: true,
+            source_in: if asset_id.is_some() { 0.0 } else { start },
+            volume: 1.0,
+            speed: 1.0,
+            color_label: ClipColor::None,
+            notes: String::new(),
+            volume_keyframes: Vec::new(),
+            audio_pan: 0.0,
+            custom_vf: None,
+            custom_af: None,
+            speed_ramp: None,
+            effects: Vec::new(),
+            audio_phase_inverted: false,
+            track_index: 0,
+            audio_crossfade_out: 0.0,
+            position_keyframes: Vec::new(),
+            group_id: None,
+            transition_out: Transition::default(),
+            opacity: 1.0,
+            audio_override: None,
+            reversed: false,
+            eq_bands: Vec::new(),
+            video_start: None,
+            video_end: None,
+            audio_start: None,
+            audio_end: None,
+        }
+    }
+
+    /// Zakres obrazu na osi czasu (od, do); przy połączonym klipie cały klip
+    pub fn video_range(&self) -> (f32, f32) {
+        let (a, b) = self.track_trim(self.video_start, self.video_end).unwrap_or((0.0, self.output_duration()));
+        (self.start + a, self.start + b)
+    }
+
+    /// Zakres dźwięku na osi czasu (od, do); przy połączonym klipie cały klip
+    pub fn audio_range(&self) -> (f32, f32) {
+        let (a, b) = self.track_trim(self.audio_start, self.audio_end).unwrap_or((0.0, self.output_duration()));
+        (self.start + a, self.start + b)
+    }
+
+    /// Przycięcie obrazu w czasie wyjściowym klipu, jeśli klip jest rozłączony i obraz przycięty
+    pub fn video_trim(&self) -> Option<(f32, f32)> {
+        self.track_trim(self.video_start, self.video_end)
+    }
+
+    /// Przycięcie dźwięku w czasie wyjściowym klipu, jeśli klip jest rozłączony i dźwięk przycięty
+    pub fn audio_trim(&self) -> Option<(f32, f32)> {
+        self.track_trim(self.audio_start, self.audio_end)
+    }
+
+    fn track_trim(&self, start: Option<f32>, end: Option<f32>) -> Option<(f32, f32)> {
+        if self.linked || (start.is_none() && end.is_none()) {
+            return None;
+        }
+        let duration = self.output_duration();
+        let a = start.unwrap_or(0.0).clamp(0.0, duration);
+        let b = end.unwrap_or(duration).clamp(a, duration);
+        Some((a, b))
+    }
+
+    /// Przesuwa niezależne przycięcia ścieżek o `by` s wstecz (prawa część po podziale klipu)
+    pub fn shift_track_trims(&mut self, by: f32) {
+        for t in [&mut self.video_start, &mut self.video_end, &mut self.audio_start, &mut self.audio_end].into_iter().flatten() {
+            *t -= by;
+        }
+    }
+
+    /// Czyści niezależne przycięcia (po ponownym połączeniu obrazu i dźwięku)
+    pub fn clear_track_trims(&mut self) {
+        self.video_start = None;
+        self.video_end = None;
+        self.audio_start = None;
+        self.audio_end = None;
+    }
+
+    /// Punkt wyjścia w materiale źródłowym
+    pub fn source_out(&self) -> f32 {
+        self.source_in + (self.end - self.start)
+    }
+
+    /// Długość klipu w gotowym filmie (po zmianie prędkości)
+    pub fn output_duration(&self) -> f32 {
+        let span = (self.end - self.start).max(0.0);
+        match self.active_speed_ramp() {
+            // Całka z 1/v(x) dla prędkości liniowej w czasie źródła
+            Some((s0, s1)) => span * (s1 / s0).ln() / (s1 - s0),
+            None => span / self.speed.max(0.01),
+        }
+    }
+
+    /// Koniec klipu na osi czasu wg długości wyjściowej (szerokość na timeline uwzględnia prędkość)
+    pub fn timeline_end(&self) -> f32 {
+        self.start + self.output_duration()
+    }
+
+    /// Rampa prędkości, jeśli faktycznie zmienia prędkość (s0 != s1)
+    pub fn active_speed_ramp(&self) -> Option<(f32, f32)> {
+        self.speed_ramp
+            .map(|(s0, s1)| (s0.max(0.01), s1.max(0.01)))
+            .filter(|(s0, s1)| (s1 - s0).abs() > 0.001)
+    }
+
+    /// Czas wyjściowy (s od początku klipu) po odtworzeniu `played` s źródła
+    /// w kolejności odtwarzania; uwzględnia prędkość i rampę
+    pub fn output_time_at(&self, played: f32) -> f32 {
+        let span = (self.end - self.start).max(0.001);
+        match self.active_speed_ramp() {
+            // Całka z 1/v(x) od 0 do `played`
+            Some((s0, s1)) => span / (s1 - s0) * (1.0 + (s1 - s0) * played / (s0 * span)).ln(),
+            None => played / self.speed.max(0.01),
+        }
+    }
+
+    /// Odwrotność `output_time_at`: ile klipu (w czasie źródła) gra po `t` s wyjścia
+    pub fn played_at_output(&self, t: f32) -> f32 {
+        let span = (self.end - self.start).max(0.001);
+        let played = match self.active_speed_ramp() {
+            Some((s0, s1)) => s0 * span / (s1 - s0) * (((s1 - s0) * t / span).exp() - 1.0),
+            None => t * self.speed.max(0.01),
+        };
+        played.clamp(0.0, span)
+    }
+
+    /// Czas wyjściowy, w którym gra przesunięcie źródła `offset` (od `source_in`);
+    /// jak `output_time_at`, ale z odtwarzaniem wstecz
+    pub fn output_time_at_source(&self, offset: f32) -> f32 {
+        let span = (self.end - self.start).max(0.0);
+        let played = if self.reversed { span - offset } else { offset };
+        self.output_time_at(played.clamp(0.0, span))
+    }
+
+    /// Wzmocnienie z obwiedni w czasie `t` od początku klipu (interpolacja liniowa)
+    pub fn envelope_gain(&self, t: f32) -> f32 {
+        let keys = &self.volume_keyframes;
+        let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+            return 1.0;
+        };
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+        for pair in keys.windows(2) {
+            let ((t0, g0), (t1, g1)) = (pair[0], pair[1]);
+            if t >= t0 && t <= t1 {
+                if t1 - t0 <= f32::EPSILON {
+                    return g1;
+                }
+                return g0 + (g1 - g0) * (t - t0) / (t1 - t0);
+            }
+        }
+        last.1
+    }
+
+    /// Pozycja nakładki w czasie `t` od początku klipu (liniowo między punktami, bez punktów = 0,0)
+    pub fn position_at(&self, t: f32) -> (f32, f32) {
+        let keys = &self.position_keyframes;
+        let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+            return (0.0, 0.0);
+        };
+        if t <= first.0 {
+            return (first.1, first.2);
+        }
+        for pair in keys.windows(2) {
+            let ((t0, x0, y0), (t1, x1, y1)) = (pair[0], pair[1]);
+            if t >= t0 && t <= t1 {
+                let f = if t1 - t0 <= f32::EPSILON { 1.0 } else { (t - t0) / (t1 - t0) };
+                return (x0 + (x1 - x0) * f, y0 + (y1 - y0) * f);
+            }
+        }
+        (last.1, last.2)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ProjectData {
+    /// Wersja formatu pliku (migracje w migration.rs)
+    #[serde(default)]
+    pub version: u32,
     pub input_path: String,
     pub output_path: String,
     pub clips: Vec<Clip>,
@@ -66,6 +490,807 @@ pub struct ProjectData {
     pub video_fps: f32,
     #[serde(default)]
     pub media_library: Vec<MediaAsset>,
+    #[serde(default)]
+    pub subtitles: Vec<SubtitleEntry>,
+    /// Nazwy grup klipów (klucz = `Clip::group_id`)
+    #[serde(default)]
+    pub groups: HashMap<u32, String>,
+    /// Znaczniki rozdziałów na osi czasu (posortowane po czasie)
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+    /// Znak wodny renderu (zapisywany z projektem, żeby archiwum zabrało obraz)
+    #[serde(default)]
+    pub watermark: Option<WatermarkSettings>,
+}
+
+/// Znacznik rozdziału: czas na osi + tytuł (eksport jako rozdziały WebVTT)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Marker {
+    pub time: f32,
+    pub title: String,
+}
+
+/// Szablon nazwy pliku wyjściowego, np. "renders/{project}_{date}_{seq:03}.mp4"
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OutputTemplate {
+    pub template: String,
+}
+
+impl Default for OutputTemplate {
+    fn default() -> Self {
+        Self {
+            template: "{project}_{date}_{seq:03}.mp4".to_string(),
+        }
+    }
+}
+
+/// Pozycja nakładki timecode w renderze
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TimecodePosition {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl std::fmt::Display for TimecodePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimecodePosition::TopLeft => write!(f, "Top Left"),
+            TimecodePosition::TopRight => write!(f, "Top Right"),
+            TimecodePosition::BottomLeft => write!(f, "Bottom Left"),
+            TimecodePosition::BottomRight => write!(f, "Bottom Right"),
+            TimecodePosition::Center => write!(f, "Center"),
+        }
+    }
+}
+
+/// Rozdzielczość wyjściowa renderu
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OutputResolution {
+    #[default]
+    Source,
+    Hd720,
+    FullHd1080,
+    Uhd2160,
+    Vertical1080x1920,
+    Square1080,
+}
+
+impl OutputResolution {
+    pub const ALL: [OutputResolution; 6] = [
+        OutputResolution::Source,
+        OutputResolution::Hd720,
+        OutputResolution::FullHd1080,
+        OutputResolution::Uhd2160,
+        OutputResolution::Vertical1080x1920,
+        OutputResolution::Square1080,
+    ];
+
+    /// Wymiary wyjścia (None = jak źródło)
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        match self {
+            OutputResolution::Source => None,
+            OutputResolution::Hd720 => Some((1280, 720)),
+            OutputResolution::FullHd1080 => Some((1920, 1080)),
+            OutputResolution::Uhd2160 => Some((3840, 2160)),
+            OutputResolution::Vertical1080x1920 => Some((1080, 1920)),
+            OutputResolution::Square1080 => Some((1080, 1080)),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.dimensions() {
+            None => write!(f, "Source"),
+            Some((w, h)) => write!(f, "{w}x{h}"),
+        }
+    }
+}
+
+/// Dopasowanie obrazu gdy proporcje wyjścia różnią się od źródła
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AspectRatioMode {
+    Stretch,
+    #[default]
+    Letterbox,
+    Pillarbox,
+    Crop,
+}
+
+impl AspectRatioMode {
+    pub const ALL: [AspectRatioMode; 4] = [
+        AspectRatioMode::Stretch,
+        AspectRatioMode::Letterbox,
+        AspectRatioMode::Pillarbox,
+        AspectRatioMode::Crop,
+    ];
+}
+
+impl std::fmt::Display for AspectRatioMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AspectRatioMode::Stretch => write!(f, "Stretch"),
+            AspectRatioMode::Letterbox => write!(f, "Letterbox"),
+            AspectRatioMode::Pillarbox => write!(f, "Pillarbox"),
+            AspectRatioMode::Crop => write!(f, "Crop"),
+        }
+    }
+}
+
+/// Rodzaj przejścia między klipami (nazwa filtra xfade)
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TransitionKind {
+    #[default]
+    Cut,
+    Dissolve,
+    WipeLeft,
+    WipeRight,
+    WipeUp,
+    WipeDown,
+    FadeToBlack,
+}
+
+impl TransitionKind {
+    pub const ALL: [TransitionKind; 7] = [
+        TransitionKind::Cut,
+        TransitionKind::Dissolve,
+        TransitionKind::WipeLeft,
+        TransitionKind::WipeRight,
+        TransitionKind::WipeUp,
+        TransitionKind::WipeDown,
+        TransitionKind::FadeToBlack,
+    ];
+
+    /// Nazwa przejścia w filtrze xfade; None = ostre cięcie
+    pub fn xfade_name(self) -> Option<&'static str> {
+        match self {
+            TransitionKind::Cut => None,
+            TransitionKind::Dissolve => Some("fade"),
+            TransitionKind::WipeLeft => Some("wipeleft"),
+            TransitionKind::WipeRight => Some("wiperight"),
+            TransitionKind::WipeUp => Some("wipeup"),
+            TransitionKind::WipeDown => Some("wipedown"),
+            TransitionKind::FadeToBlack => Some("fadeblack"),
+        }
+    }
+}
+
+impl std::fmt::Display for TransitionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionKind::Cut => write!(f, "Cut"),
+            TransitionKind::Dissolve => write!(f, "Dissolve"),
+            TransitionKind::WipeLeft => write!(f, "Wipe Left"),
+            TransitionKind::WipeRight => write!(f, "Wipe Right"),
+            TransitionKind::WipeUp => write!(f, "Wipe Up"),
+            TransitionKind::WipeDown => write!(f, "Wipe Down"),
+            TransitionKind::FadeToBlack => write!(f, "Fade to Black"),
+        }
+    }
+}
+
+/// Przejście na końcu klipu; klipy nachodzą na siebie o `duration` (film się skraca)
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct Transition {
+    pub duration: f32,
+    pub kind: TransitionKind,
+}
+
+impl Transition {
+    pub fn is_active(&self) -> bool {
+        self.kind != TransitionKind::Cut && self.duration > 0.0
+    }
+}
+
+/// Algorytm rozciągania audio przy zmianie prędkości klipu
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StretchAlgorithm {
+    #[default]
+    Atempo,
+    Rubberband,
+}
+
+impl std::fmt::Display for StretchAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StretchAlgorithm::Atempo => write!(f, "atempo"),
+            StretchAlgorithm::Rubberband => write!(f, "Rubberband"),
+        }
+    }
+}
+
+/// Ustawienia renderu przekazywane do render_video
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RenderSettings {
+    pub burn_timecode: bool,
+    pub timecode_position: TimecodePosition,
+    #[serde(default)]
+    pub output_resolution: OutputResolution,
+    #[serde(default)]
+    pub aspect_mode: AspectRatioMode,
+    /// Zapis {output}.srt z napisami przeliczonymi na os czasu po montazu
+    #[serde(default)]
+    pub write_srt: bool,
+    #[serde(default)]
+    pub stretch_algorithm: StretchAlgorithm,
+    /// Skok do I-klatki (-noaccurate_seek, szybciej, ciecie do GOP wczesniej);
+    /// domyslnie wylaczony - segmenty zaczynaja sie dokladnie co do klatki
+    #[serde(default)]
+    pub force_iframe_seek: bool,
+    #[serde(default)]
+    pub multi_output: MultiOutput,
+    /// Jakosc libx264 (nizej = lepiej, wiekszy plik)
+    #[serde(default = "default_crf")]
+    pub crf: u8,
+    #[serde(default = "default_audio_bitrate")]
+    pub audio_bitrate_kbps: u32,
+    /// Kodowanie dwuprzebiegowe segmentow ze stalym bitrate zamiast CRF
+    #[serde(default)]
+    pub two_pass: bool,
+    #[serde(default = "default_target_bitrate")]
+    pub target_bitrate_kbps: u32,
+    /// Zapis {output}.vtt z rozdziałami ze znaczników (gdy są znaczniki)
+    #[serde(default)]
+    pub write_webvtt_chapters: bool,
+    /// Ścieżki w pliku wyjściowym (sam obraz / sam dźwięk / oba)
+    #[serde(default)]
+    pub export_tracks: TrackType,
+    /// Obraz i dźwięk jako osobne pliki: {output}.video.mp4 i {output}.audio.aac
+    #[serde(default)]
+    pub separate_track_files: bool,
+    /// Luki między klipami V1 wypełniane w renderze czarnym obrazem z ciszą
+    #[serde(default)]
+    pub auto_fill_gaps: bool,
+    /// Logo / znak wodny nakładany na gotowy film
+    #[serde(default)]
+    pub watermark: Option<WatermarkSettings>,
+    /// Render tylko fragmentu [mark in, mark out] (`render_range`, ustawiany przy starcie renderu)
+    #[serde(default)]
+    pub render_range_only: bool,
+    #[serde(default)]
+    pub render_range: Option<(f32, f32)>,
+    /// Kodeki dodatkowego wyjścia; Auto = wg rozszerzenia pliku
+    #[serde(default)]
+    pub output_codec: OutputCodec,
+}
+
+/// Para kodeków (wideo / audio) dodatkowego wyjścia
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputCodec {
+    #[default]
+    Auto,
+    H264,
+    Vp9,
+    ProRes,
+}
+
+impl OutputCodec {
+    pub const ALL: [OutputCodec; 4] = [OutputCodec::Auto, OutputCodec::H264, OutputCodec::Vp9, OutputCodec::ProRes];
+
+    /// Kodek dla Auto: .webm -> VP9, .mov -> ProRes, reszta H.264
+    pub fn resolve(self, path: &str) -> OutputCodec {
+        if self != OutputCodec::Auto {
+            return self;
+        }
+        let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        match ext.as_str() {
+            "webm" => OutputCodec::Vp9,
+            "mov" => OutputCodec::ProRes,
+            _ => OutputCodec::H264,
+        }
+    }
+}
+
+impl std::fmt::Display for OutputCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputCodec::Auto => write!(f, "Auto"),
+            OutputCodec::H264 => write!(f, "H.264 / AAC"),
+            OutputCodec::Vp9 => write!(f, "VP9 / Opus"),
+            OutputCodec::ProRes => write!(f, "ProRes / PCM"),
+        }
+    }
+}
+
+/// Położenie znaku wodnego; Custom = lewy górny róg w px kadru
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Custom(u32, u32),
+}
+
+impl WatermarkPosition {
+    pub const CORNERS: [WatermarkPosition; 4] = [
+        WatermarkPosition::TopLeft,
+        WatermarkPosition::TopRight,
+        WatermarkPosition::BottomLeft,
+        WatermarkPosition::BottomRight,
+    ];
+}
+
+impl std::fmt::Display for WatermarkPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatermarkPosition::TopLeft => write!(f, "Top Left"),
+            WatermarkPosition::TopRight => write!(f, "Top Right"),
+            WatermarkPosition::BottomLeft => write!(f, "Bottom Left"),
+            WatermarkPosition::BottomRight => write!(f, "Bottom Right"),
+            WatermarkPosition::Custom(..) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Obraz znaku wodnego: krycie 0.0-1.0, skala względem własnej szerokości obrazu
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatermarkSettings {
+    pub path: PathBuf,
+    pub position: WatermarkPosition,
+    pub opacity: f32,
+    pub scale: f32,
+}
+
+impl WatermarkSettings {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, position: WatermarkPosition::default(), opacity: 0.8, scale: 1.0 }
+    }
+}
+
+impl RenderSettings {
+    /// Zakres osi czasu do renderu, gdy włączono render zakresu i zakres jest poprawny
+    pub fn active_range(&self) -> Option<(f32, f32)> {
+        self.render_range.filter(|(start, end)| self.render_range_only && end > start)
+    }
+
+    /// Przyblizony czas kodowania na sekunde materialu (libx264 -preset fast);
+    /// do szacowania czasu renderu, bez pomiarow
+    pub fn complexity_factor(&self) -> f32 {
+        let mut factor = 0.5;
+        if self.crf < 18 {
+            factor *= 1.2;
+        }
+        if !self.force_iframe_seek {
+            // Domyslny dokladny seek dekoduje klatki od poprzedniej I-klatki do punktu
+            // ciecia; -noaccurate_seek (force_iframe_seek) tego nie robi
+            factor *= 1.3;
+        }
+        if self.burn_timecode || self.output_resolution.dimensions().is_some() {
+            factor *= 1.1;
+        }
+        if self.two_pass {
+            // Pierwszy przebieg (bez audio) kosztuje prawie tyle co drugi
+            factor *= 1.9;
+        }
+        if self.multi_output.enabled {
+            factor *= 1.0 + self.multi_output.formats.len() as f32;
+        }
+        factor
+    }
+}
+
+fn default_crf() -> u8 {
+    18
+}
+
+fn default_audio_bitrate() -> u32 {
+    192
+}
+
+fn default_target_bitrate() -> u32 {
+    4000
+}
+
+/// Dodatkowe pliki wyjsciowe kodowane z gotowego montazu (bez ponownego ciecia)
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct MultiOutput {
+    pub enabled: bool,
+    pub formats: Vec<OutputTarget>,
+}
+
+/// Z `settings` brana jest rozdzielczosc, tryb proporcji, kodek (`output_codec`, Auto = wg rozszerzenia
+/// `path`), CRF i bitrate audio
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct OutputTarget {
+    pub path: String,
+    pub settings: RenderSettings,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            burn_timecode: false,
+            timecode_position: TimecodePosition::default(),
+            output_resolution: OutputResolution::default(),
+            aspect_mode: AspectRatioMode::default(),
+            write_srt: false,
+            stretch_algorithm: StretchAlgorithm::default(),
+            force_iframe_seek: false,
+            multi_output: MultiOutput::default(),
+            crf: default_crf(),
+            audio_bitrate_kbps: default_audio_bitrate(),
+            two_pass: false,
+            target_bitrate_kbps: default_target_bitrate(),
+            write_webvtt_chapters: false,
+            export_tracks: TrackType::Both,
+            separate_track_files: false,
+            auto_fill_gaps: false,
+            watermark: None,
+            render_range_only: false,
+            render_range: None,
+            output_codec: OutputCodec::default(),
+        }
+    }
+}
+
+/// Postęp renderu w tle (parsowany z -progress ffmpeg)
+#[derive(Clone, Default)]
+pub struct RenderProgress {
+    pub percent: f32,
+    pub fps: f32,
+    pub speed: f32,
+    pub eta_secs: f64,
+    pub total_frames: u64,
+    pub frames_done: u64,
+    /// Informacje z przebiegu (np. ktore segmenty skopiowano bez kodowania)
+    pub messages: Vec<String>,
+    /// Status kazdego dodatkowego wyjscia (MultiOutput), w kolejnosci `formats`
+    pub target_status: Vec<String>,
+}
+
+/// Stan pozycji kolejki renderu
+#[derive(Clone, PartialEq)]
+pub enum QueueStatus {
+    Pending,
+    Running,
+    /// Zakończony; czas trwania renderu
+    Done(Duration),
+    Failed(String),
+}
+
+/// Pozycja kolejki: zapisany projekt renderowany z ustawieniami z chwili dodania
+#[derive(Clone)]
+pub struct QueueEntry {
+    pub project_path: PathBuf,
+    pub output_path: PathBuf,
+    pub settings: RenderSettings,
+    pub status: QueueStatus,
+}
+
+/// Kolejka renderu przetwarzana po kolei w wątku w tle
+pub type RenderQueue = Vec<QueueEntry>;
+
+/// Krok historii cofania (Ctrl+Z / Ctrl+Y)
+#[derive(Clone)]
+pub enum UndoAction {
+    /// Pełny stan listy klipów sprzed zmiany struktury
+    Snapshot(Vec<Clip>),
+    /// Zmiana etykiety koloru wielu klipów naraz - bez kopiowania całej listy
+    BatchColorChange {
+        indices: Vec<usize>,
+        old_colors: Vec<ClipColor>,
+        new_color: ClipColor,
+    },
+}
+
+/// Poziom wpisu w logu sesji
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    /// Kolor wpisu; Info jak zwykły tekst motywu (czytelny także w jasnym)
+    pub fn color(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            LogLevel::Info => visuals.text_color(),
+            LogLevel::Warning => egui::Color32::YELLOW,
+            LogLevel::Error => egui::Color32::from_rgb(255, 90, 90),
+        }
+    }
+}
+
+/// Wpis w logu sesji (komunikaty statusu i błędy)
+#[derive(Clone)]
+pub struct LogEntry {
+    pub timestamp: Instant,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+impl LogEntry {
+    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: Instant::now(),
+            level,
+            message: message.into(),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Info, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Error, message)
+    }
+}
+
+/// Rodzaj komunikatu na pasku stanu
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+    /// Trwająca operacja - animowany spinner
+    Working,
+}
+
+impl StatusKind {
+    pub fn color(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            StatusKind::Info => visuals.text_color(),
+            StatusKind::Success => egui::Color32::from_rgb(90, 200, 90),
+            StatusKind::Warning => egui::Color32::from_rgb(255, 160, 40),
+            StatusKind::Error => egui::Color32::from_rgb(255, 90, 90),
+            StatusKind::Working => egui::Color32::from_rgb(80, 160, 255),
+        }
+    }
+
+    /// Poziom wpisu w logu sesji dla tego komunikatu
+    pub fn log_level(&self) -> LogLevel {
+        match self {
+            StatusKind::Warning => LogLevel::Warning,
+            StatusKind::Error => LogLevel::Error,
+            StatusKind::Info | StatusKind::Success | StatusKind::Working => LogLevel::Info,
+        }
+    }
+}
+
+/// Bieżący komunikat paska stanu. Success znika po `SUCCESS_TIMEOUT`, Error wisi do zamknięcia.
+#[derive(Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub kind: StatusKind,
+    pub timestamp: Instant,
+}
+
+impl StatusMessage {
+    pub const SUCCESS_TIMEOUT: Duration = Duration::from_secs(3);
+
+    pub fn new(kind: StatusKind, text: impl Into<String>) -> Self {
+        Self { text: text.into(), kind, timestamp: Instant::now() }
+    }
+
+    pub fn expired(&self) -> bool {
+        self.kind == StatusKind::Success && self.timestamp.elapsed() >= Self::SUCCESS_TIMEOUT
+    }
+}
+
+/// Ustawienia miniatur na timeline (height 0 = zachowaj proporcje)
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailSettings {
+    pub width: u32,
+    pub height: u32,
+    pub count: usize,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            width: 200,
+            height: 0,
+            count: 8,
+        }
+    }
+}
+
+/// Rozmiary okna i paneli z poprzedniej sesji (biblioteka mediów leży w panelu narzędzi)
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowLayout {
+    pub timeline_height: f32,
+    pub tools_width: f32,
+    /// Pozycja okna na ekranie; None = decyduje system (np. Wayland nie podaje pozycji)
+    pub window_pos: Option<[f32; 2]>,
+    pub window_size: [f32; 2],
+}
+
+impl Default for WindowLayout {
+    fn default() -> Self {
+        Self {
+            timeline_height: 240.0,
+            tools_width: 300.0,
+            window_pos: None,
+            window_size: [1280.0, 800.0],
+        }
+    }
+}
+
+/// Ustawienia aplikacji zapisywane między sesjami
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub clip_props_pos: egui::Pos2,
+    pub thumbnails: ThumbnailSettings,
+    /// Limit katalogu cache (waveformy itp.); najstarsze pliki są usuwane po przekroczeniu
+    pub max_cache_size_mb: u64,
+    /// Mnożnik zegara audio przy synchronizacji A/V (korekta dryfu np. WASAPI exclusive)
+    pub audio_clock_correction: f32,
+    /// Podgląd klatek podczas przeciągania playheada
+    pub live_drag_preview: bool,
+    pub ripple_delete: bool,
+    /// Czasy (playhead, znaczniki, cięcia, przesuwanie klipów) zaokrąglane do klatek
+    pub timeline_snap_to_frames: bool,
+    pub preview_quality: PreviewQuality,
+    /// Dopasowanie obrazu do panelu podglądu
+    pub preview_fit: PreviewFit,
+    /// Skalowanie amplitudy waveformów na osi czasu
+    pub waveform_amplitude: WaveformAmplitude,
+    /// Maksymalna liczba klatek/s przy odtwarzaniu (0 = bez limitu)
+    pub playback_fps_limit: u32,
+    /// Nazwa urządzenia wyjściowego audio (puste = domyślne systemowe)
+    pub audio_device_name: String,
+    /// Ścieżki do narzędzi (puste = "ffmpeg" / "ffprobe" z PATH)
+    pub ffmpeg_path: String,
+    pub ffprobe_path: String,
+    /// Co ile sekund zapisywać kopię projektu obok pliku .rev (0 = wyłączone)
+    pub auto_save_interval_secs: u64,
+    /// Podgląd, odtwarzanie i render z lokalnej kopii plików źródłowych (wolne / sieciowe dyski)
+    pub use_cache_copy: bool,
+    pub theme: Theme,
+    /// Kolor akcentu (zaznaczenie w UI i zaznaczone klipy na osi czasu)
+    pub accent_color: egui::Color32,
+    pub keybindings: KeyBindings,
+    pub window_layout: WindowLayout,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            clip_props_pos: egui::pos2(340.0, 120.0),
+            thumbnails: ThumbnailSettings::default(),
+            max_cache_size_mb: 1024,
+            audio_clock_correction: 1.0,
+            live_drag_preview: true,
+            ripple_delete: false,
+            timeline_snap_to_frames: true,
+            preview_quality: PreviewQuality::Standard,
+            preview_fit: PreviewFit::Fit,
+            waveform_amplitude: WaveformAmplitude::Auto,
+            playback_fps_limit: 0,
+            audio_device_name: String::new(),
+            ffmpeg_path: String::new(),
+            ffprobe_path: String::new(),
+            auto_save_interval_secs: 300,
+            use_cache_copy: false,
+            theme: Theme::Dark,
+            accent_color: egui::Color32::from_rgb(80, 170, 255),
+            keybindings: KeyBindings::default(),
+            window_layout: WindowLayout::default(),
+        }
+    }
+}
+
+/// Skrót klawiszowy: klawisz + modyfikatory (Ctrl/Cmd jako `command`)
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KeyCombo {
+    pub key: egui::Key,
+    pub modifiers: egui::Modifiers,
+}
+
+impl KeyCombo {
+    pub const fn new(key: egui::Key, modifiers: egui::Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Czy skrót został wciśnięty w tej klatce (modyfikatory muszą się zgadzać dokładnie)
+    pub fn pressed(&self, input: &egui::InputState) -> bool {
+        if !input.key_pressed(self.key) {
+            return false;
+        }
+        // Znaki jak '+' wymagają Shift na części układów - wtedy Shift nie jest porównywany
+        let mut modifiers = input.modifiers;
+        if matches!(self.key, egui::Key::Plus | egui::Key::Minus | egui::Key::Equals) && !self.modifiers.shift {
+            modifiers.shift = false;
+        }
+        modifiers.matches_exact(self.modifiers)
+    }
+}
+
+impl std::fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shortcut = egui::KeyboardShortcut::new(self.modifiers, self.key);
+        write!(f, "{}", shortcut.format(&egui::ModifierNames::NAMES, cfg!(target_os = "macos")))
+    }
+}
+
+/// Skróty klawiszowe edytora (zapisywane w ustawieniach)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub play_pause: KeyCombo,
+    pub tool_hand: KeyCombo,
+    pub tool_blade: KeyCombo,
+    pub split_clip: KeyCombo,
+    pub mark_in: KeyCombo,
+    pub mark_out: KeyCombo,
+    pub add_marker: KeyCombo,
+    pub zoom_in: KeyCombo,
+    pub zoom_out: KeyCombo,
+    /// Grupowanie zaznaczonych klipów (2+), w przeciwnym razie okno "Go to"
+    pub group_or_goto: KeyCombo,
+    pub toggle_audio: KeyCombo,
+    pub toggle_video: KeyCombo,
+    pub undo: KeyCombo,
+    pub redo: KeyCombo,
+    pub redo_alt: KeyCombo,
+    pub delete_clip: KeyCombo,
+    pub delete_clip_alt: KeyCombo,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use egui::{Key, Modifiers};
+        Self {
+            play_pause: KeyCombo::new(Key::Space, Modifiers::NONE),
+            tool_hand: KeyCombo::new(Key::A, Modifiers::NONE),
+            tool_blade: KeyCombo::new(Key::B, Modifiers::NONE),
+            split_clip: KeyCombo::new(Key::S, Modifiers::NONE),
+            mark_in: KeyCombo::new(Key::I, Modifiers::NONE),
+            mark_out: KeyCombo::new(Key::O, Modifiers::NONE),
+            add_marker: KeyCombo::new(Key::M, Modifiers::NONE),
+            zoom_in: KeyCombo::new(Key::Plus, Modifiers::NONE),
+            zoom_out: KeyCombo::new(Key::Minus, Modifiers::NONE),
+            group_or_goto: KeyCombo::new(Key::G, Modifiers::COMMAND),
+            toggle_audio: KeyCombo::new(Key::U, Modifiers::NONE),
+            toggle_video: KeyCombo::new(Key::V, Modifiers::NONE),
+            undo: KeyCombo::new(Key::Z, Modifiers::COMMAND),
+            redo: KeyCombo::new(Key::Y, Modifiers::COMMAND),
+            redo_alt: KeyCombo::new(Key::Z, Modifiers::COMMAND.plus(Modifiers::SHIFT)),
+            delete_clip: KeyCombo::new(Key::Delete, Modifiers::NONE),
+            delete_clip_alt: KeyCombo::new(Key::Backspace, Modifiers::NONE),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Akcje z nazwami do edytora skrótów (kolejność wyświetlania)
+    pub fn entries_mut(&mut self) -> [(&'static str, &mut KeyCombo); 17] {
+        [
+            ("Play / Pause", &mut self.play_pause),
+            ("Hand tool", &mut self.tool_hand),
+            ("Blade tool", &mut self.tool_blade),
+            ("Split at playhead", &mut self.split_clip),
+            ("Mark in", &mut self.mark_in),
+            ("Mark out", &mut self.mark_out),
+            ("Add marker", &mut self.add_marker),
+            ("Zoom in", &mut self.zoom_in),
+            ("Zoom out", &mut self.zoom_out),
+            ("Group / Go to", &mut self.group_or_goto),
+            ("Toggle audio", &mut self.toggle_audio),
+            ("Toggle video", &mut self.toggle_video),
+            ("Undo", &mut self.undo),
+            ("Redo", &mut self.redo),
+            ("Redo (alt)", &mut self.redo_alt),
+            ("Delete clip", &mut self.delete_clip),
+            ("Delete clip (alt)", &mut self.delete_clip_alt),
+        ]
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -80,8 +1305,133 @@ pub struct FadeDrag {
     pub kind: FadeKind,
 }
 
+/// Rozdzielczosc klatek podgladu
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PreviewQuality {
+    Draft,
+    #[default]
+    Standard,
+    Full,
+}
+
+impl PreviewQuality {
+    pub const ALL: [PreviewQuality; 3] = [PreviewQuality::Draft, PreviewQuality::Standard, PreviewQuality::Full];
+
+    /// Szerokosc klatki w px; Full = szerokosc zrodla
+    pub fn width(self, video_width: u32) -> u32 {
+        match self {
+            PreviewQuality::Draft => 320,
+            PreviewQuality::Standard => 640,
+            PreviewQuality::Full => if video_width > 0 { video_width } else { 1920 },
+        }
+    }
+}
+
+impl std::fmt::Display for PreviewQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewQuality::Draft => write!(f, "Draft"),
+            PreviewQuality::Standard => write!(f, "Standard"),
+            PreviewQuality::Full => write!(f, "Full"),
+        }
+    }
+}
+
+/// Skalowanie obrazu w panelu podglądu: Fit = cały kadr (pasy), Fill = wypełnienie z przycięciem,
+/// Native = piksel 1:1 (przesuwanie przeciąganiem), Stretch = bez zachowania proporcji
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PreviewFit {
+    #[default]
+    Fit,
+    Fill,
+    Native,
+    Stretch,
+}
+
+impl PreviewFit {
+    /// Następny tryb dla przycisku przełączającego
+    pub fn next(self) -> Self {
+        match self {
+            PreviewFit::Fit => PreviewFit::Fill,
+            PreviewFit::Fill => PreviewFit::Native,
+            PreviewFit::Native => PreviewFit::Stretch,
+            PreviewFit::Stretch => PreviewFit::Fit,
+        }
+    }
+}
+
+impl std::fmt::Display for PreviewFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewFit::Fit => write!(f, "Fit"),
+            PreviewFit::Fill => write!(f, "Fill"),
+            PreviewFit::Native => write!(f, "1:1"),
+            PreviewFit::Stretch => write!(f, "Stretch"),
+        }
+    }
+}
+
+/// Skalowanie amplitudy waveformu: Auto = wg szczytu nagrania (astats), Normalized = skala
+/// pierwiastkowa (cbrt, widać ciche audio), Dbfs(próg) = wzmocnienie tak, by próg był pełną skalą
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum WaveformAmplitude {
+    #[default]
+    Auto,
+    Normalized,
+    Dbfs(f32),
+}
+
+impl WaveformAmplitude {
+    /// Domyślny próg dla trybu dBFS
+    pub const DEFAULT_DBFS: f32 = -24.0;
+}
+
+impl std::fmt::Display for WaveformAmplitude {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaveformAmplitude::Auto => write!(f, "Auto"),
+            WaveformAmplitude::Normalized => write!(f, "Normalized"),
+            WaveformAmplitude::Dbfs(threshold) => write!(f, "{threshold:.0} dBFS"),
+        }
+    }
+}
+
+/// Motyw interfejsu; System = motyw systemu (ciemny, gdy nieznany)
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::System];
+
+    /// Bazowe visuals egui dla motywu (`system_dark` = wykryty motyw systemu)
+    pub fn visuals(self, system_dark: Option<bool>) -> egui::Visuals {
+        let dark = match self {
+            Theme::Dark => true,
+            Theme::Light => false,
+            Theme::System => system_dark.unwrap_or(true),
+        };
+        if dark { egui::Visuals::dark() } else { egui::Visuals::light() }
+    }
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::Dark => write!(f, "Dark"),
+            Theme::Light => write!(f, "Light"),
+            Theme::System => write!(f, "System"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Tool {
     Hand,
     Scissors,
+    Envelope,
 }
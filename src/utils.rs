@@ -1,7 +1,14 @@
 // utils.rs - Funkcje pomocnicze
 use anyhow::{Context, Result};
 use eframe::egui;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::ffmpeg::get_video_info_ffprobe;
+use crate::migration::{migrate_to_current, project_version, CURRENT_PROJECT_VERSION};
+use crate::types::{MediaAsset, MediaType, ProjectData, Settings};
 
 /// Ładuje teksturę z pliku
 pub fn load_texture_from_path(
@@ -19,13 +26,32 @@ pub fn load_texture_from_memory(
     data: &[u8],
     name: &str,
 ) -> Result<egui::TextureHandle> {
+    Ok(ctx.load_texture(name, decode_color_image(data)?, egui::TextureOptions::LINEAR))
+}
+
+/// Dekoduje obraz (PNG itp.) do ColorImage bez wysyłania na GPU
+pub fn decode_color_image(data: &[u8]) -> Result<egui::ColorImage> {
     let image = image::load_from_memory(data)
         .context("Nie mozna zdekodowac obrazu")?
         .to_rgba8();
     let size = [image.width() as usize, image.height() as usize];
-    let pixels = image.into_raw();
-    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
-    Ok(ctx.load_texture(name, color_image, egui::TextureOptions::LINEAR))
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, &image.into_raw()))
+}
+
+/// Histogram klatki: 256 przedziałów dla R, G, B i luma (Rec.709)
+pub type Histogram = [[u32; 256]; 4];
+
+pub fn compute_histogram(image: &egui::ColorImage) -> Box<Histogram> {
+    let mut hist = Box::new([[0u32; 256]; 4]);
+    for px in &image.pixels {
+        let [r, g, b, _] = px.to_array();
+        let luma = (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as usize;
+        hist[0][r as usize] += 1;
+        hist[1][g as usize] += 1;
+        hist[2][b as usize] += 1;
+        hist[3][luma.min(255)] += 1;
+    }
+    hist
 }
 
 /// Oblicza skalowany rozmiar podglądu
@@ -48,7 +74,555 @@ pub fn clamp_offset(offset: f32, duration: f32, window: f32) -> f32 {
     }
 }
 
-/// Snap time do siatki (obecnie brak snappingu)
-pub fn snap_time(time: f32, _zoom: f32) -> f32 {
-    time
+/// Zaokrągla czas do najbliższej granicy klatki (fps <= 0 = bez zmian)
+pub fn snap_to_frame(time: f32, fps: f32) -> f32 {
+    if fps <= 0.0 {
+        return time;
+    }
+    (time * fps).round() / fps
+}
+
+/// Rozwija tokeny szablonu wyjścia: {date} (YYYYMMDD), {time} (HHMMSS),
+/// {project} (nazwa projektu) oraz {seq} / {seq:03} (kolejny wolny numer w katalogu)
+pub fn expand_output_template(template: &str, project_name: &str) -> String {
+    let now = chrono::Local::now();
+    let expanded = template
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{project}", project_name);
+
+    let Some(start) = expanded.find("{seq") else {
+        return expanded;
+    };
+    let Some(len) = expanded[start..].find('}') else {
+        return expanded;
+    };
+    let token = &expanded[start + 4..start + len];
+    let width = token.trim_start_matches(':').parse::<usize>().unwrap_or(1);
+    let prefix = &expanded[..start];
+    let suffix = &expanded[start + len + 1..];
+
+    // Skanujemy katalog wyjściowy w poszukiwaniu najwyższego użytego numeru
+    let prefix_path = Path::new(prefix);
+    let (dir, name_prefix) = if prefix.ends_with('/') || prefix.ends_with('\\') {
+        (prefix_path.to_path_buf(), String::new())
+    } else {
+        (
+            prefix_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            prefix_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        )
+    };
+    let scan_dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir.as_path() };
+    let mut next = 1u32;
+    if let Ok(entries) = std::fs::read_dir(scan_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(middle) = name
+                .strip_prefix(name_prefix.as_str())
+                .and_then(|rest| rest.strip_suffix(suffix))
+                && let Ok(n) = middle.parse::<u32>()
+            {
+                next = next.max(n + 1);
+            }
+        }
+    }
+    format!("{prefix}{next:0width$}{suffix}")
+}
+
+/// Dokleja _001, _002, ... do nazwy pliku, aż znajdzie nieistniejącą ścieżkę
+pub fn auto_rename_output(path: &str) -> PathBuf {
+    let original = Path::new(path);
+    let stem = original.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = original.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1u32..)
+        .map(|n| original.with_file_name(format!("{stem}_{n:03}{ext}")))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| original.to_path_buf())
+}
+
+/// Czy ścieżka wyjściowa wskazuje katalog (kończy się separatorem albo istnieje jako katalog)
+pub fn is_output_dir(path: &str) -> bool {
+    !path.is_empty() && (path.ends_with('/') || path.ends_with('\\') || Path::new(path).is_dir())
+}
+
+/// Nazwa pliku w katalogu wyjściowym: {nazwa_źródła}_{data_czas}.{ext}; zajęta dostaje _001, _002...
+pub fn auto_output_path(input_path: &str, output_dir: &Path, ext: &str) -> PathBuf {
+    let base = Path::new(input_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "output".to_string());
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let path = output_dir.join(format!("{base}_{stamp}.{ext}"));
+    if path.exists() {
+        auto_rename_output(&path.to_string_lossy())
+    } else {
+        path
+    }
+}
+
+/// Ścieżka pliku ustawień (~/.config/rustycut/settings.json)
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rustycut").join("settings.json"))
+}
+
+/// Wczytuje ustawienia (brak pliku = domyślne)
+pub fn load_settings() -> Settings {
+    settings_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Zapisuje ustawienia na dysk
+pub fn save_settings(settings: &Settings) -> Result<()> {
+    let path = settings_path().context("Brak katalogu konfiguracji")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+/// Postęp kopiowania (skopiowane bajty, rozmiar pliku)
+pub type CopyProgress = Arc<Mutex<(u64, u64)>>;
+
+/// Katalog lokalnych kopii plików źródłowych (~/.cache/rustycut/sources)
+pub fn source_cache_dir() -> PathBuf {
+    dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("rustycut").join("sources")
+}
+
+/// Katalog na media generowane do projektu (np. czarne wypełnienia luk) - muszą przeżyć sesję.
+/// Obok zapisanego projektu ({nazwa}_media), dla niezapisanego w ~/.cache/rustycut/generated.
+pub fn generated_media_dir(project_path: Option<&Path>) -> PathBuf {
+    match project_path.and_then(|p| Some((p.parent()?, p.file_stem()?))) {
+        Some((dir, stem)) => dir.join(format!("{}_media", stem.to_string_lossy())),
+        None => dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("rustycut").join("generated"),
+    }
+}
+
+/// Lokalna kopia pliku źródłowego (np. z dysku sieciowego) w `cache_dir`. Klucz kopii to hash pełnej
+/// ścieżki, rozmiaru i czasu modyfikacji - pliki o tej samej nazwie z różnych katalogów się nie mieszają,
+/// a zmieniony plik dostaje nową kopię; istniejąca jest używana od razu.
+pub fn cache_source_file(path: &str, cache_dir: &Path, progress: Option<&CopyProgress>) -> Result<PathBuf> {
+    let meta = std::fs::metadata(path).with_context(|| format!("Brak pliku zrodlowego {path}"))?;
+    use std::hash::{Hash, Hasher};
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    let mut hasher = std::hash::DefaultHasher::new();
+    (path, meta.len(), mtime).hash(&mut hasher);
+    let source = Path::new(path);
+    let stem = source.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "source".into());
+    let ext = source.extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_default();
+    let cached = cache_dir.join(format!("{stem}_{:016x}.{ext}", hasher.finish()));
+    if cached.metadata().is_ok_and(|m| m.len() == meta.len()) {
+        return Ok(cached);
+    }
+
+    std::fs::create_dir_all(cache_dir).with_context(|| format!("Nie mozna utworzyc katalogu {}", cache_dir.display()))?;
+    // Kopia do pliku .part - przerwane kopiowanie nie zostawia niepełnej "gotowej" kopii
+    let part = cached.with_extension(format!("{ext}.part"));
+    let mut reader = std::fs::File::open(source)?;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&part)?);
+    let mut buf = vec![0u8; 1 << 20];
+    let mut copied = 0u64;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        copied += n as u64;
+        if let Some(progress) = progress
+            && let Ok(mut p) = progress.lock()
+        {
+            *p = (copied, meta.len());
+        }
+    }
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(&part, &cached).with_context(|| format!("Nie mozna zapisac kopii {}", cached.display()))?;
+    Ok(cached)
+}
+
+/// Dyskowy cache miniatur (~/.cache/rustycut/thumbs/{hash}_{czas w ms}.png) - przeżywa restart aplikacji
+pub struct ThumbnailCache {
+    pub dir: PathBuf,
+}
+
+impl Default for ThumbnailCache {
+    fn default() -> Self {
+        Self { dir: dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("rustycut").join("thumbs") }
+    }
+}
+
+impl ThumbnailCache {
+    /// Klucz źródła: ścieżka, czas modyfikacji i rozmiar miniatury. Zmodyfikowany plik dostaje
+    /// nowy klucz, więc stare miniatury przestają pasować i są generowane od nowa.
+    pub fn source_hash(path: &str, width: u32, height: i32) -> Result<u64> {
+        use std::hash::{Hash, Hasher};
+        let mtime = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("Brak pliku zrodlowego {path}"))?
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut hasher = std::hash::DefaultHasher::new();
+        (path, mtime.as_nanos(), width, height).hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    fn path_for(&self, hash: u64, time_ms: u32) -> PathBuf {
+        self.dir.join(format!("{hash:016x}_{time_ms}.png"))
+    }
+
+    /// Ścieżka zapisanej miniatury, jeśli istnieje
+    pub fn get(&self, hash: u64, time_ms: u32) -> Option<PathBuf> {
+        let path = self.path_for(hash, time_ms);
+        path.is_file().then_some(path)
+    }
+
+    /// Zapisuje miniaturę (PNG); zapis przez plik tymczasowy, żeby przerwany zapis nie dał uszkodzonego PNG
+    pub fn put(&self, hash: u64, time_ms: u32, data: &[u8]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Nie mozna utworzyc katalogu {}", self.dir.display()))?;
+        let path = self.path_for(hash, time_ms);
+        let part = path.with_extension("png.part");
+        std::fs::write(&part, data).context("Nie mozna zapisac miniatury")?;
+        std::fs::rename(&part, &path).context("Nie mozna zapisac miniatury")?;
+        Ok(())
+    }
+}
+
+/// Zwraca ścieżki brakujących plików źródłowych projektu
+pub fn check_missing_media(data: &ProjectData, library: &[MediaAsset]) -> Vec<String> {
+    let mut missing = Vec::new();
+    if !data.input_path.is_empty() && !Path::new(&data.input_path).exists() {
+        missing.push(data.input_path.clone());
+    }
+    for asset in library {
+        if !Path::new(&asset.path).exists() && !missing.contains(&asset.path) {
+            missing.push(asset.path.clone());
+        }
+    }
+    missing
+}
+
+/// Przekierowuje referencję do przeniesionego pliku (input_path lub MediaAsset.path)
+pub fn relocate_media(data: &mut ProjectData, library: &mut [MediaAsset], old_path: &str, new_path: &str) {
+    if data.input_path == old_path {
+        data.input_path = new_path.to_string();
+    }
+    for asset in library.iter_mut().filter(|a| a.path == old_path) {
+        asset.path = new_path.to_string();
+    }
+}
+
+/// Szuka plików o nazwach z `filenames` w drzewach katalogów `roots` (bez ukrytych katalogów
+/// i dowiązań, do 12 poziomów w głąb) w jednym przejściu; zwraca pierwsze trafienie dla każdej
+/// nazwy. Kończy wcześniej, gdy wszystkie nazwy zostały znalezione.
+pub fn find_files_recursive(roots: &[PathBuf], filenames: &HashSet<String>) -> HashMap<String, PathBuf> {
+    const MAX_DEPTH: usize = 12;
+    let mut found = HashMap::new();
+    for root in roots {
+        let mut stack = vec![(root.clone(), 0)];
+        while let Some((current, depth)) = stack.pop() {
+            if found.len() == filenames.len() {
+                return found;
+            }
+            let Ok(entries) = std::fs::read_dir(&current) else { continue };
+            let mut subdirs = Vec::new();
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else { continue };
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if file_type.is_file() && filenames.contains(&name) && !found.contains_key(&name) {
+                    found.insert(name, entry.path());
+                } else if file_type.is_dir() && depth < MAX_DEPTH && !name.starts_with('.') {
+                    subdirs.push(entry.path());
+                }
+            }
+            // Najpierw pliki z bieżącego katalogu, potem podkatalogi w kolejności nazw
+            subdirs.sort();
+            stack.extend(subdirs.into_iter().rev().map(|path| (path, depth + 1)));
+        }
+    }
+    found
+}
+
+/// Rozszerzenia plików wideo rozpoznawane przy imporcie katalogu
+const VIDEO_EXTENSIONS: [&str; 4] = ["mp4", "mov", "avi", "mkv"];
+
+/// Skanuje katalog (bez podkatalogów) i buduje assety wideo posortowane wg nazwy pliku.
+/// Pliki, których ffprobe nie potrafi odczytać, są pomijane.
+pub fn import_folder(dir: &Path) -> Result<Vec<MediaAsset>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Nie mozna odczytac katalogu {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| VIDEO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        })
+        .collect();
+    paths.sort_by_key(|p| p.file_name().map(|n| n.to_ascii_lowercase()));
+    let assets = paths
+        .iter()
+        .filter_map(|path| {
+            let path_str = path.display().to_string();
+            let (duration, ..) = get_video_info_ffprobe(&path_str).ok()?;
+            Some((path_str, path.file_name()?.to_string_lossy().into_owned(), duration))
+        })
+        .enumerate()
+        .map(|(id, (path, name, duration))| MediaAsset {
+            id,
+            path,
+            name,
+            kind: MediaType::Video,
+            duration: if duration < 0.1 { 10.0 } else { duration },
+        })
+        .collect();
+    Ok(assets)
+}
+
+/// Zamienia względne ścieżki mediów (np. z archiwum projektu) na bezwzględne względem katalogu projektu
+pub fn resolve_relative_media(data: &mut ProjectData, project_dir: &Path) {
+    let resolve = |path: &mut String| {
+        if !path.is_empty() && Path::new(path.as_str()).is_relative() {
+            *path = project_dir.join(path.as_str()).to_string_lossy().into_owned();
+        }
+    };
+    resolve(&mut data.input_path);
+    for asset in &mut data.media_library {
+        resolve(&mut asset.path);
+    }
+    for path in data.clips.iter_mut().filter_map(|c| c.audio_override.as_mut()) {
+        resolve(path);
+    }
+    if let Some(watermark) = &mut data.watermark
+        && watermark.path.is_relative()
+        && !watermark.path.as_os_str().is_empty()
+    {
+        watermark.path = project_dir.join(&watermark.path);
+    }
+}
+
+/// Błąd wczytywania: projekt zapisany nowszą wersją programu (UI pokazuje własny komunikat)
+#[derive(Debug)]
+pub struct ProjectTooNew;
+
+impl std::fmt::Display for ProjectTooNew {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Projekt z nowszej wersji programu")
+    }
+}
+
+impl std::error::Error for ProjectTooNew {}
+
+/// Wczytuje plik projektu (.rev): migracja do bieżącej wersji i bezwzględne ścieżki mediów
+pub fn load_project_file(path: &Path) -> Result<ProjectData> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Nie mozna odczytac projektu {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&content).context("Blad parsowania projektu")?;
+    if project_version(&value) > CURRENT_PROJECT_VERSION {
+        return Err(anyhow::Error::new(ProjectTooNew).context(path.display().to_string()));
+    }
+    let mut data = migrate_to_current(value)?;
+    if let Some(dir) = path.parent() {
+        resolve_relative_media(&mut data, dir);
+    }
+    Ok(data)
+}
+
+/// Usuwa najstarsze pliki (wg czasu modyfikacji) z katalogów, aż suma rozmiarów wszystkich zmieści się
+/// we wspólnym limicie. Nieistniejące katalogi są pomijane. Zwraca liczbę zwolnionych bajtów.
+pub fn evict_cache_to_limit(dirs: &[&Path], max_bytes: u64) -> Result<u64> {
+    let mut files = Vec::new();
+    for dir in dirs.iter().filter(|d| d.is_dir()) {
+        for entry in std::fs::read_dir(dir).with_context(|| format!("Nie mozna odczytac katalogu {}", dir.display()))? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            if meta.is_file() {
+                let modified = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                files.push((modified, meta.len(), entry.path()));
+            }
+        }
+    }
+    let mut total: u64 = files.iter().map(|f| f.1).sum();
+    files.sort_by_key(|f| f.0);
+    let mut freed = 0;
+    for (_, size, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+            freed += size;
+        }
+    }
+    Ok(freed)
+}
+
+/// Formatuje czas pozostały jako MM:SS lub H:MM:SS
+pub fn format_eta(secs: f64) -> String {
+    let total = secs.max(0.0).round() as u64;
+    let (h, m, s) = (total / 3600, (total / 60) % 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// Czy klatkaż NTSC (29.97 / 59.94) - timecode w trybie drop-frame
+pub fn is_drop_frame_rate(fps: f32) -> bool {
+    (fps - 29.97).abs() < 0.01 || (fps - 59.94).abs() < 0.01
+}
+
+/// Parametry drop-frame: (nominalna liczba klatek/s, klatki pomijane co minutę)
+fn drop_frame_params(fps: f32) -> (u64, u64) {
+    let nominal = fps.round().max(1.0) as u64;
+    (nominal, nominal / 15)
+}
+
+/// Formatuje czas jako timecode HH:MM:SS:FF; przy `drop_frame` (SMPTE DF) HH:MM:SS;FF -
+/// numery klatek 00 i 01 (04 przy 59.94) są pomijane na początku każdej minuty poza co 10.
+pub fn secs_to_timecode(secs: f32, fps: f32, drop_frame: bool) -> String {
+    let fps = fps.max(1.0);
+    let mut total_frames = (secs.max(0.0) * fps).round() as u64;
+    let fps_int = fps.round().max(1.0) as u64;
+    let separator = if drop_frame { ';' } else { ':' };
+    if drop_frame {
+        let (nominal, drop) = drop_frame_params(fps);
+        let per_10_min = nominal * 600 - drop * 9;
+        let per_min = nominal * 60 - drop;
+        let (tens, rest) = (total_frames / per_10_min, total_frames % per_10_min);
+        total_frames += drop * 9 * tens + if rest > drop { drop * ((rest - drop) / per_min) } else { 0 };
+    }
+    let frames = total_frames % fps_int;
+    let total_secs = total_frames / fps_int;
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:02}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60,
+        frames
+    )
+}
+
+/// Parsuje timecode HH:MM:SS:FF lub HH:MM:SS;FF (akceptuje też HH:MM:SS i MM:SS)
+pub fn timecode_to_secs(tc: &str, fps: f32, drop_frame: bool) -> Option<f32> {
+    let parts: Vec<f32> = tc
+        .trim()
+        .split([':', ';'])
+        .map(|p| p.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let nominal = fps.max(1.0).round();
+    let secs = match parts.as_slice() {
+        [m, s] => m * 60.0 + s,
+        [h, m, s] => h * 3600.0 + m * 60.0 + s,
+        [h, m, s, f] if *f < nominal && drop_frame => {
+            // Numer klatki DF -> liczba klatek -> sekundy przy rzeczywistym fps
+            let (_, drop) = drop_frame_params(fps);
+            let minutes = h * 60.0 + m;
+            let dropped = drop as f32 * (minutes - (minutes / 10.0).floor());
+            ((h * 3600.0 + m * 60.0 + s) * nominal + f - dropped) / fps.max(1.0)
+        }
+        [h, m, s, f] if *f < nominal => h * 3600.0 + m * 60.0 + s + f / nominal,
+        _ => return None,
+    };
+    (secs >= 0.0).then_some(secs)
+}
+
+/// Długość ramki obwiedni energii przy wykrywaniu uderzeń (s)
+const BEAT_FRAME_SECS: f32 = 0.01;
+/// Zakres tempa szukany przez autokorelację (BPM)
+const BEAT_BPM_RANGE: (f32, f32) = (70.0, 180.0);
+
+/// Obwiednia onsetów: dodatnie przyrosty RMS między sąsiednimi ramkami
+fn onset_envelope(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let hop = ((sample_rate as f32 * BEAT_FRAME_SECS) as usize).max(1);
+    let rms: Vec<f32> = samples
+        .chunks(hop)
+        .map(|c| (c.iter().map(|s| s * s).sum::<f32>() / c.len() as f32).sqrt())
+        .collect();
+    let mut flux = vec![0.0; rms.len()];
+    for i in 1..rms.len() {
+        flux[i] = (rms[i] - rms[i - 1]).max(0.0);
+    }
+    flux
+}
+
+/// Tempo z maksimum autokorelacji obwiedni w zakresie `BEAT_BPM_RANGE`
+fn envelope_tempo(flux: &[f32]) -> Option<f32> {
+    let mean = flux.iter().sum::<f32>() / flux.len().max(1) as f32;
+    let centered: Vec<f32> = flux.iter().map(|v| v - mean).collect();
+    let lag_for = |bpm: f32| (60.0 / bpm / BEAT_FRAME_SECS).round() as usize;
+    let (min_lag, max_lag) = (lag_for(BEAT_BPM_RANGE.1), lag_for(BEAT_BPM_RANGE.0));
+    if centered.len() <= max_lag * 2 {
+        return None;
+    }
+    let (best_lag, best_score) = (min_lag..=max_lag)
+        .map(|lag| (lag, centered.iter().zip(&centered[lag..]).map(|(a, b)| a * b).sum::<f32>()))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    (best_score > 0.0).then(|| 60.0 / (best_lag as f32 * BEAT_FRAME_SECS))
+}
+
+/// Szacuje tempo (BPM) nagrania; None gdy brak wyraźnego rytmu
+pub fn estimate_bpm(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    envelope_tempo(&onset_envelope(samples, sample_rate))
+}
+
+/// Wykrywa uderzenia (s od początku próbek) z różnic energii sąsiednich ramek.
+/// Przy znanym tempie (`bpm_hint` lub oszacowanym) zwraca równą siatkę z fazą dopasowaną
+/// do onsetów; bez tempa - pojedyncze wyraźne onsety.
+pub fn detect_beats(samples: &[f32], sample_rate: u32, bpm_hint: Option<f32>) -> Vec<f32> {
+    let flux = onset_envelope(samples, sample_rate);
+    if flux.len() < 2 {
+        return Vec::new();
+    }
+    let Some(bpm) = bpm_hint.filter(|b| *b > 0.0).or_else(|| envelope_tempo(&flux)) else {
+        return pick_onsets(&flux);
+    };
+    let period = 60.0 / bpm / BEAT_FRAME_SECS;
+    let grid = |phase: f32| (0..).map(move |k| phase + k as f32 * period);
+    let score = |phase: f32| -> f32 {
+        grid(phase)
+            .map(|pos| pos.round() as usize)
+            .take_while(|&i| i < flux.len())
+            .map(|i| flux[i])
+            .sum()
+    };
+    let phase = (0..period.ceil() as usize)
+        .map(|p| p as f32)
+        .max_by(|a, b| score(*a).total_cmp(&score(*b)))
+        .unwrap_or(0.0);
+    grid(phase)
+        .take_while(|pos| (pos.round() as usize) < flux.len())
+        .map(|pos| pos * BEAT_FRAME_SECS)
+        .collect()
+}
+
+/// Lokalne maksima obwiedni powyżej średnia + odchylenie, co najmniej 100 ms od siebie
+fn pick_onsets(flux: &[f32]) -> Vec<f32> {
+    let n = flux.len() as f32;
+    let mean = flux.iter().sum::<f32>() / n;
+    let std = (flux.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n).sqrt();
+    let threshold = mean + std;
+    let min_gap = (0.1 / BEAT_FRAME_SECS) as usize;
+    let mut onsets = Vec::new();
+    let mut last: Option<usize> = None;
+    for i in 1..flux.len() - 1 {
+        let is_peak = flux[i] > threshold && flux[i] >= flux[i - 1] && flux[i] > flux[i + 1];
+        if is_peak && last.is_none_or(|l| i - l >= min_gap) {
+            onsets.push(i as f32 * BEAT_FRAME_SECS);
+            last = Some(i);
+        }
+    }
+    onsets
 }